@@ -5,6 +5,7 @@
 //! actual query building is in the [visitor](../visitor/index.html) module.
 //!
 //! For prelude, all important imports are in `prisma_query::ast::*`.
+mod case;
 mod column;
 mod compare;
 mod conditions;
@@ -21,11 +22,14 @@ mod over;
 mod query;
 mod row;
 mod select;
+#[cfg(feature = "serde-1")]
+mod serialization;
 mod table;
 mod union_all;
 mod update;
 mod values;
 
+pub use case::{case, Case};
 pub use column::Column;
 pub use compare::{Comparable, Compare};
 pub use conditions::ConditionTree;
@@ -45,5 +49,5 @@ pub use select::Select;
 pub use table::*;
 pub use union_all::UnionAll;
 pub use update::*;
-pub(crate) use values::Params;
 pub use values::{asterisk, DatabaseValue, ParameterizedValue};
+pub(crate) use values::{hex, Params};
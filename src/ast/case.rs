@@ -0,0 +1,77 @@
+use crate::ast::DatabaseValue;
+
+/// A `CASE` expression, comparing the operand to the value of each branch and
+/// evaluating to the result of the first match, or to the `ELSE` value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case<'a> {
+    pub(crate) operand: Box<DatabaseValue<'a>>,
+    pub(crate) branches: Vec<(DatabaseValue<'a>, DatabaseValue<'a>)>,
+    pub(crate) otherwise: Option<Box<DatabaseValue<'a>>>,
+}
+
+impl<'a> Case<'a> {
+    /// Adds a `WHEN value THEN result` branch.
+    pub fn when<V, R>(mut self, value: V, result: R) -> Self
+    where
+        V: Into<DatabaseValue<'a>>,
+        R: Into<DatabaseValue<'a>>,
+    {
+        self.branches.push((value.into(), result.into()));
+        self
+    }
+
+    /// The value if no branch matches, `NULL` if not set.
+    pub fn otherwise<T>(mut self, value: T) -> Self
+    where
+        T: Into<DatabaseValue<'a>>,
+    {
+        self.otherwise = Some(Box::new(value.into()));
+        self
+    }
+}
+
+impl<'a> From<Case<'a>> for DatabaseValue<'a> {
+    #[inline]
+    fn from(case: Case<'a>) -> Self {
+        DatabaseValue::Case(Box::new(case))
+    }
+}
+
+/// A `CASE` expression on the given operand.
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+/// let name = case(Column::from("id"))
+///     .when(1, "musti")
+///     .when(2, "naukio")
+///     .otherwise(Column::from("name"));
+///
+/// let query = Update::table("cats").set("name", name);
+/// let (sql, params) = Sqlite::build(query);
+///
+/// assert_eq!(
+///     "UPDATE `cats` SET `name` = CASE `id` WHEN ? THEN ? WHEN ? THEN ? ELSE `name` END",
+///     sql
+/// );
+///
+/// assert_eq!(
+///     vec![
+///         ParameterizedValue::Integer(1),
+///         ParameterizedValue::from("musti"),
+///         ParameterizedValue::Integer(2),
+///         ParameterizedValue::from("naukio"),
+///     ],
+///     params
+/// );
+/// ```
+#[inline]
+pub fn case<'a, T>(operand: T) -> Case<'a>
+where
+    T: Into<DatabaseValue<'a>>,
+{
+    Case {
+        operand: Box::new(operand.into()),
+        branches: Vec::new(),
+        otherwise: None,
+    }
+}
@@ -66,7 +66,8 @@ impl<'a> From<Compare<'a>> for Expression<'a> {
 
 /// An item that can be compared against other values in the database.
 pub trait Comparable<'a> {
-    /// Tests if both sides are the same value.
+    /// Tests if both sides are the same value. Comparing to a `NULL`, such as
+    /// `None`, is rendered as `IS NULL`.
     ///
     /// ```rust
     /// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
@@ -86,7 +87,8 @@ pub trait Comparable<'a> {
     where
         T: Into<DatabaseValue<'a>>;
 
-    /// Tests if both sides are not the same value.
+    /// Tests if both sides are not the same value. Comparing to a `NULL`, such
+    /// as `None`, is rendered as `IS NOT NULL`.
     ///
     /// ```rust
     /// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
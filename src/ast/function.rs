@@ -1,9 +1,11 @@
 mod aggregate_to_string;
 mod count;
+mod random;
 mod row_number;
 
 pub use aggregate_to_string::*;
 pub use count::*;
+pub use random::*;
 pub use row_number::*;
 
 use super::DatabaseValue;
@@ -22,6 +24,7 @@ pub(crate) enum FunctionType<'a> {
     RowNumber(RowNumber<'a>),
     Count(Count<'a>),
     AggregateToString(AggregateToString<'a>),
+    Random,
 }
 
 impl<'a> Function<'a> {
@@ -0,0 +1,43 @@
+use super::{Function, FunctionType};
+use crate::ast::{DatabaseValue, IntoOrderDefinition, OrderDefinition};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Random;
+
+/// A random number, rendered as `RANDOM()` or as `RAND()` on MySQL. Ordering
+/// by it shuffles the rows.
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+/// let query = Select::from_table("users").order_by(random()).limit(3);
+/// let (sql, _) = Sqlite::build(query);
+/// assert_eq!("SELECT `users`.* FROM `users` ORDER BY RANDOM() LIMIT ?", sql);
+/// ```
+#[inline]
+pub fn random() -> Random {
+    Random
+}
+
+impl<'a> From<Random> for Function<'a> {
+    #[inline]
+    fn from(_: Random) -> Self {
+        Function {
+            typ_: FunctionType::Random,
+            alias: None,
+        }
+    }
+}
+
+impl<'a> From<Random> for DatabaseValue<'a> {
+    #[inline]
+    fn from(f: Random) -> Self {
+        Function::from(f).into()
+    }
+}
+
+impl<'a> IntoOrderDefinition<'a> for Random {
+    #[inline]
+    fn into_order_definition(self) -> OrderDefinition<'a> {
+        (self.into(), None)
+    }
+}
@@ -1,23 +1,118 @@
+use crate::{ast::ParameterizedValue, error::Error};
+use std::{convert::TryFrom, fmt};
+
 #[cfg(feature = "uuid-0_7")]
 use uuid::Uuid;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+/// The id of a row, such as the last inserted id.
+///
+/// Integer ids are `i64`, as the keys of the databases, on every target. The
+/// unsigned conversions are checked:
+///
+/// ```rust
+/// # use prisma_query::ast::Id;
+/// # use std::convert::TryFrom;
+/// assert_eq!(Id::Int(-1), Id::from(-1i64));
+/// assert_eq!(Id::Int(1), Id::try_from(1u64).unwrap());
+/// assert!(Id::try_from(u64::max_value()).is_err());
+/// ```
+///
+/// Before, `Id::Int` held a `usize`. The integer is now built and read back
+/// as an `i64`:
+///
+/// ```rust
+/// # use prisma_query::ast::Id;
+/// let id = Id::Int(42i64);
+/// let int: i64 = id.as_int().unwrap();
+///
+/// assert_eq!(42, int);
+/// ```
+///
+/// and a `usize` converts only with a check:
+///
+/// ```compile_fail
+/// # use prisma_query::ast::Id;
+/// let id = Id::from(42usize);
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum Id {
     String(String),
-    Int(usize),
+    Int(i64),
     #[cfg(feature = "uuid-0_7")]
     UUID(Uuid),
 }
 
-impl From<usize> for Id {
-    fn from(u: usize) -> Self {
-        Id::Int(u)
+impl Id {
+    /// Returns a &str if the id is a string, otherwise `None`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Id::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer if the id is an integer, otherwise `None`.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Id::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+/// The string as it is, the integer in decimal and the UUID hyphenated.
+///
+/// ```rust
+/// # use prisma_query::ast::Id;
+/// assert_eq!("musti", Id::from("musti").to_string());
+/// assert_eq!("42", Id::from(42).to_string());
+/// ```
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Id::String(s) => write!(f, "{}", s),
+            Id::Int(i) => write!(f, "{}", i),
+            #[cfg(feature = "uuid-0_7")]
+            Id::UUID(u) => write!(f, "{}", u.to_hyphenated_ref()),
+        }
+    }
+}
+
+impl From<i64> for Id {
+    fn from(i: i64) -> Self {
+        Id::Int(i)
     }
 }
 
-impl From<u64> for Id {
-    fn from(u: u64) -> Self {
-        Id::Int(u as usize)
+impl From<i32> for Id {
+    fn from(i: i32) -> Self {
+        Id::Int(i64::from(i))
+    }
+}
+
+impl From<u32> for Id {
+    fn from(u: u32) -> Self {
+        Id::Int(i64::from(u))
+    }
+}
+
+impl TryFrom<u64> for Id {
+    type Error = Error;
+
+    fn try_from(u: u64) -> Result<Self, Self::Error> {
+        i64::try_from(u)
+            .map(Id::Int)
+            .map_err(|_| Error::ConversionError("Integer id overflow"))
+    }
+}
+
+impl TryFrom<usize> for Id {
+    type Error = Error;
+
+    fn try_from(u: usize) -> Result<Self, Self::Error> {
+        i64::try_from(u)
+            .map(Id::Int)
+            .map_err(|_| Error::ConversionError("Integer id overflow"))
     }
 }
 
@@ -27,9 +122,39 @@ impl From<String> for Id {
     }
 }
 
+impl<'a> From<&'a str> for Id {
+    fn from(s: &'a str) -> Self {
+        Id::String(s.into())
+    }
+}
+
 #[cfg(feature = "uuid-0_7")]
 impl From<Uuid> for Id {
     fn from(u: Uuid) -> Self {
         Id::UUID(u)
     }
 }
+
+/// An integer, text or a UUID, such as the value returned by a `RETURNING`
+/// query, is an id.
+///
+/// ```rust
+/// # use prisma_query::ast::*;
+/// # use std::convert::TryFrom;
+/// assert_eq!(Id::Int(1), Id::try_from(ParameterizedValue::Integer(1)).unwrap());
+/// assert_eq!(Id::from("musti"), Id::try_from(ParameterizedValue::from("musti")).unwrap());
+/// assert!(Id::try_from(ParameterizedValue::Null).is_err());
+/// ```
+impl<'a> TryFrom<ParameterizedValue<'a>> for Id {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<Self, Self::Error> {
+        match value {
+            ParameterizedValue::Integer(i) => Ok(Id::Int(i)),
+            ParameterizedValue::Text(s) => Ok(Id::String(s.into_owned())),
+            #[cfg(feature = "uuid-0_7")]
+            ParameterizedValue::Uuid(u) => Ok(Id::UUID(u)),
+            _ => Err(Error::ConversionError("Not an id")),
+        }
+    }
+}
@@ -6,8 +6,8 @@ pub struct Insert<'a> {
     pub(crate) table: Table<'a>,
     pub(crate) columns: Vec<Column<'a>>,
     pub(crate) values: Vec<Row<'a>>,
-    pub(crate) on_conflict: Option<OnConflict>,
-    pub(crate) returning: Option<Vec<Column<'a>>>,
+    pub(crate) on_conflict: Option<OnConflict<'a>>,
+    pub(crate) returning: Option<Vec<DatabaseValue<'a>>>,
 }
 
 pub struct SingleRowInsert<'a> {
@@ -22,9 +22,9 @@ pub struct MultiRowInsert<'a> {
     pub(crate) values: Vec<Row<'a>>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 /// `INSERT` conflict resolution strategies.
-pub enum OnConflict {
+pub enum OnConflict<'a> {
     /// When a row already exists, do nothing.
     ///
     /// ```rust
@@ -36,6 +36,64 @@ pub enum OnConflict {
     /// assert_eq!("INSERT OR IGNORE INTO `users` DEFAULT VALUES", sql);
     /// ```
     DoNothing,
+    /// When a row with the same values in the given unique columns already
+    /// exists, update it with the assignments. MySQL ignores the columns,
+    /// updating on a conflict in any unique key. The inserted values can be
+    /// used in the assignments with [excluded](fn.excluded.html).
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Postgres}};
+    /// let insert: Insert = Insert::single_into("users")
+    ///     .value("id", 1)
+    ///     .value("name", "musti")
+    ///     .into();
+    ///
+    /// let conflict = OnConflict::Update(
+    ///     vec![Column::from("id")],
+    ///     vec![(Column::from("name"), excluded("name"))],
+    /// );
+    ///
+    /// let (sql, _) = Postgres::build(insert.on_conflict(conflict));
+    ///
+    /// assert_eq!(
+    ///     "INSERT INTO \"users\" (\"id\", \"name\") VALUES ($1, $2) \
+    ///      ON CONFLICT (\"id\") DO UPDATE SET \"name\" = excluded.\"name\"",
+    ///     sql
+    /// );
+    /// ```
+    Update(Vec<Column<'a>>, Vec<(Column<'a>, DatabaseValue<'a>)>),
+}
+
+/// The value the conflicting `INSERT` tried to write to the column, to be
+/// used in the assignments of
+/// [OnConflict::Update](enum.OnConflict.html#variant.Update).
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Mysql}};
+/// let insert: Insert = Insert::single_into("users")
+///     .value("id", 1)
+///     .value("visits", 1)
+///     .into();
+///
+/// let conflict = OnConflict::Update(
+///     vec![Column::from("id")],
+///     vec![(Column::from("visits"), excluded("visits"))],
+/// );
+///
+/// let (sql, _) = Mysql::build(insert.on_conflict(conflict));
+///
+/// assert_eq!(
+///     "INSERT INTO `users` (`id`, `visits`) VALUES (?, ?) \
+///      ON DUPLICATE KEY UPDATE `visits` = VALUES(`visits`)",
+///     sql
+/// );
+/// ```
+#[inline]
+pub fn excluded<'a, C>(column: C) -> DatabaseValue<'a>
+where
+    C: Into<Column<'a>>,
+{
+    DatabaseValue::Excluded(Box::new(column.into()))
 }
 
 impl<'a> From<Insert<'a>> for Query<'a> {
@@ -112,6 +170,43 @@ impl<'a> Insert<'a> {
         }
     }
 
+    /// Creates a new single row `INSERT` statement for the given table from
+    /// pairs of columns and values, in the order the iterator yields them.
+    /// Without any pairs the statement inserts the `DEFAULT VALUES`.
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+    /// # use std::collections::BTreeMap;
+    /// let mut row = BTreeMap::new();
+    /// row.insert("name", ParameterizedValue::from("musti"));
+    /// row.insert("age", ParameterizedValue::from(9));
+    ///
+    /// let (sql, params) = Sqlite::build(Insert::from_map("users", row));
+    ///
+    /// assert_eq!("INSERT INTO `users` (`age`, `name`) VALUES (?, ?)", sql);
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         ParameterizedValue::Integer(9),
+    ///         ParameterizedValue::from("musti"),
+    ///     ],
+    ///     params,
+    /// );
+    /// ```
+    pub fn from_map<T, I, K, V>(table: T, values: I) -> SingleRowInsert<'a>
+    where
+        T: Into<Table<'a>>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Column<'a>>,
+        V: Into<DatabaseValue<'a>>,
+    {
+        values
+            .into_iter()
+            .fold(Self::single_into(table), |insert, (k, v)| {
+                insert.value(k, v)
+            })
+    }
+
     /// Creates a new multi row `INSERT` statement for the given table.
     #[inline]
     pub fn multi_into<T, K>(table: T, columns: Vec<K>) -> MultiRowInsert<'a>
@@ -128,12 +223,13 @@ impl<'a> Insert<'a> {
 
     /// Sets the conflict resolution strategy.
     #[inline]
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+    pub fn on_conflict(mut self, on_conflict: OnConflict<'a>) -> Self {
         self.on_conflict = Some(on_conflict);
         self
     }
 
-    /// Sets the returned columns. Works only with PostgreSQL.
+    /// Sets the returned columns. Only PostgreSQL supports `RETURNING`, the
+    /// other visitors leave it out.
     ///
     /// ```rust
     /// # use prisma_query::{ast::*, visitor::{Visitor, Postgres}};
@@ -147,7 +243,26 @@ impl<'a> Insert<'a> {
     where
         K: Into<Column<'a>>,
     {
-        self.returning = Some(columns.into_iter().map(|k| k.into()).collect());
+        let columns = columns.into_iter().map(|k| {
+            let column: Column<'a> = k.into();
+            column.into()
+        });
+
+        self.returning = Some(columns.collect());
+        self
+    }
+
+    /// Returns all columns of the inserted rows.
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Postgres}};
+    /// let insert = Insert::from(Insert::single_into("users")).returning_all();
+    /// let (sql, _) = Postgres::build(insert);
+    ///
+    /// assert_eq!("INSERT INTO \"users\" DEFAULT VALUES RETURNING *", sql);
+    /// ```
+    pub fn returning_all(mut self) -> Self {
+        self.returning = Some(vec![asterisk()]);
         self
     }
 }
@@ -202,3 +317,46 @@ impl<'a> MultiRowInsert<'a> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::{Mysql, Postgres, Sqlite, Visitor};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn inserts_from_a_map_keep_the_iteration_order() {
+        let mut row = BTreeMap::new();
+        row.insert("b", ParameterizedValue::from(2));
+        row.insert("c", ParameterizedValue::Null);
+        row.insert("a", ParameterizedValue::from("one"));
+
+        let (sql, params) = Postgres::build(Insert::from_map("t", row.clone()));
+
+        assert_eq!(
+            "INSERT INTO \"t\" (\"a\", \"b\", \"c\") VALUES ($1, $2, $3)",
+            sql
+        );
+
+        assert_eq!(
+            vec![
+                ParameterizedValue::from("one"),
+                ParameterizedValue::Integer(2),
+                ParameterizedValue::Null,
+            ],
+            params
+        );
+
+        let (sql, _) = Mysql::build(Insert::from_map("t", row));
+        assert_eq!("INSERT INTO `t` (`a`, `b`, `c`) VALUES (?, ?, ?)", sql);
+    }
+
+    #[test]
+    fn inserts_from_an_empty_map_use_the_default_values() {
+        let row: BTreeMap<String, ParameterizedValue> = BTreeMap::new();
+        let (sql, params) = Sqlite::build(Insert::from_map("t", row));
+
+        assert_eq!("INSERT INTO `t` DEFAULT VALUES", sql);
+        assert!(params.is_empty());
+    }
+}
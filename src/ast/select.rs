@@ -241,6 +241,37 @@ impl<'a> Select<'a> {
         self
     }
 
+    /// Adds a random ordering to the `ORDER BY` section, shuffling the rows.
+    /// Together with a limit, picks the given number of random rows.
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+    /// let query = Select::from_table("users").order_by("foo").order_by_random();
+    /// let (sql, _) = Sqlite::build(query);
+    ///
+    /// assert_eq!("SELECT `users`.* FROM `users` ORDER BY `foo`, RANDOM()", sql);
+    /// ```
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Postgres}};
+    /// let query = Select::from_table("users").order_by_random().limit(10);
+    /// let (sql, _) = Postgres::build(query);
+    ///
+    /// assert_eq!("SELECT \"users\".* FROM \"users\" ORDER BY RANDOM() LIMIT $1", sql);
+    /// ```
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Mysql}};
+    /// let query = Select::from_table("users").order_by_random().order_by("foo".descend());
+    /// let (sql, _) = Mysql::build(query);
+    ///
+    /// assert_eq!("SELECT `users`.* FROM `users` ORDER BY RAND(), `foo` DESC", sql);
+    /// ```
+    #[inline]
+    pub fn order_by_random(self) -> Self {
+        self.order_by(random())
+    }
+
     /// Adds a grouping to the `GROUP BY` section.
     ///
     /// This does not check if the grouping is actually valid in respect to aggregated columns.
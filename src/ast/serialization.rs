@@ -0,0 +1,352 @@
+//! Serde support for the values, with the `serde-1` feature.
+//!
+//! A `ParameterizedValue` is serialized as its variant in `type` and the
+//! data in `value`, such as `{"type":"integer","value":1}`. Binary data is
+//! encoded as base64, UUIDs as hyphenated text, date-times as RFC 3339 and
+//! decimals as text not to lose precision. Text and binary data are always
+//! deserialized into owned values.
+//!
+//! An `Id` is a bare number or string. A UUID id is tagged to tell it apart
+//! from a string, such as `{"uuid":"550e8400-e29b-41d4-a716-446655440000"}`.
+use crate::ast::{Id, ParameterizedValue};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+
+#[cfg(feature = "json-1")]
+use serde_json::Value;
+
+#[cfg(feature = "uuid-0_7")]
+use uuid::Uuid;
+
+#[cfg(feature = "chrono-0_4")]
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+#[cfg(feature = "rust_decimal-1")]
+use rust_decimal::Decimal;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum Tagged<'a> {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(Cow<'a, str>),
+    Boolean(bool),
+    Char(char),
+    Bytes(#[serde(with = "base64_bytes")] Cow<'a, [u8]>),
+    #[cfg(feature = "array")]
+    Array(Cow<'a, [ParameterizedValue<'a>]>),
+    #[cfg(feature = "json-1")]
+    Json(Cow<'a, Value>),
+    #[cfg(feature = "uuid-0_7")]
+    Uuid(#[serde(with = "display_from_str")] Uuid),
+    #[cfg(feature = "chrono-0_4")]
+    DateTime(#[serde(with = "rfc3339")] DateTime<Utc>),
+    #[cfg(feature = "chrono-0_4")]
+    Date(#[serde(with = "display_from_str")] NaiveDate),
+    #[cfg(feature = "chrono-0_4")]
+    Time(#[serde(with = "display_from_str")] NaiveTime),
+    #[cfg(feature = "rust_decimal-1")]
+    Decimal(#[serde(with = "display_from_str")] Decimal),
+}
+
+impl<'a, 'b> From<&'b ParameterizedValue<'a>> for Tagged<'b> {
+    fn from(value: &'b ParameterizedValue<'a>) -> Self {
+        match value {
+            ParameterizedValue::Null => Tagged::Null,
+            ParameterizedValue::Integer(i) => Tagged::Integer(*i),
+            ParameterizedValue::Real(f) => Tagged::Real(*f),
+            ParameterizedValue::Text(s) => Tagged::Text(Cow::Borrowed(&s[..])),
+            ParameterizedValue::Boolean(b) => Tagged::Boolean(*b),
+            ParameterizedValue::Char(c) => Tagged::Char(*c),
+            ParameterizedValue::Bytes(bytes) => Tagged::Bytes(Cow::Borrowed(&bytes[..])),
+            #[cfg(feature = "array")]
+            ParameterizedValue::Array(values) => Tagged::Array(Cow::Borrowed(&values[..])),
+            #[cfg(feature = "json-1")]
+            ParameterizedValue::Json(json) => Tagged::Json(Cow::Borrowed(json)),
+            #[cfg(feature = "uuid-0_7")]
+            ParameterizedValue::Uuid(uuid) => Tagged::Uuid(*uuid),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::DateTime(dt) => Tagged::DateTime(*dt),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(date) => Tagged::Date(*date),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(time) => Tagged::Time(*time),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(d) => Tagged::Decimal(*d),
+        }
+    }
+}
+
+impl<'a> From<Tagged<'a>> for ParameterizedValue<'a> {
+    fn from(value: Tagged<'a>) -> Self {
+        match value {
+            Tagged::Null => ParameterizedValue::Null,
+            Tagged::Integer(i) => ParameterizedValue::Integer(i),
+            Tagged::Real(f) => ParameterizedValue::Real(f),
+            Tagged::Text(s) => ParameterizedValue::Text(Cow::Owned(s.into_owned())),
+            Tagged::Boolean(b) => ParameterizedValue::Boolean(b),
+            Tagged::Char(c) => ParameterizedValue::Char(c),
+            Tagged::Bytes(bytes) => ParameterizedValue::Bytes(Cow::Owned(bytes.into_owned())),
+            #[cfg(feature = "array")]
+            Tagged::Array(values) => ParameterizedValue::Array(values.into_owned()),
+            #[cfg(feature = "json-1")]
+            Tagged::Json(json) => ParameterizedValue::Json(json.into_owned()),
+            #[cfg(feature = "uuid-0_7")]
+            Tagged::Uuid(uuid) => ParameterizedValue::Uuid(uuid),
+            #[cfg(feature = "chrono-0_4")]
+            Tagged::DateTime(dt) => ParameterizedValue::DateTime(dt),
+            #[cfg(feature = "chrono-0_4")]
+            Tagged::Date(date) => ParameterizedValue::Date(date),
+            #[cfg(feature = "chrono-0_4")]
+            Tagged::Time(time) => ParameterizedValue::Time(time),
+            #[cfg(feature = "rust_decimal-1")]
+            Tagged::Decimal(d) => ParameterizedValue::Decimal(d),
+        }
+    }
+}
+
+impl<'a> Serialize for ParameterizedValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Tagged::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for ParameterizedValue<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Tagged::deserialize(deserializer).map(ParameterizedValue::from)
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Id::String(s) => serializer.serialize_str(s),
+            Id::Int(i) => serializer.serialize_i64(*i),
+            #[cfg(feature = "uuid-0_7")]
+            Id::UUID(uuid) => UuidId {
+                uuid: uuid.to_hyphenated().to_string(),
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+/// A UUID id, tagged to tell it apart from a string id.
+#[cfg(feature = "uuid-0_7")]
+#[derive(Serialize, Deserialize)]
+struct UuidId {
+    uuid: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UntaggedId {
+    Int(i64),
+    String(String),
+    #[cfg(feature = "uuid-0_7")]
+    Uuid(UuidId),
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let id = match UntaggedId::deserialize(deserializer)? {
+            UntaggedId::Int(i) => Id::Int(i),
+            UntaggedId::String(s) => Id::String(s),
+            #[cfg(feature = "uuid-0_7")]
+            UntaggedId::Uuid(UuidId { uuid }) => {
+                Id::UUID(Uuid::parse_str(&uuid).map_err(de::Error::custom)?)
+            }
+        };
+
+        Ok(id)
+    }
+}
+
+mod base64_bytes {
+    use super::*;
+
+    pub fn serialize<S>(bytes: &Cow<'_, [u8]>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&base64::encode(bytes))
+    }
+
+    pub fn deserialize<'de, 'a, D>(deserializer: D) -> Result<Cow<'a, [u8]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+
+        base64::decode(&encoded)
+            .map(Cow::Owned)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(any(
+    feature = "uuid-0_7",
+    feature = "chrono-0_4",
+    feature = "rust_decimal-1"
+))]
+mod display_from_str {
+    use super::*;
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "chrono-0_4")]
+mod rfc3339 {
+    use super::*;
+
+    pub fn serialize<S>(dt: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&dt.to_rfc3339())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "json-1"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn round_trip(value: ParameterizedValue) {
+        let json = serde_json::to_string(&value).unwrap();
+        let deserialized: ParameterizedValue<'static> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value, deserialized, "round-tripping {}", json);
+    }
+
+    #[test]
+    fn values_are_tagged_with_their_type() {
+        assert_eq!(
+            json!({"type": "integer", "value": 1}),
+            serde_json::to_value(ParameterizedValue::Integer(1)).unwrap()
+        );
+
+        assert_eq!(
+            json!({"type": "null"}),
+            serde_json::to_value(ParameterizedValue::Null).unwrap()
+        );
+
+        assert_eq!(
+            json!({"type": "bytes", "value": "AJ+Slv8="}),
+            serde_json::to_value(ParameterizedValue::from(&[0u8, 159, 146, 150, 255][..])).unwrap()
+        );
+    }
+
+    #[test]
+    fn all_variants_round_trip() {
+        round_trip(ParameterizedValue::Null);
+        round_trip(ParameterizedValue::Integer(i64::min_value()));
+        round_trip(ParameterizedValue::Integer(i64::max_value()));
+        round_trip(ParameterizedValue::Real(-1.5));
+        round_trip(ParameterizedValue::from("musti"));
+        round_trip(ParameterizedValue::from(""));
+        round_trip(ParameterizedValue::Boolean(false));
+        round_trip(ParameterizedValue::Char('ä'));
+        round_trip(ParameterizedValue::from(vec![0u8, 1, 254, 255]));
+        round_trip(ParameterizedValue::from(Vec::<u8>::new()));
+        round_trip(ParameterizedValue::Json(json!({"cat": ["musti", 1]})));
+
+        #[cfg(feature = "array")]
+        round_trip(ParameterizedValue::Array(vec![
+            ParameterizedValue::Integer(1),
+            ParameterizedValue::Null,
+            ParameterizedValue::from("naukio"),
+        ]));
+
+        #[cfg(feature = "uuid-0_7")]
+        round_trip(ParameterizedValue::Uuid(Uuid::new_v4()));
+
+        #[cfg(feature = "chrono-0_4")]
+        round_trip(ParameterizedValue::DateTime(Utc::now()));
+
+        #[cfg(feature = "chrono-0_4")]
+        round_trip(ParameterizedValue::Date(NaiveDate::from_ymd(1969, 7, 20)));
+
+        #[cfg(feature = "chrono-0_4")]
+        round_trip(ParameterizedValue::Time(NaiveTime::from_hms_micro(
+            20, 17, 40, 5,
+        )));
+
+        #[cfg(feature = "rust_decimal-1")]
+        round_trip(ParameterizedValue::Decimal(
+            "1234567890.123456789012345678".parse().unwrap(),
+        ));
+    }
+
+    #[test]
+    fn ids_are_bare_numbers_and_strings() {
+        let ids = vec![Id::Int(1), Id::String("musti".into())];
+
+        assert_eq!(json!([1, "musti"]), serde_json::to_value(&ids).unwrap());
+
+        let deserialized: Vec<Id> = serde_json::from_value(json!([1, "musti"])).unwrap();
+        assert_eq!(ids, deserialized);
+    }
+
+    #[cfg(feature = "uuid-0_7")]
+    #[test]
+    fn uuid_ids_round_trip() {
+        let uuid = Uuid::new_v4();
+        let id = Id::UUID(uuid);
+        let json = serde_json::to_value(&id).unwrap();
+
+        assert_eq!(json!({ "uuid": uuid.to_hyphenated().to_string() }), json);
+        assert_eq!(id, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn uuid_shaped_string_ids_round_trip() {
+        let id = Id::String("550e8400-e29b-41d4-a716-446655440000".into());
+        let json = serde_json::to_string(&id).unwrap();
+
+        assert_eq!("\"550e8400-e29b-41d4-a716-446655440000\"", json);
+        assert_eq!(id, serde_json::from_str(&json).unwrap());
+    }
+}
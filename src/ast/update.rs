@@ -1,4 +1,4 @@
-use crate::ast::*;
+use crate::{ast::*, error::Error};
 
 /// A builder for an `UPDATE` statement.
 #[derive(Debug, PartialEq, Clone)]
@@ -31,6 +31,52 @@ impl<'a> Update<'a> {
         }
     }
 
+    /// Creates an `UPDATE` statement to the given table, setting the columns
+    /// to the values in the order the iterator yields them. Fails with
+    /// `EmptyUpdate` if there is nothing to set.
+    ///
+    /// ```rust
+    /// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+    /// # use std::collections::BTreeMap;
+    /// # fn main() -> prisma_query::Result<()> {
+    /// let mut row = BTreeMap::new();
+    /// row.insert("name", ParameterizedValue::from("musti"));
+    /// row.insert("age", ParameterizedValue::from(9));
+    ///
+    /// let query = Update::from_map("users", row)?.so_that("id".equals(1));
+    /// let (sql, params) = Sqlite::build(query);
+    ///
+    /// assert_eq!("UPDATE `users` SET `age` = ?, `name` = ? WHERE `id` = ?", sql);
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         ParameterizedValue::Integer(9),
+    ///         ParameterizedValue::from("musti"),
+    ///         ParameterizedValue::Integer(1),
+    ///     ],
+    ///     params,
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_map<T, I, K, V>(table: T, values: I) -> crate::Result<Self>
+    where
+        T: Into<Table<'a>>,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Column<'a>>,
+        V: Into<DatabaseValue<'a>>,
+    {
+        let update = values
+            .into_iter()
+            .fold(Self::table(table), |update, (k, v)| update.set(k, v));
+
+        if update.columns.is_empty() {
+            Err(Error::EmptyUpdate)
+        } else {
+            Ok(update)
+        }
+    }
+
     /// Add another column value assignment to the query
     ///
     /// ```rust
@@ -107,3 +153,44 @@ impl<'a> Update<'a> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::{Postgres, Visitor};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn updates_from_a_map_keep_the_iteration_order() {
+        let mut row = BTreeMap::new();
+        row.insert("b".to_string(), ParameterizedValue::from(2));
+        row.insert("a".to_string(), ParameterizedValue::from("one"));
+
+        let update = Update::from_map("t", row).unwrap();
+        let (sql, params) = Postgres::build(update.so_that("id".equals(3)));
+
+        assert_eq!(
+            "UPDATE \"t\" SET \"a\" = $1, \"b\" = $2 WHERE \"id\" = $3",
+            sql
+        );
+
+        assert_eq!(
+            vec![
+                ParameterizedValue::from("one"),
+                ParameterizedValue::Integer(2),
+                ParameterizedValue::Integer(3),
+            ],
+            params
+        );
+    }
+
+    #[test]
+    fn updates_from_an_empty_map_fail() {
+        let row: BTreeMap<&str, ParameterizedValue> = BTreeMap::new();
+
+        match Update::from_map("t", row) {
+            Err(Error::EmptyUpdate) => (),
+            res => panic!("expected EmptyUpdate, got {:?}", res),
+        }
+    }
+}
@@ -13,7 +13,10 @@ use serde_json::{Number, Value};
 use uuid::Uuid;
 
 #[cfg(feature = "chrono-0_4")]
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+#[cfg(feature = "rust_decimal-1")]
+use rust_decimal::Decimal;
 
 /// A value we must parameterize for the prepared statement.
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +27,7 @@ pub enum ParameterizedValue<'a> {
     Text(Cow<'a, str>),
     Boolean(bool),
     Char(char),
+    Bytes(Cow<'a, [u8]>),
     #[cfg(feature = "array")]
     Array(Vec<ParameterizedValue<'a>>),
     #[cfg(feature = "json-1")]
@@ -32,9 +36,20 @@ pub enum ParameterizedValue<'a> {
     Uuid(Uuid),
     #[cfg(feature = "chrono-0_4")]
     DateTime(DateTime<Utc>),
+    #[cfg(feature = "chrono-0_4")]
+    Date(NaiveDate),
+    #[cfg(feature = "chrono-0_4")]
+    Time(NaiveTime),
+    #[cfg(feature = "rust_decimal-1")]
+    Decimal(Decimal),
 }
 
-pub(crate) struct Params<'a>(pub(crate) &'a [ParameterizedValue<'a>]);
+/// The parameters of a query for logging, each written with the given
+/// function.
+pub(crate) struct Params<'a>(
+    pub(crate) &'a [ParameterizedValue<'a>],
+    pub(crate) fn(&ParameterizedValue<'a>) -> String,
+);
 
 impl<'a> fmt::Display for Params<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -42,7 +57,7 @@ impl<'a> fmt::Display for Params<'a> {
 
         write!(f, "[")?;
         for (i, val) in self.0.iter().enumerate() {
-            write!(f, "{}", val)?;
+            write!(f, "{}", (self.1)(val))?;
 
             if i < (len - 1) {
                 write!(f, ",")?;
@@ -52,6 +67,11 @@ impl<'a> fmt::Display for Params<'a> {
     }
 }
 
+/// The bytes as lowercase hexadecimal digits.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 impl<'a> fmt::Display for ParameterizedValue<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -61,6 +81,7 @@ impl<'a> fmt::Display for ParameterizedValue<'a> {
             ParameterizedValue::Text(val) => write!(f, "\"{}\"", val),
             ParameterizedValue::Boolean(val) => write!(f, "{}", val),
             ParameterizedValue::Char(val) => write!(f, "'{}'", val),
+            ParameterizedValue::Bytes(val) => write!(f, "X'{}'", hex(val)),
             #[cfg(feature = "array")]
             ParameterizedValue::Array(vals) => {
                 let len = vals.len();
@@ -81,6 +102,12 @@ impl<'a> fmt::Display for ParameterizedValue<'a> {
             ParameterizedValue::Uuid(val) => write!(f, "{}", val),
             #[cfg(feature = "chrono-0_4")]
             ParameterizedValue::DateTime(val) => write!(f, "{}", val),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(val) => write!(f, "{}", val),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(val) => write!(f, "{}", val),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(val) => write!(f, "{}", val),
         }
     }
 }
@@ -101,6 +128,9 @@ impl<'a> From<ParameterizedValue<'a>> for Value {
                     .to_string();
                 Value::String(s)
             }
+            ParameterizedValue::Bytes(bytes) => {
+                Value::Array(bytes.iter().map(|b| Value::from(*b)).collect())
+            }
             ParameterizedValue::Json(v) => v,
             #[cfg(feature = "array")]
             ParameterizedValue::Array(v) => Value::Array(v.into_iter().map(Value::from).collect()),
@@ -108,11 +138,51 @@ impl<'a> From<ParameterizedValue<'a>> for Value {
             ParameterizedValue::Uuid(u) => Value::String(u.to_hyphenated().to_string()),
             #[cfg(feature = "chrono-0_4")]
             ParameterizedValue::DateTime(dt) => Value::String(dt.to_rfc3339()),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(date) => Value::String(date.to_string()),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(time) => Value::String(time.to_string()),
+            // A JSON number would lose the precision.
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(d) => Value::String(d.to_string()),
         }
     }
 }
 
 impl<'a> ParameterizedValue<'a> {
+    /// The name of the variant, for error messages.
+    ///
+    /// ```rust
+    /// # use prisma_query::ast::*;
+    /// assert_eq!("integer", ParameterizedValue::Integer(1).type_name());
+    /// assert_eq!("text", ParameterizedValue::from("musti").type_name());
+    /// ```
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ParameterizedValue::Null => "null",
+            ParameterizedValue::Integer(_) => "integer",
+            ParameterizedValue::Real(_) => "real",
+            ParameterizedValue::Text(_) => "text",
+            ParameterizedValue::Boolean(_) => "boolean",
+            ParameterizedValue::Char(_) => "char",
+            ParameterizedValue::Bytes(_) => "bytes",
+            #[cfg(feature = "array")]
+            ParameterizedValue::Array(_) => "array",
+            #[cfg(feature = "json-1")]
+            ParameterizedValue::Json(_) => "json",
+            #[cfg(feature = "uuid-0_7")]
+            ParameterizedValue::Uuid(_) => "uuid",
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::DateTime(_) => "datetime",
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(_) => "date",
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(_) => "time",
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(_) => "decimal",
+        }
+    }
+
     /// `true` if the `ParameterizedValue` is null.
     pub fn is_null(&self) -> bool {
         match self {
@@ -145,6 +215,31 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// `true` if the `ParameterizedValue` is binary data.
+    pub fn is_bytes(&self) -> bool {
+        match self {
+            ParameterizedValue::Bytes(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a byte slice if the value is binary data, otherwise `None`.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            ParameterizedValue::Bytes(bytes) => Some(bytes.borrow()),
+            _ => None,
+        }
+    }
+
+    /// Transforms the `ParameterizedValue` to a `Vec<u8>` if it's binary
+    /// data, otherwise `None`.
+    pub fn into_bytes(self) -> Option<Vec<u8>> {
+        match self {
+            ParameterizedValue::Bytes(bytes) => Some(bytes.into_owned()),
+            _ => None,
+        }
+    }
+
     /// Returns a cloned String if the value is text, otherwise `None`.
     pub fn to_string(&self) -> Option<String> {
         match self {
@@ -170,7 +265,8 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
-    /// Returns an i64 if the value is an integer, otherwise `None`.
+    /// Returns an i64 if the value is an integer, otherwise `None`. Reals are
+    /// not converted, see [coerce_i64](#method.coerce_i64).
     pub fn as_i64(&self) -> Option<i64> {
         match self {
             ParameterizedValue::Integer(i) => Some(*i),
@@ -178,6 +274,31 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// Returns an i64 if the value is an integer, a real without a fractional
+    /// part or text holding an integer, otherwise `None`.
+    ///
+    /// ```rust
+    /// # use prisma_query::ast::*;
+    /// assert_eq!(Some(2), ParameterizedValue::Real(2.0).coerce_i64());
+    /// assert_eq!(Some(2), ParameterizedValue::from("2").coerce_i64());
+    /// assert_eq!(None, ParameterizedValue::Real(2.5).coerce_i64());
+    /// assert_eq!(None, ParameterizedValue::Real(2.0).as_i64());
+    /// ```
+    pub fn coerce_i64(&self) -> Option<i64> {
+        match self {
+            ParameterizedValue::Integer(i) => Some(*i),
+            ParameterizedValue::Real(f)
+                if f.fract() == 0.0
+                    && *f >= i64::min_value() as f64
+                    && *f < i64::max_value() as f64 =>
+            {
+                Some(*f as i64)
+            }
+            ParameterizedValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
     /// `true` if the `ParameterizedValue` is a real value.
     pub fn is_real(&self) -> bool {
         match self {
@@ -186,7 +307,8 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
-    /// Returns a f64 if the value is a real value, otherwise `None`.
+    /// Returns a f64 if the value is a real value, otherwise `None`. Integers
+    /// and decimals are not converted, see [coerce_f64](#method.coerce_f64).
     pub fn as_f64(&self) -> Option<f64> {
         match self {
             ParameterizedValue::Real(f) => Some(*f),
@@ -194,6 +316,27 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// Returns a f64 if the value is a real value, an integer, a decimal or
+    /// text holding a number, otherwise `None`. Large integers and decimals
+    /// lose precision.
+    ///
+    /// ```rust
+    /// # use prisma_query::ast::*;
+    /// assert_eq!(Some(2.0), ParameterizedValue::Integer(2).coerce_f64());
+    /// assert_eq!(Some(2.5), ParameterizedValue::from("2.5").coerce_f64());
+    /// assert_eq!(None, ParameterizedValue::Integer(2).as_f64());
+    /// ```
+    pub fn coerce_f64(&self) -> Option<f64> {
+        match self {
+            ParameterizedValue::Real(f) => Some(*f),
+            ParameterizedValue::Integer(i) => Some(*i as f64),
+            ParameterizedValue::Text(s) => s.trim().parse().ok(),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(d) => d.to_string().parse().ok(),
+            _ => None,
+        }
+    }
+
     /// `true` if the `ParameterizedValue` is a boolean value.
     pub fn is_bool(&self) -> bool {
         match self {
@@ -204,7 +347,8 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
-    /// Returns a bool if the value is a boolean, otherwise `None`.
+    /// Returns a bool if the value is a boolean, otherwise `None`. As the only
+    /// accessor converting the value, an integer `0` or `1` is a boolean too.
     pub fn as_bool(&self) -> Option<bool> {
         match self {
             ParameterizedValue::Boolean(b) => Some(*b),
@@ -232,8 +376,26 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// `true` if the `ParameterizedValue` is a decimal.
+    #[cfg(feature = "rust_decimal-1")]
+    pub fn is_decimal(&self) -> bool {
+        match self {
+            ParameterizedValue::Decimal(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a Decimal if the value is a decimal, otherwise `None`.
+    #[cfg(feature = "rust_decimal-1")]
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            ParameterizedValue::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+
     /// `true` if the `ParameterizedValue` is a DateTime.
-    #[cfg(feature = "uuid-0_7")]
+    #[cfg(feature = "chrono-0_4")]
     pub fn is_datetime(&self) -> bool {
         match self {
             ParameterizedValue::DateTime(_) => true,
@@ -250,6 +412,42 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// `true` if the `ParameterizedValue` is a date.
+    #[cfg(feature = "chrono-0_4")]
+    pub fn is_date(&self) -> bool {
+        match self {
+            ParameterizedValue::Date(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a NaiveDate if the value is a date, otherwise `None`.
+    #[cfg(feature = "chrono-0_4")]
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        match self {
+            ParameterizedValue::Date(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    /// `true` if the `ParameterizedValue` is a time of day.
+    #[cfg(feature = "chrono-0_4")]
+    pub fn is_time(&self) -> bool {
+        match self {
+            ParameterizedValue::Time(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a NaiveTime if the value is a time of day, otherwise `None`.
+    #[cfg(feature = "chrono-0_4")]
+    pub fn as_time(&self) -> Option<NaiveTime> {
+        match self {
+            ParameterizedValue::Time(time) => Some(*time),
+            _ => None,
+        }
+    }
+
     /// `true` if the `ParameterizedValue` is a JSON value.
     #[cfg(feature = "json-1")]
     pub fn is_json(&self) -> bool {
@@ -277,6 +475,51 @@ impl<'a> ParameterizedValue<'a> {
         }
     }
 
+    /// Copies borrowed text and binary data, making the value independent of
+    /// the data it was borrowing.
+    ///
+    /// ```rust
+    /// # use prisma_query::ast::*;
+    /// let value = {
+    ///     let name = String::from("musti");
+    ///     ParameterizedValue::from(name.as_str()).into_owned()
+    /// };
+    ///
+    /// assert_eq!(Some("musti"), value.as_str());
+    /// ```
+    pub fn into_owned(self) -> ParameterizedValue<'static> {
+        match self {
+            ParameterizedValue::Null => ParameterizedValue::Null,
+            ParameterizedValue::Integer(i) => ParameterizedValue::Integer(i),
+            ParameterizedValue::Real(f) => ParameterizedValue::Real(f),
+            ParameterizedValue::Text(s) => ParameterizedValue::Text(Cow::Owned(s.into_owned())),
+            ParameterizedValue::Boolean(b) => ParameterizedValue::Boolean(b),
+            ParameterizedValue::Char(c) => ParameterizedValue::Char(c),
+            ParameterizedValue::Bytes(bytes) => {
+                ParameterizedValue::Bytes(Cow::Owned(bytes.into_owned()))
+            }
+            #[cfg(feature = "array")]
+            ParameterizedValue::Array(values) => ParameterizedValue::Array(
+                values
+                    .into_iter()
+                    .map(ParameterizedValue::into_owned)
+                    .collect(),
+            ),
+            #[cfg(feature = "json-1")]
+            ParameterizedValue::Json(json) => ParameterizedValue::Json(json),
+            #[cfg(feature = "uuid-0_7")]
+            ParameterizedValue::Uuid(uuid) => ParameterizedValue::Uuid(uuid),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::DateTime(dt) => ParameterizedValue::DateTime(dt),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(date) => ParameterizedValue::Date(date),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(time) => ParameterizedValue::Time(time),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(d) => ParameterizedValue::Decimal(d),
+        }
+    }
+
     /// Returns a Vec<T> if the value is an array of T, otherwise `None`.
     pub fn into_vec<T>(self) -> Option<Vec<T>>
     where
@@ -311,6 +554,10 @@ pub enum DatabaseValue<'a> {
     Function(Function<'a>),
     /// A qualified asterisk to a table
     Asterisk(Option<Box<Table<'a>>>),
+    /// A `CASE` expression
+    Case(Box<Case<'a>>),
+    /// The value a conflicting `INSERT` tried to write to the column
+    Excluded(Box<Column<'a>>),
 }
 
 /// A quick alias to create an asterisk to a table.
@@ -343,17 +590,163 @@ impl<'a> From<String> for ParameterizedValue<'a> {
     }
 }
 
+impl<'a> From<&'a [u8]> for ParameterizedValue<'a> {
+    fn from(that: &'a [u8]) -> Self {
+        ParameterizedValue::Bytes(that.into())
+    }
+}
+
+impl<'a> From<Vec<u8>> for ParameterizedValue<'a> {
+    fn from(that: Vec<u8>) -> Self {
+        ParameterizedValue::Bytes(that.into())
+    }
+}
+
+/// Saturates at `i64::MAX`. Converting through `u64` with `TryFrom` fails
+/// instead of saturating.
+///
+/// ```rust
+/// # use prisma_query::ast::*;
+/// # use std::convert::TryFrom;
+/// assert_eq!(ParameterizedValue::Integer(42), ParameterizedValue::from(42usize));
+///
+/// assert_eq!(
+///     ParameterizedValue::Integer(i64::max_value()),
+///     ParameterizedValue::from(usize::max_value())
+/// );
+///
+/// assert!(ParameterizedValue::try_from(usize::max_value() as u64).is_err());
+/// ```
 impl<'a> From<usize> for ParameterizedValue<'a> {
     #[inline]
     fn from(that: usize) -> Self {
-        ParameterizedValue::Integer(i64::try_from(that).unwrap())
+        ParameterizedValue::Integer(i64::try_from(that).unwrap_or(i64::max_value()))
     }
 }
 
 impl<'a> From<i32> for ParameterizedValue<'a> {
     #[inline]
     fn from(that: i32) -> Self {
-        ParameterizedValue::Integer(i64::try_from(that).unwrap())
+        ParameterizedValue::Integer(i64::from(that))
+    }
+}
+
+impl<'a> From<u32> for ParameterizedValue<'a> {
+    #[inline]
+    fn from(that: u32) -> Self {
+        ParameterizedValue::Integer(i64::from(that))
+    }
+}
+
+impl<'a> From<i16> for ParameterizedValue<'a> {
+    #[inline]
+    fn from(that: i16) -> Self {
+        ParameterizedValue::Integer(i64::from(that))
+    }
+}
+
+impl<'a> From<u16> for ParameterizedValue<'a> {
+    #[inline]
+    fn from(that: u16) -> Self {
+        ParameterizedValue::Integer(i64::from(that))
+    }
+}
+
+impl<'a> From<f32> for ParameterizedValue<'a> {
+    #[inline]
+    fn from(that: f32) -> Self {
+        ParameterizedValue::Real(f64::from(that))
+    }
+}
+
+/// A `char` is a string of one character. The `Char` variant is the single
+/// byte `"char"` type of PostgreSQL.
+impl<'a> From<char> for ParameterizedValue<'a> {
+    #[inline]
+    fn from(that: char) -> Self {
+        ParameterizedValue::Text(that.to_string().into())
+    }
+}
+
+/// `None` is a `NULL`, `Some(value)` the value itself.
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+/// let middle_name: Option<&str> = None;
+/// let query = Insert::single_into("users").value("middle_name", middle_name);
+/// let (sql, params) = Sqlite::build(query);
+///
+/// assert_eq!("INSERT INTO `users` (`middle_name`) VALUES (?)", sql);
+/// assert_eq!(vec![ParameterizedValue::Null], params);
+/// ```
+///
+/// Plain literals and options of them are inferred in the builders alike.
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Sqlite}};
+/// let conditions = "age"
+///     .equals(10)
+///     .and("weight".equals(1.5))
+///     .and("id".in_selection(vec![1, 2]))
+///     .and("lives".equals(Some(10)));
+///
+/// let query = Select::from_table("cats").so_that(conditions);
+/// let (_, params) = Sqlite::build(query);
+///
+/// assert_eq!(
+///     vec![
+///         ParameterizedValue::Integer(10),
+///         ParameterizedValue::Real(1.5),
+///         ParameterizedValue::Integer(1),
+///         ParameterizedValue::Integer(2),
+///         ParameterizedValue::Integer(10),
+///     ],
+///     params
+/// );
+/// ```
+impl<'a, T> From<Option<T>> for ParameterizedValue<'a>
+where
+    T: Into<ParameterizedValue<'a>>,
+{
+    #[inline]
+    fn from(that: Option<T>) -> Self {
+        match that {
+            Some(value) => value.into(),
+            None => ParameterizedValue::Null,
+        }
+    }
+}
+
+/// An unsigned 64-bit integer fits into an `Integer` only up to `i64::MAX`.
+///
+/// ```rust
+/// # use prisma_query::ast::*;
+/// # use std::convert::TryFrom;
+/// assert_eq!(
+///     ParameterizedValue::Integer(42),
+///     ParameterizedValue::try_from(42u64).unwrap()
+/// );
+///
+/// assert!(ParameterizedValue::try_from(u64::max_value()).is_err());
+/// ```
+impl<'a> TryFrom<u64> for ParameterizedValue<'a> {
+    type Error = Error;
+
+    fn try_from(that: u64) -> Result<Self, Self::Error> {
+        i64::try_from(that)
+            .map(ParameterizedValue::Integer)
+            .map_err(|_| Error::ConversionError("Integer overflow"))
+    }
+}
+
+impl<'a> From<Id> for ParameterizedValue<'a> {
+    fn from(id: Id) -> Self {
+        match id {
+            Id::Int(i) => i.into(),
+            Id::String(s) => s.into(),
+            #[cfg(feature = "uuid-0_7")]
+            Id::UUID(u) => ParameterizedValue::Uuid(u),
+        }
     }
 }
 
@@ -383,6 +776,16 @@ impl<'a> TryFrom<ParameterizedValue<'a>> for String {
     }
 }
 
+impl<'a> TryFrom<ParameterizedValue<'a>> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<Vec<u8>, Self::Error> {
+        value
+            .into_bytes()
+            .ok_or(Error::ConversionError("Not bytes"))
+    }
+}
+
 impl<'a> TryFrom<ParameterizedValue<'a>> for bool {
     type Error = Error;
 
@@ -402,6 +805,65 @@ impl<'a> TryFrom<ParameterizedValue<'a>> for DateTime<Utc> {
     }
 }
 
+#[cfg(feature = "chrono-0_4")]
+impl<'a> TryFrom<ParameterizedValue<'a>> for NaiveDate {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<NaiveDate, Self::Error> {
+        value.as_date().ok_or(Error::ConversionError("Not a date"))
+    }
+}
+
+#[cfg(feature = "chrono-0_4")]
+impl<'a> TryFrom<ParameterizedValue<'a>> for NaiveTime {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<NaiveTime, Self::Error> {
+        value.as_time().ok_or(Error::ConversionError("Not a time"))
+    }
+}
+
+/// Parses text and takes 16 bytes as a UUID too, for the databases storing
+/// them as `CHAR(36)` or `BINARY(16)`.
+///
+/// ```
+/// # use prisma_query::ast::ParameterizedValue;
+/// # use std::convert::TryFrom;
+/// # use uuid::Uuid;
+/// let text = ParameterizedValue::from("67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// let uuid = Uuid::try_from(text).unwrap();
+///
+/// let bytes = ParameterizedValue::from(uuid.as_bytes().to_vec());
+///
+/// assert_eq!(uuid, Uuid::try_from(bytes).unwrap());
+/// ```
+#[cfg(feature = "uuid-0_7")]
+impl<'a> TryFrom<ParameterizedValue<'a>> for Uuid {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<Uuid, Self::Error> {
+        let uuid = match value {
+            ParameterizedValue::Uuid(uuid) => Some(uuid),
+            ParameterizedValue::Text(s) => Uuid::parse_str(&s).ok(),
+            ParameterizedValue::Bytes(bytes) => Uuid::from_slice(&bytes).ok(),
+            _ => None,
+        };
+
+        uuid.ok_or(Error::ConversionError("Not a UUID"))
+    }
+}
+
+#[cfg(feature = "rust_decimal-1")]
+impl<'a> TryFrom<ParameterizedValue<'a>> for Decimal {
+    type Error = Error;
+
+    fn try_from(value: ParameterizedValue<'a>) -> Result<Decimal, Self::Error> {
+        value
+            .as_decimal()
+            .ok_or(Error::ConversionError("Not a decimal"))
+    }
+}
+
 macro_rules! parameterized_value {
     ($kind:ident,$paramkind:ident) => {
         impl<'a> From<$kind> for ParameterizedValue<'a> {
@@ -422,6 +884,9 @@ parameterized_value!(Value, Json);
 #[cfg(feature = "uuid-0_7")]
 parameterized_value!(Uuid, Uuid);
 
+#[cfg(feature = "rust_decimal-1")]
+parameterized_value!(Decimal, Decimal);
+
 #[cfg(feature = "chrono-0_4")]
 impl<'a> From<DateTime<Utc>> for ParameterizedValue<'a> {
     #[inline]
@@ -430,6 +895,12 @@ impl<'a> From<DateTime<Utc>> for ParameterizedValue<'a> {
     }
 }
 
+#[cfg(feature = "chrono-0_4")]
+parameterized_value!(NaiveDate, Date);
+
+#[cfg(feature = "chrono-0_4")]
+parameterized_value!(NaiveTime, Time);
+
 /*
  * Here be the database value converters.
  */
@@ -621,8 +1092,92 @@ impl<'a> Comparable<'a> for DatabaseValue<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visitor::{Sqlite, Visitor};
     use std::str::FromStr;
 
+    #[test]
+    fn options_are_converted_into_values_or_nulls() {
+        let name: Option<&str> = Some("Musti");
+        let owned_name: Option<String> = None;
+
+        assert_eq!(ParameterizedValue::from("Musti"), name.into());
+        assert_eq!(ParameterizedValue::Null, owned_name.into());
+        assert_eq!(ParameterizedValue::Integer(10), Some(10).into());
+        assert_eq!(ParameterizedValue::Integer(10), Some(10i64).into());
+        assert_eq!(ParameterizedValue::Real(1.5), Some(1.5).into());
+        assert_eq!(ParameterizedValue::Boolean(true), Some(true).into());
+        assert_eq!(ParameterizedValue::Null, None::<i64>.into());
+        assert_eq!(ParameterizedValue::Null, Some(None::<bool>).into());
+
+        assert_eq!(
+            DatabaseValue::Parameterized(ParameterizedValue::from("Musti")),
+            DatabaseValue::from(name)
+        );
+
+        assert_eq!(
+            DatabaseValue::Parameterized(ParameterizedValue::Null),
+            DatabaseValue::from(None::<&str>)
+        );
+    }
+
+    #[test]
+    fn numbers_are_widened_into_integers_and_reals() {
+        assert_eq!(ParameterizedValue::Integer(-1), (-1i16).into());
+        assert_eq!(ParameterizedValue::Integer(65535), u16::max_value().into());
+        assert_eq!(
+            ParameterizedValue::Integer(-2147483648),
+            i32::min_value().into()
+        );
+        assert_eq!(
+            ParameterizedValue::Integer(4294967295),
+            u32::max_value().into()
+        );
+        assert_eq!(ParameterizedValue::Real(1.5), 1.5f32.into());
+        assert_eq!(ParameterizedValue::from("a"), 'a'.into());
+    }
+
+    #[test]
+    fn bare_literals_can_be_compared_against() {
+        let conditions = "age"
+            .equals(10)
+            .and("weight".less_than(1.5))
+            .and("name".not_equals("Musti"))
+            .and("initial".equals('M'))
+            .and("paws".equals(4u16));
+
+        let (_, params) = Sqlite::build(Select::from_table("cats").so_that(conditions));
+
+        assert_eq!(
+            vec![
+                ParameterizedValue::Integer(10),
+                ParameterizedValue::Real(1.5),
+                ParameterizedValue::from("Musti"),
+                ParameterizedValue::from("M"),
+                ParameterizedValue::Integer(4),
+            ],
+            params
+        );
+    }
+
+    #[test]
+    fn only_the_coerce_accessors_convert_numbers() {
+        assert_eq!(None, ParameterizedValue::from("1").as_i64());
+        assert_eq!(Some(1), ParameterizedValue::from(" 1 ").coerce_i64());
+        assert_eq!(None, ParameterizedValue::Real(1e19).coerce_i64());
+        assert_eq!(None, ParameterizedValue::Boolean(true).coerce_i64());
+        assert_eq!(None, ParameterizedValue::from("meow").coerce_f64());
+        assert_eq!(Some(-1.0), ParameterizedValue::Integer(-1).coerce_f64());
+    }
+
+    #[test]
+    fn bytes_are_displayed_as_hex() {
+        let pv = ParameterizedValue::from(&[0u8, 159, 146, 150, 255][..]);
+
+        assert_eq!("X'009f9296ff'", format!("{}", pv));
+        assert_eq!(Some(&[0u8, 159, 146, 150, 255][..]), pv.as_bytes());
+        assert_eq!(None, pv.as_str());
+    }
+
     #[test]
     fn a_parameterized_value_of_ints_can_be_converted_into_a_vec() {
         let pv = ParameterizedValue::Array(vec![ParameterizedValue::Integer(1)]);
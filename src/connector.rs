@@ -7,9 +7,27 @@
 //! [PostgreSQL](struct.PostgreSql.html) and [SQLite](struct.Sqlite.html) connect
 //! to the corresponding databases and implement the
 //! [Queryable](trait.Queryable.html) trait for generalized querying interface.
+#[cfg(feature = "async")]
+mod async_queryable;
+mod batch;
+mod cancel;
+mod connection_info;
+mod execute_result;
+mod introspection;
+mod params;
+mod query_log;
 mod queryable;
+mod read_write_split;
+#[cfg(any(feature = "mysql-16", feature = "postgresql-0_16"))]
+mod reconnect;
 mod result_set;
+mod retry;
+mod returning;
+mod script;
+mod server_version;
+mod statement_cache;
 mod transaction;
+mod upsert;
 
 pub(crate) mod metrics;
 
@@ -31,6 +49,19 @@ pub use self::postgres::*;
 #[cfg(feature = "rusqlite-0_19")]
 pub use sqlite::*;
 
+#[cfg(feature = "async")]
+pub use self::async_queryable::*;
+pub use self::batch::{BatchInsertResult, BatchLimits, UpdateAssignment};
+pub use self::cancel::CancelToken;
+pub(crate) use self::cancel::Session;
+pub use self::connection_info::*;
+pub use self::execute_result::{ExecuteResult, UpsertOutcome};
+pub use self::introspection::{ColumnDescription, ColumnType};
+pub use self::params::Secret;
+pub use self::query_log::{ConnectionEvent, QueryInfo, QueryLogger};
+pub use self::read_write_split::ReadWriteSplit;
 pub use self::result_set::*;
+pub use self::retry::{RetryPolicy, RetryingQueryable};
+pub use self::server_version::{Backend, Capabilities, ServerVersion};
 pub use queryable::*;
 pub use transaction::*;
@@ -0,0 +1,378 @@
+//! Async versions of the connectors, enabled with the `async` feature.
+//!
+//! The drivers of this crate are blocking, Postgres included: its
+//! `tokio-postgres` version is built on futures 0.1. The connections are
+//! therefore wrapped in an [AsyncConnection](struct.AsyncConnection.html),
+//! running each operation on the blocking thread pool of tokio with
+//! `tokio::task::spawn_blocking`. Both the basic and the threaded scheduler
+//! work, as long as the `blocking` feature of tokio is enabled.
+//!
+//! The operations move to another thread, so the queries must own their
+//! data, e.g. be built from `&'static str` names or owned strings.
+use super::{Queryable, ResultSet};
+use crate::{
+    ast::{Id, ParameterizedValue, Query},
+    error::Error,
+};
+use async_trait::async_trait;
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::task;
+
+/// Represents a connection or a transaction that can be queried without
+/// blocking the async runtime.
+#[async_trait]
+pub trait AsyncQueryable {
+    /// Executes the given query and returns the ID of the last inserted row.
+    async fn execute(&mut self, q: Query<'static>) -> crate::Result<Option<Id>>;
+
+    /// Executes the given query and returns the result set.
+    async fn query(&mut self, q: Query<'static>) -> crate::Result<ResultSet>;
+
+    /// Executes a query given as SQL, interpolating the given parameters and
+    /// returning a set of results.
+    async fn query_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<ResultSet>;
+
+    /// Executes a query given as SQL, interpolating the given parameters and
+    /// returning the number of affected rows.
+    async fn execute_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<u64>;
+
+    /// Runs a command in the database, for queries that can't be run using
+    /// prepared statements.
+    async fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()>;
+
+    /// Starts a new transaction
+    async fn start_transaction<'a>(&'a mut self) -> crate::Result<AsyncTransaction<'a>>;
+}
+
+/// The connection shared with the operations running on the blocking pool.
+struct Shared<Q: ?Sized> {
+    /// Set by a transaction dropped without a commit or a rollback.
+    rollback_pending: AtomicBool,
+    conn: Mutex<Q>,
+}
+
+/// Runs `f` on the blocking thread pool, first rolling back a transaction
+/// dropped since the previous operation.
+async fn blocking<Q, F, T>(shared: Arc<Shared<Q>>, f: F) -> crate::Result<T>
+where
+    Q: Queryable + Send + ?Sized + 'static,
+    F: FnOnce(&mut Q) -> crate::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let task = task::spawn_blocking(move || {
+        let mut conn = shared.conn.lock().map_err(|_| {
+            Error::ConnectionError(failure::err_msg(
+                "A previous operation panicked on the connection",
+            ))
+        })?;
+
+        if shared.rollback_pending.swap(false, Ordering::SeqCst) {
+            conn.raw_cmd("ROLLBACK")?;
+        }
+
+        f(&mut *conn)
+    });
+
+    task.await
+        .map_err(|e| Error::ConnectionError(failure::err_msg(e.to_string())))?
+}
+
+fn owned_params(params: &[ParameterizedValue]) -> Vec<ParameterizedValue<'static>> {
+    params
+        .iter()
+        .cloned()
+        .map(ParameterizedValue::into_owned)
+        .collect()
+}
+
+/// A blocking connection usable from async code.
+///
+/// ```no_run
+/// # use prisma_query::{ast::*, connector::*};
+/// # async fn run() -> prisma_query::Result<()> {
+/// let mut conn = AsyncConnection::new(Sqlite::new("db/test.db")?);
+///
+/// let mut tx = conn.start_transaction().await?;
+/// tx.raw_cmd("CREATE TABLE async_doc (id, name)").await?;
+/// tx.execute(Insert::single_into("async_doc").value("id", 1).into()).await?;
+/// tx.commit().await?;
+///
+/// let res = conn.query(Select::from_table("async_doc").into()).await?;
+/// assert_eq!(1, res.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncConnection<Q> {
+    shared: Arc<Shared<Q>>,
+}
+
+impl<Q> AsyncConnection<Q>
+where
+    Q: Queryable + Send + 'static,
+{
+    pub fn new(inner: Q) -> Self {
+        let shared = Shared {
+            rollback_pending: AtomicBool::new(false),
+            conn: Mutex::new(inner),
+        };
+
+        Self {
+            shared: Arc::new(shared),
+        }
+    }
+
+    /// Runs `f` with the wrapped blocking connection on the blocking thread
+    /// pool, e.g. to call the methods specific to the connector.
+    pub async fn with_inner<F, T>(&mut self, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&mut Q) -> crate::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        blocking(self.shared.clone(), f).await
+    }
+}
+
+#[async_trait]
+impl<Q> AsyncQueryable for AsyncConnection<Q>
+where
+    Q: Queryable + Send + 'static,
+{
+    async fn execute(&mut self, q: Query<'static>) -> crate::Result<Option<Id>> {
+        blocking(self.shared.clone(), move |conn| conn.execute(q)).await
+    }
+
+    async fn query(&mut self, q: Query<'static>) -> crate::Result<ResultSet> {
+        blocking(self.shared.clone(), move |conn| conn.query(q)).await
+    }
+
+    async fn query_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<ResultSet> {
+        let (sql, params) = (sql.to_string(), owned_params(params));
+        blocking(self.shared.clone(), move |conn| {
+            conn.query_raw(&sql, &params)
+        })
+        .await
+    }
+
+    async fn execute_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<u64> {
+        let (sql, params) = (sql.to_string(), owned_params(params));
+        blocking(self.shared.clone(), move |conn| {
+            conn.execute_raw(&sql, &params)
+        })
+        .await
+    }
+
+    async fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        let cmd = cmd.to_string();
+        blocking(self.shared.clone(), move |conn| conn.raw_cmd(&cmd)).await
+    }
+
+    async fn start_transaction<'a>(&'a mut self) -> crate::Result<AsyncTransaction<'a>> {
+        AsyncTransaction::new(self.shared.clone()).await
+    }
+}
+
+/// An async database transaction. If not commited, the transaction will be
+/// rolled back when dropped, right before the next operation on the
+/// connection.
+///
+/// Does not support nesting, so starting a new transaction using the
+/// transaction object will panic.
+pub struct AsyncTransaction<'a> {
+    shared: Arc<Shared<dyn Queryable + Send>>,
+    done: bool,
+    _conn: PhantomData<&'a mut ()>,
+}
+
+impl<'a> AsyncTransaction<'a> {
+    async fn new(shared: Arc<Shared<dyn Queryable + Send>>) -> crate::Result<AsyncTransaction<'a>> {
+        blocking(shared.clone(), |conn| conn.raw_cmd("BEGIN")).await?;
+
+        Ok(Self {
+            shared,
+            done: false,
+            _conn: PhantomData,
+        })
+    }
+
+    /// Commit the changes to the database and consume the transaction.
+    pub async fn commit(mut self) -> crate::Result<()> {
+        self.done = true;
+        blocking(self.shared.clone(), |conn| conn.raw_cmd("COMMIT")).await
+    }
+
+    /// Rolls back the changes to the database.
+    pub async fn rollback(&mut self) -> crate::Result<()> {
+        self.done = true;
+        blocking(self.shared.clone(), |conn| conn.raw_cmd("ROLLBACK")).await
+    }
+}
+
+impl<'a> Drop for AsyncTransaction<'a> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.shared.rollback_pending.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+#[async_trait]
+impl<'t> AsyncQueryable for AsyncTransaction<'t> {
+    async fn execute(&mut self, q: Query<'static>) -> crate::Result<Option<Id>> {
+        blocking(self.shared.clone(), move |conn| conn.execute(q)).await
+    }
+
+    async fn query(&mut self, q: Query<'static>) -> crate::Result<ResultSet> {
+        blocking(self.shared.clone(), move |conn| conn.query(q)).await
+    }
+
+    async fn query_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<ResultSet> {
+        let (sql, params) = (sql.to_string(), owned_params(params));
+        blocking(self.shared.clone(), move |conn| {
+            conn.query_raw(&sql, &params)
+        })
+        .await
+    }
+
+    async fn execute_raw<'a>(
+        &mut self,
+        sql: &str,
+        params: &[ParameterizedValue<'a>],
+    ) -> crate::Result<u64> {
+        let (sql, params) = (sql.to_string(), owned_params(params));
+        blocking(self.shared.clone(), move |conn| {
+            conn.execute_raw(&sql, &params)
+        })
+        .await
+    }
+
+    async fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        let cmd = cmd.to_string();
+        blocking(self.shared.clone(), move |conn| conn.raw_cmd(&cmd)).await
+    }
+
+    async fn start_transaction<'a>(&'a mut self) -> crate::Result<AsyncTransaction<'a>> {
+        panic!("Nested transactions are not supported")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast::*, connector::Sqlite};
+
+    #[tokio::test(threaded_scheduler)]
+    async fn should_insert_query_update_and_delete() {
+        let mut conn = AsyncConnection::new(Sqlite::new_in_memory().unwrap());
+
+        conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .unwrap();
+
+        let insert = Insert::single_into("cats").value("name", "musti");
+        let id = conn.execute(insert.into()).await.unwrap();
+        assert_eq!(Some(Id::Int(1)), id);
+
+        let update = Update::table("cats").set("name", "naukio");
+        conn.execute(update.into()).await.unwrap();
+
+        let res = conn.query(Select::from_table("cats").into()).await.unwrap();
+        assert_eq!(
+            Some("naukio"),
+            res.first().and_then(|row| row["name"].as_str())
+        );
+
+        let deleted = conn.execute_raw("DELETE FROM cats", &[]).await.unwrap();
+        assert_eq!(1, deleted);
+    }
+
+    #[tokio::test]
+    async fn should_roll_back_a_dropped_transaction() {
+        let mut conn = AsyncConnection::new(Sqlite::new_in_memory().unwrap());
+        conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+
+        {
+            let mut tx = conn.start_transaction().await.unwrap();
+            tx.raw_cmd("INSERT INTO cats (id) VALUES (1)")
+                .await
+                .unwrap();
+        }
+
+        let mut tx = conn.start_transaction().await.unwrap();
+        tx.raw_cmd("INSERT INTO cats (id) VALUES (2)")
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let res = conn.query_raw("SELECT id FROM cats", &[]).await.unwrap();
+        assert_eq!(1, res.len());
+        assert_eq!(Some(2), res.first().and_then(|row| row[0].as_i64()));
+    }
+
+    #[cfg(feature = "postgresql-0_16")]
+    #[tokio::test(threaded_scheduler)]
+    async fn should_query_postgres() {
+        use crate::connector::PostgreSql;
+        use std::{convert::TryFrom, env};
+
+        let url = format!(
+            "postgresql://{}:{}@{}:{}/{}",
+            env::var("TEST_PG_USER").unwrap(),
+            env::var("TEST_PG_PASSWORD").unwrap(),
+            env::var("TEST_PG_HOST").unwrap(),
+            env::var("TEST_PG_PORT").unwrap(),
+            env::var("TEST_PG_DB").unwrap(),
+        );
+
+        let conn =
+            task::spawn_blocking(move || PostgreSql::try_from(url::Url::parse(&url).unwrap()));
+        let mut conn = AsyncConnection::new(conn.await.unwrap().unwrap());
+
+        let mut tx = conn.start_transaction().await.unwrap();
+        tx.raw_cmd("CREATE TEMPORARY TABLE async_cats (id int4, name text)")
+            .await
+            .unwrap();
+
+        let insert = Insert::single_into("async_cats")
+            .value("id", 1)
+            .value("name", "musti");
+        tx.execute(insert.into()).await.unwrap();
+
+        let res = tx
+            .query(Select::from_table("async_cats").into())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some("musti"),
+            res.first().and_then(|row| row["name"].as_str())
+        );
+    }
+}
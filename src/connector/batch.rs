@@ -0,0 +1,275 @@
+use crate::ast::*;
+
+/// Per-statement limits of a database, used to split batch operations into
+/// statements the database accepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchLimits {
+    /// The maximum number of bind parameters in a single statement.
+    pub max_parameters: usize,
+    /// An estimated upper bound for the size of the parameter data in a single
+    /// statement, if the database limits it.
+    pub max_bytes: Option<usize>,
+}
+
+impl Default for BatchLimits {
+    /// The limits of SQLite, the most conservative of the supported databases.
+    fn default() -> Self {
+        Self {
+            max_parameters: 999,
+            max_bytes: None,
+        }
+    }
+}
+
+/// The outcome of a batch insert.
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchInsertResult {
+    /// The number of inserted rows.
+    pub count: u64,
+    /// The `last_insert_id` of every statement reporting one, in the order of
+    /// the statements. On MySQL the id generated for the first row of the
+    /// statement, on SQLite the `rowid` of its last row. Postgres reports
+    /// none, the statements having no `RETURNING` clause.
+    pub last_insert_ids: Vec<Id>,
+}
+
+/// The new values of a row in a bulk update: the key of the row, and the
+/// columns to set with their values.
+pub type UpdateAssignment<'a> = (ParameterizedValue<'a>, Vec<(Column<'a>, DatabaseValue<'a>)>);
+
+/// Splits the rows into chunks fitting the given limits. A row is never split
+/// between two chunks; a row exceeding the limits on its own gets a chunk of
+/// its own.
+pub(crate) fn chunk_rows<'a>(rows: Vec<Row<'a>>, limits: BatchLimits) -> Vec<Vec<Row<'a>>> {
+    chunk_by(rows, limits, |row| {
+        (row.values.len(), estimated_size(row.values.iter()))
+    })
+}
+
+/// Splits the assignments of a bulk update into chunks fitting the given
+/// limits. A key takes a parameter in the `IN` list, and two more for every
+/// `WHEN ? THEN ?` of its columns.
+pub(crate) fn chunk_assignments<'a>(
+    assignments: Vec<UpdateAssignment<'a>>,
+    limits: BatchLimits,
+) -> Vec<Vec<UpdateAssignment<'a>>> {
+    chunk_by(assignments, limits, |(_, values)| {
+        let parameters = 1 + values.len() * 2;
+        let bytes = 16 + estimated_size(values.iter().map(|(_, value)| value));
+
+        (parameters, bytes)
+    })
+}
+
+/// Builds an `UPDATE` setting every assigned column to a `CASE` on the key,
+/// keeping the current value for the keys not assigning the column.
+pub(crate) fn update_statement<'a>(
+    table: Table<'a>,
+    key_column: Column<'a>,
+    assignments: Vec<UpdateAssignment<'a>>,
+) -> Update<'a> {
+    let mut keys = Vec::with_capacity(assignments.len());
+    let mut columns: Vec<(Column<'a>, Vec<(ParameterizedValue<'a>, DatabaseValue<'a>)>)> =
+        Vec::new();
+
+    for (key, values) in assignments.into_iter() {
+        for (column, value) in values.into_iter() {
+            match columns.iter_mut().find(|(c, _)| c == &column) {
+                Some((_, branches)) => branches.push((key.clone(), value)),
+                None => columns.push((column, vec![(key.clone(), value)])),
+            }
+        }
+
+        keys.push(key);
+    }
+
+    let update = columns
+        .into_iter()
+        .fold(Update::table(table), |update, (column, branches)| {
+            let expression = branches
+                .into_iter()
+                .fold(case(key_column.clone()), |expression, (key, value)| {
+                    expression.when(key, value)
+                });
+
+            let otherwise = column.clone();
+            update.set(column, expression.otherwise(otherwise))
+        });
+
+    update.so_that(key_column.in_selection(keys))
+}
+
+/// Splits the items into chunks fitting the given limits, measuring each item
+/// by its parameter count and estimated size.
+fn chunk_by<T, F>(items: Vec<T>, limits: BatchLimits, measure: F) -> Vec<Vec<T>>
+where
+    F: Fn(&T) -> (usize, usize),
+{
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut parameters = 0;
+    let mut bytes = 0;
+
+    for item in items.into_iter() {
+        let (item_parameters, item_bytes) = measure(&item);
+
+        let exceeds_parameters = parameters + item_parameters > limits.max_parameters;
+
+        let exceeds_bytes = limits
+            .max_bytes
+            .map(|max| bytes + item_bytes > max)
+            .unwrap_or(false);
+
+        if !current.is_empty() && (exceeds_parameters || exceeds_bytes) {
+            chunks.push(current);
+            current = Vec::new();
+            parameters = 0;
+            bytes = 0;
+        }
+
+        parameters += item_parameters;
+        bytes += item_bytes;
+        current.push(item);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn estimated_size<'a, 'b: 'a, I>(values: I) -> usize
+where
+    I: Iterator<Item = &'a DatabaseValue<'b>>,
+{
+    values
+        .map(|value| match value {
+            DatabaseValue::Parameterized(ParameterizedValue::Text(s)) => s.len(),
+            _ => 16,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::{Sqlite, Visitor};
+
+    fn rows(count: usize, width: usize) -> Vec<Row<'static>> {
+        (0..count)
+            .map(|i| Row::from(vec![i as i64; width]))
+            .collect()
+    }
+
+    #[test]
+    fn chunks_respect_the_parameter_limit() {
+        let limits = BatchLimits {
+            max_parameters: 10,
+            max_bytes: None,
+        };
+
+        let chunks = chunk_rows(rows(7, 3), limits);
+
+        assert_eq!(vec![3, 3, 1], chunks.iter().map(Vec::len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn chunks_respect_the_byte_limit() {
+        let limits = BatchLimits {
+            max_parameters: 1000,
+            max_bytes: Some(10),
+        };
+
+        let rows = vec![
+            Row::from(vec!["aaaaaa"]),
+            Row::from(vec!["bbbbbb"]),
+            Row::from(vec!["cc"]),
+        ];
+
+        let chunks = chunk_rows(rows, limits);
+
+        assert_eq!(vec![1, 2], chunks.iter().map(Vec::len).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn an_oversized_row_gets_a_chunk_of_its_own() {
+        let limits = BatchLimits {
+            max_parameters: 2,
+            max_bytes: None,
+        };
+
+        let chunks = chunk_rows(rows(2, 5), limits);
+
+        assert_eq!(2, chunks.len());
+        assert!(chunks.iter().all(|chunk| chunk[0].values.len() == 5));
+    }
+
+    #[test]
+    fn assignments_are_chunked_by_their_parameters() {
+        let limits = BatchLimits {
+            max_parameters: 10,
+            max_bytes: None,
+        };
+
+        let assignments: Vec<UpdateAssignment> = (0..5)
+            .map(|i| {
+                let values = vec![
+                    (Column::from("name"), DatabaseValue::from("musti")),
+                    (Column::from("age"), DatabaseValue::from(i)),
+                ];
+
+                (ParameterizedValue::from(i), values)
+            })
+            .collect();
+
+        let chunks = chunk_assignments(assignments, limits);
+
+        assert_eq!(
+            vec![2, 2, 1],
+            chunks.iter().map(Vec::len).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn update_statement_sets_each_column_with_a_case() {
+        let assignments = vec![
+            (
+                ParameterizedValue::from(1),
+                vec![(Column::from("name"), DatabaseValue::from("musti"))],
+            ),
+            (
+                ParameterizedValue::from(2),
+                vec![
+                    (Column::from("name"), DatabaseValue::from("naukio")),
+                    (Column::from("age"), DatabaseValue::from(5)),
+                ],
+            ),
+        ];
+
+        let update = update_statement("cats".into(), "id".into(), assignments);
+        let (sql, params) = Sqlite::build(update);
+
+        assert_eq!(
+            "UPDATE `cats` SET \
+             `name` = CASE `id` WHEN ? THEN ? WHEN ? THEN ? ELSE `name` END, \
+             `age` = CASE `id` WHEN ? THEN ? ELSE `age` END \
+             WHERE `id` IN (?, ?)",
+            sql
+        );
+
+        assert_eq!(
+            vec![
+                ParameterizedValue::from(1),
+                ParameterizedValue::from("musti"),
+                ParameterizedValue::from(2),
+                ParameterizedValue::from("naukio"),
+                ParameterizedValue::from(2),
+                ParameterizedValue::from(5),
+                ParameterizedValue::from(1),
+                ParameterizedValue::from(2),
+            ],
+            params
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+};
+
+/// Cancels the query running on the connection it was taken from, usable from
+/// another thread while the connection is busy. The cancelled query fails with
+/// `Error::QueryCancelled`.
+///
+/// Cancelling when no query runs does nothing, apart from SQLite, where it
+/// interrupts the next statement started before the running one finishes. A
+/// token of a connection opened again after losing it, or dropped, does
+/// nothing either, as the server may have given its id to another session.
+///
+/// ```
+/// # use prisma_query::{connector::*, error::Error};
+/// # use std::{thread, time::Duration};
+/// let mut conn = Sqlite::new_in_memory().unwrap();
+/// let token = conn.cancel_token().unwrap();
+///
+/// let canceller = thread::spawn(move || {
+///     thread::sleep(Duration::from_millis(100));
+///     token.cancel().unwrap();
+/// });
+///
+/// let res = conn.query_raw(
+///     "WITH RECURSIVE r(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM r) SELECT COUNT(*) FROM r",
+///     &[],
+/// );
+///
+/// canceller.join().unwrap();
+///
+/// match res {
+///     Err(Error::QueryCancelled) => (),
+///     res => panic!("Expected `QueryCancelled`, got {:?}", res.map(|_| ())),
+/// }
+/// ```
+pub struct CancelToken {
+    session: Option<Weak<AtomicBool>>,
+    cancel: Box<dyn Fn() -> crate::Result<()> + Send>,
+}
+
+impl CancelToken {
+    pub(crate) fn new<F>(cancel: F) -> Self
+    where
+        F: Fn() -> crate::Result<()> + Send + 'static,
+    {
+        Self {
+            session: None,
+            cancel: Box::new(cancel),
+        }
+    }
+
+    /// A token only cancelling while the session is the current one of the
+    /// connection.
+    pub(crate) fn for_session<F>(session: &Session, cancel: F) -> Self
+    where
+        F: Fn() -> crate::Result<()> + Send + 'static,
+    {
+        Self {
+            session: Some(Arc::downgrade(&session.0)),
+            cancel: Box::new(cancel),
+        }
+    }
+
+    /// Asks the database to cancel the running query. PostgreSQL and MySQL
+    /// open a separate connection for sending the request.
+    pub fn cancel(&self) -> crate::Result<()> {
+        if let Some(ref session) = self.session {
+            match session.upgrade() {
+                Some(requested) => requested.store(true, Ordering::SeqCst),
+                None => return Ok(()),
+            }
+        }
+
+        (self.cancel)()
+    }
+}
+
+/// A server session of a connection, replaced when the connection is opened
+/// again. Its tokens record the cancel requests, telling a cancelled query
+/// apart from one stopped by the server for another reason.
+#[derive(Debug, Default)]
+pub(crate) struct Session(Arc<AtomicBool>);
+
+impl Session {
+    /// Leaves the tokens of the previous session without effect.
+    pub(crate) fn renew(&mut self) {
+        self.0 = Arc::default();
+    }
+
+    /// True if a token asked for a cancel since the last call.
+    pub(crate) fn take_cancel_request(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancelToken").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn counting_token(session: &Session) -> (CancelToken, Arc<AtomicUsize>) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+
+        let token = CancelToken::for_session(session, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        (token, count)
+    }
+
+    #[test]
+    fn tokens_record_the_cancel_requests() {
+        let session = Session::default();
+        let (token, count) = counting_token(&session);
+
+        assert!(!session.take_cancel_request());
+
+        token.cancel().unwrap();
+
+        assert_eq!(1, count.load(Ordering::SeqCst));
+        assert!(session.take_cancel_request());
+        assert!(!session.take_cancel_request());
+    }
+
+    #[test]
+    fn tokens_of_a_previous_session_do_nothing() {
+        let mut session = Session::default();
+        let (token, count) = counting_token(&session);
+
+        session.renew();
+        token.cancel().unwrap();
+
+        assert_eq!(0, count.load(Ordering::SeqCst));
+        assert!(!session.take_cancel_request());
+    }
+}
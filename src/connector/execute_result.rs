@@ -0,0 +1,43 @@
+use super::ResultSet;
+use crate::ast::Id;
+
+/// Everything the database reported of a write, returned by
+/// [execute_with_result](trait.Queryable.html#method.execute_with_result).
+///
+/// ```
+/// # use prisma_query::{ast::*, connector::*};
+/// # let mut conn = Sqlite::new_in_memory().unwrap();
+/// conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)").unwrap();
+/// conn.raw_cmd("INSERT INTO cats (name) VALUES ('musti')").unwrap();
+///
+/// let insert = Insert::single_into("cats").value("name", "naukio");
+/// let result = conn.execute_with_result(insert.into()).unwrap();
+///
+/// assert_eq!(1, result.rows_affected);
+/// assert_eq!(Some(Id::Int(2)), result.last_insert_id);
+/// assert!(result.returning.is_none());
+/// ```
+#[derive(Debug, Default)]
+pub struct ExecuteResult {
+    /// The number of inserted, updated or deleted rows.
+    pub rows_affected: u64,
+    /// The id of the last inserted row. On Postgres the first column of the
+    /// last returned row, on MySQL and SQLite the id the database reports
+    /// for the connection, also for other statements than inserts. `None`
+    /// for a MySQL id above `i64::MAX`, the statement having run anyway.
+    pub last_insert_id: Option<Id>,
+    /// The rows of a `RETURNING` clause, if the statement had one.
+    pub returning: Option<ResultSet>,
+    /// What an [upsert](trait.Queryable.html#method.upsert) did, `None` for
+    /// other statements.
+    pub upsert: Option<UpsertOutcome>,
+}
+
+/// Whether an upsert inserted a new row or updated the existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    /// The database doesn't tell, as with SQLite.
+    Unknown,
+}
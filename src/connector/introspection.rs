@@ -0,0 +1,126 @@
+use super::ResultSet;
+use crate::error::Error;
+
+/// A column of a table, as described by the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDescription {
+    pub name: String,
+    /// The type, normalized over the databases.
+    pub column_type: ColumnType,
+    /// The type as given by the database, e.g. `character varying`.
+    pub data_type: String,
+    pub nullable: bool,
+    /// The default value as an SQL expression.
+    pub default: Option<String>,
+}
+
+/// The type of a column, normalized over the databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Decimal,
+    Boolean,
+    Text,
+    Date,
+    Time,
+    DateTime,
+    Json,
+    Uuid,
+    Bytes,
+    /// Any other type, such as arrays, enums or geometric types.
+    Other,
+}
+
+impl ColumnType {
+    /// Normalizes a type name of any of the databases. SQLite types are
+    /// matched as in its type affinity rules.
+    ///
+    /// ```
+    /// # use prisma_query::connector::ColumnType;
+    /// assert_eq!(ColumnType::Text, ColumnType::from_data_type("character varying"));
+    /// assert_eq!(ColumnType::Integer, ColumnType::from_data_type("BIGINT(20) UNSIGNED"));
+    /// assert_eq!(ColumnType::DateTime, ColumnType::from_data_type("timestamp with time zone"));
+    /// ```
+    pub fn from_data_type(data_type: &str) -> Self {
+        let data_type = data_type.to_lowercase();
+        let data_type = data_type.split('(').next().unwrap_or("").trim();
+
+        let data_type = data_type
+            .trim_end_matches(" unsigned")
+            .trim_end_matches(" signed");
+
+        match data_type {
+            "boolean" | "bool" => ColumnType::Boolean,
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2"
+            | "int4" | "int8" | "smallserial" | "serial" | "bigserial" => ColumnType::Integer,
+            "real" | "float" | "double" | "double precision" | "float4" | "float8" => {
+                ColumnType::Float
+            }
+            "decimal" | "numeric" | "money" => ColumnType::Decimal,
+            "char" | "character" | "varchar" | "character varying" | "text" | "tinytext"
+            | "mediumtext" | "longtext" | "citext" | "clob" => ColumnType::Text,
+            "date" => ColumnType::Date,
+            "time" | "time without time zone" | "time with time zone" => ColumnType::Time,
+            "datetime"
+            | "timestamp"
+            | "timestamp without time zone"
+            | "timestamp with time zone"
+            | "timestamptz" => ColumnType::DateTime,
+            "json" | "jsonb" => ColumnType::Json,
+            "uuid" => ColumnType::Uuid,
+            "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" | "bytea" => {
+                ColumnType::Bytes
+            }
+            s if s.contains("int") && !s.contains("interval") && !s.contains("point") => {
+                ColumnType::Integer
+            }
+            s if s.contains("char") || s.contains("clob") || s.contains("text") => ColumnType::Text,
+            s if s.contains("blob") => ColumnType::Bytes,
+            s if s.contains("real") || s.contains("floa") || s.contains("doub") => {
+                ColumnType::Float
+            }
+            _ => ColumnType::Other,
+        }
+    }
+}
+
+/// Reads the descriptions from rows of name, type, nullability as `YES` or
+/// `NO`, and default, as in `information_schema.columns`.
+pub(crate) fn columns_from_information_schema(
+    rows: ResultSet,
+) -> crate::Result<Vec<ColumnDescription>> {
+    rows.into_iter()
+        .map(|row| {
+            let name = row[0].as_str().ok_or_else(column_read_failure)?;
+            let data_type = row[1].as_str().ok_or_else(column_read_failure)?;
+            let nullable = row[2].as_str().ok_or_else(column_read_failure)?;
+
+            Ok(ColumnDescription {
+                name: name.to_string(),
+                column_type: ColumnType::from_data_type(data_type),
+                data_type: data_type.to_string(),
+                nullable: nullable == "YES",
+                default: row[3].as_str().map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// The names in the first column of the rows.
+pub(crate) fn names(rows: ResultSet) -> crate::Result<Vec<String>> {
+    rows.into_iter()
+        .map(|row| {
+            row[0]
+                .as_str()
+                .map(String::from)
+                .ok_or_else(column_read_failure)
+        })
+        .collect()
+}
+
+fn column_read_failure() -> Error {
+    Error::ColumnReadFailure(failure::err_msg(
+        "Unexpected value in the schema description",
+    ))
+}
@@ -1,14 +1,19 @@
+use super::query_log::{QueryLogger, RowCount};
 use crate::ast::{ParameterizedValue, Params};
+#[cfg(feature = "postgresql-0_16")]
+use crate::visitor::{self, Visitor};
 use std::time::Instant;
 
 pub(crate) fn query<'a, F, T>(
     tag: &'static str,
     query: &str,
     params: &[ParameterizedValue<'a>],
+    logger: Option<QueryLogger>,
     f: F,
-) -> T
+) -> crate::Result<T>
 where
-    F: FnOnce() -> T,
+    F: FnOnce() -> crate::Result<T>,
+    T: RowCount,
 {
     let start = Instant::now();
     let res = f();
@@ -20,17 +25,15 @@ where
             info!(
                 "query: \"{}\", params: {} (in {}ms)",
                 query,
-                Params(params),
+                Params(params, inline_parameter(tag)),
                 start.elapsed().as_millis(),
             );
         }
         #[cfg(feature = "tracing-log")]
         {
-            //let params: Vec<String> = params.iter().map(|p| format!("{}", p)).collect();
-
             tracing::info!(
                 query,
-                params = %Params(params),
+                params = %Params(params, inline_parameter(tag)),
                 duration_ns = start.elapsed().as_nanos() as u64,
             )
         }
@@ -38,9 +41,22 @@ where
 
     timing!(format!("{}.query.time", tag), start, end);
 
+    if let Some(logger) = logger {
+        logger.log(query, params, end - start, &res);
+    }
+
     res
 }
 
+/// Writes a parameter as a literal of the database the tag starts with.
+fn inline_parameter<'a>(tag: &str) -> fn(&ParameterizedValue<'a>) -> String {
+    match tag.split('.').next() {
+        #[cfg(feature = "postgresql-0_16")]
+        Some("postgres") => visitor::Postgres::inline_parameter,
+        _ => |value| value.to_string(),
+    }
+}
+
 pub(crate) fn connect<F, T>(tag: &'static str, f: F) -> T
 where
     F: FnOnce() -> T,
@@ -53,3 +69,22 @@ where
 
     res
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters_are_logged_as_literals_of_the_database() {
+        let params = [ParameterizedValue::from(&[0u8, 255][..]), 1.into()];
+
+        let sqlite = Params(&params, inline_parameter("sqlite.query_raw"));
+        assert_eq!("[X'00ff',1]", sqlite.to_string());
+
+        #[cfg(feature = "postgresql-0_16")]
+        {
+            let postgres = Params(&params, inline_parameter("postgres.query_raw"));
+            assert_eq!("['\\x00ff',1]", postgres.to_string());
+        }
+    }
+}
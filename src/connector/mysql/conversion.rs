@@ -1,18 +1,36 @@
 use crate::{
     ast::ParameterizedValue,
-    connector::queryable::{ToColumnNames, ToRow},
+    connector::{
+        queryable::{ToColumnNames, ToRow},
+        MysqlUuidFormat,
+    },
 };
 #[cfg(feature = "chrono-0_4")]
-use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use mysql as my;
+#[cfg(feature = "rust_decimal-1")]
+use rust_decimal::Decimal;
+use std::convert::TryFrom;
 
-pub fn conv_params<'a>(params: &[ParameterizedValue<'a>]) -> my::Params {
+#[cfg_attr(not(feature = "uuid-0_7"), allow(unused_variables))]
+pub fn conv_params<'a>(
+    params: &[ParameterizedValue<'a>],
+    uuid_format: MysqlUuidFormat,
+) -> my::Params {
     if params.is_empty() {
         // If we don't use explicit 'Empty',
         // mysql crashes with 'internal error: entered unreachable code'
         my::Params::Empty
     } else {
-        my::Params::Positional(params.iter().map(|x| x.into()).collect::<Vec<my::Value>>())
+        let values = params.iter().map(|x| match x {
+            #[cfg(feature = "uuid-0_7")]
+            ParameterizedValue::Uuid(u) if uuid_format == MysqlUuidFormat::Binary => {
+                my::Value::Bytes(u.as_bytes().to_vec())
+            }
+            x => x.into(),
+        });
+
+        my::Params::Positional(values.collect::<Vec<my::Value>>())
     }
 }
 
@@ -23,14 +41,59 @@ impl ToRow for my::Row {
             let raw_value = row.as_ref(i).unwrap_or(&my::Value::NULL);
             let res = match raw_value {
                 my::Value::NULL => ParameterizedValue::Null,
+                #[cfg(feature = "json-1")]
+                my::Value::Bytes(b) if is_json(row, i) => match serde_json::from_slice(b) {
+                    Ok(json) => ParameterizedValue::Json(json),
+                    Err(_) => ParameterizedValue::Text(String::from_utf8(b.to_vec())?.into()),
+                },
+                #[cfg(feature = "rust_decimal-1")]
+                my::Value::Bytes(b) if is_decimal(row, i) => {
+                    let s = String::from_utf8(b.to_vec())?;
+
+                    match s.parse() {
+                        Ok(d) => ParameterizedValue::Decimal(d),
+                        Err(_) => ParameterizedValue::Text(s.into()),
+                    }
+                }
+                my::Value::Bytes(b) if is_binary(row, i) => {
+                    ParameterizedValue::Bytes(b.to_vec().into())
+                }
                 my::Value::Bytes(b) => {
                     ParameterizedValue::Text(String::from_utf8(b.to_vec())?.into())
                 }
                 my::Value::Int(i) => ParameterizedValue::Integer(*i),
-                // TOOD: This is unsafe
-                my::Value::UInt(i) => ParameterizedValue::Integer(*i as i64),
+                my::Value::UInt(i) => match i64::try_from(*i) {
+                    Ok(i) => ParameterizedValue::Integer(i),
+                    #[cfg(feature = "rust_decimal-1")]
+                    Err(_) => ParameterizedValue::Decimal(Decimal::from(*i)),
+                    #[cfg(not(feature = "rust_decimal-1"))]
+                    Err(_) => ParameterizedValue::Text(i.to_string().into()),
+                },
                 my::Value::Float(f) => ParameterizedValue::Real(*f),
                 #[cfg(feature = "chrono-0_4")]
+                my::Value::Date(year, month, day, ..) if is_date(row, i) => {
+                    match NaiveDate::from_ymd_opt(
+                        i32::from(*year),
+                        u32::from(*month),
+                        u32::from(*day),
+                    ) {
+                        Some(date) => ParameterizedValue::Date(date),
+                        None => ParameterizedValue::Null,
+                    }
+                }
+                #[cfg(feature = "chrono-0_4")]
+                my::Value::Time(false, 0, hours, minutes, seconds, micros) => {
+                    match NaiveTime::from_hms_micro_opt(
+                        u32::from(*hours),
+                        u32::from(*minutes),
+                        u32::from(*seconds),
+                        *micros,
+                    ) {
+                        Some(time) => ParameterizedValue::Time(time),
+                        None => ParameterizedValue::Null,
+                    }
+                }
+                #[cfg(feature = "chrono-0_4")]
                 my::Value::Date(..) => {
                     let ts: NaiveDateTime = row.get(i).unwrap();
                     ParameterizedValue::DateTime(DateTime::<Utc>::from_utc(ts, Utc))
@@ -76,6 +139,68 @@ impl ToRow for my::Row {
     }
 }
 
+/// True if the column is a `DECIMAL`, sent as text.
+#[cfg(feature = "rust_decimal-1")]
+fn is_decimal(row: &my::Row, i: usize) -> bool {
+    use my::consts::ColumnType::*;
+
+    row.columns_ref()
+        .get(i)
+        .map(|column| match column.column_type() {
+            MYSQL_TYPE_NEWDECIMAL | MYSQL_TYPE_DECIMAL => true,
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// True if the column is a `DATE`, without the time.
+#[cfg(feature = "chrono-0_4")]
+fn is_date(row: &my::Row, i: usize) -> bool {
+    use my::consts::ColumnType::*;
+
+    row.columns_ref()
+        .get(i)
+        .map(|column| match column.column_type() {
+            MYSQL_TYPE_DATE | MYSQL_TYPE_NEWDATE => true,
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// The collation number of the `binary` charset.
+const BINARY_COLLATION: u16 = 63;
+
+/// True if the column holds binary strings, e.g. `BLOB` or `VARBINARY`.
+/// Numbers and dates in the text protocol have the binary charset too, so
+/// the type is checked as well.
+fn is_binary(row: &my::Row, i: usize) -> bool {
+    use my::consts::ColumnType::*;
+
+    row.columns_ref()
+        .get(i)
+        .map(|column| match column.column_type() {
+            MYSQL_TYPE_TINY_BLOB
+            | MYSQL_TYPE_MEDIUM_BLOB
+            | MYSQL_TYPE_LONG_BLOB
+            | MYSQL_TYPE_BLOB
+            | MYSQL_TYPE_VARCHAR
+            | MYSQL_TYPE_VAR_STRING
+            | MYSQL_TYPE_STRING => column.character_set() == BINARY_COLLATION,
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// True if the column is of the `JSON` type. MariaDB has no such type, its
+/// `JSON` being an alias of `LONGTEXT`.
+#[cfg(feature = "json-1")]
+fn is_json(row: &my::Row, i: usize) -> bool {
+    row.columns_ref()
+        .get(i)
+        .map(|column| column.column_type() == my::consts::ColumnType::MYSQL_TYPE_JSON)
+        .unwrap_or(false)
+}
+
 impl<'a> ToColumnNames for my::Stmt<'a> {
     fn to_column_names(&self) -> Vec<String> {
         let mut names = Vec::new();
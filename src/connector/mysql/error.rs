@@ -1,4 +1,7 @@
-use crate::error::Error;
+use crate::{
+    connector::Backend,
+    error::{Error, NativeError},
+};
 use mysql as my;
 
 impl From<my::error::Error> for Error {
@@ -10,74 +13,366 @@ impl From<my::error::Error> for Error {
             my::error::Error::DriverError(e) => match e {
                 DriverError::ConnectTimeout => Error::ConnectTimeout,
                 DriverError::Timeout => Error::Timeout,
+                DriverError::ConnectionClosed => Error::ConnectionClosed,
+                DriverError::SslNotSupported => Error::TlsError {
+                    message: String::from("The server does not support TLS connections"),
+                },
                 _ => Error::QueryError(e.into())
             },
             my::error::Error::MySqlError(MySqlError {
                 ref message, code, ..
-            }) if code == 1062 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted.last().map(|s| s.split('\'').collect()).unwrap();
-                let splitted: Vec<&str> = splitted[1].split('_').collect();
+            }) => {
+                let mapped = server_error(code, message);
+                let message = message.clone();
+                let native =
+                    NativeError::new(Backend::Mysql, Some(code.to_string()), Some(message), e);
 
-                let field_name: String = splitted[0].into();
+                match mapped {
+                    Some(mapped) => mapped.with_native(native),
+                    None => native.into(),
+                }
+            }
+            my::error::Error::IoError(ref e) if is_closed_connection(e) => Error::ConnectionClosed,
+            my::error::Error::IoError(ref e)
+                if e.kind() == std::io::ErrorKind::TimedOut
+                    || e.kind() == std::io::ErrorKind::WouldBlock =>
+            {
+                Error::Timeout
+            }
+            my::error::Error::TlsError(ref e) => Error::TlsError {
+                message: format!("{}", e),
+            },
+            my::error::Error::TlsHandshakeError(ref e) => Error::TlsError {
+                message: format!("{}", e),
+            },
+            e => Error::QueryError(e.into()),
+        }
+    }
+}
 
-                Error::UniqueConstraintViolation { field_name }
+/// Maps an error sent by the server, reading the names from the quoted
+/// parts of the message. `None` if the code has no variant of its own, or the
+/// message doesn't have the expected names.
+fn server_error(code: u16, message: &str) -> Option<Error> {
+    match code {
+        // ER_DUP_ENTRY: `Duplicate entry 'musti' for key 'users.name_unique'`,
+        // the entry possibly holding quotes of its own.
+        1062 => last_quoted(message).map(|key| Error::UniqueConstraintViolation {
+            field_name: key.into(),
+            constraint: Some(key.into()),
+            native: None,
+        }),
+        // ER_BAD_NULL_ERROR: `Column 'name' cannot be null`
+        // ER_WARN_NULL_TO_NOTNULL: `Column set to default value; NULL supplied
+        // to NOT NULL column 'name' at row 1`
+        1048 | 1263 => first_quoted(message).map(|column| Error::NullConstraintViolation {
+            field_name: column.into(),
+            native: None,
+        }),
+        // ER_ROW_IS_REFERENCED_2 and ER_NO_REFERENCED_ROW_2: `Cannot delete or
+        // update a parent row: a foreign key constraint fails (`prisma`.`cats`,
+        // CONSTRAINT `cats_ibfk_1` FOREIGN KEY (`owner_id`) REFERENCES ...)`
+        1451 | 1452 => backquoted_after(message, "CONSTRAINT ").map(|constraint| {
+            Error::ForeignKeyConstraintViolation {
+                constraint: constraint.into(),
+                native: None,
             }
-            my::error::Error::MySqlError(MySqlError {
-                ref message, code, ..
-            }) if code == 1263 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted.last().map(|s| s.split('\'').collect()).unwrap();
-                let splitted: Vec<&str> = splitted[1].split('_').collect();
+        }),
+        // ER_CHECK_CONSTRAINT_VIOLATED: `Check constraint 'lives_positive' is
+        // violated.`
+        3819 => first_quoted(message).map(|constraint| Error::CheckConstraintViolation {
+            constraint: constraint.into(),
+            native: None,
+        }),
+        // ER_NO_SUCH_TABLE: `Table 'prisma.cats' doesn't exist`
+        1146 => first_quoted(message).map(|table| Error::TableDoesNotExist {
+            table: table.into(),
+            native: None,
+        }),
+        // ER_BAD_FIELD_ERROR: `Unknown column 'cats.nme' in 'field list'`
+        1054 => first_quoted(message).map(|column| Error::ColumnDoesNotExist {
+            column: column.into(),
+            native: None,
+        }),
+        // ER_BAD_DB_ERROR: `Unknown database 'prisma'`
+        // ER_DB_DROP_EXISTS: `Can't drop database 'prisma'; database doesn't
+        // exist`
+        1049 | 1008 => first_quoted(message).map(|db_name| Error::DatabaseDoesNotExist {
+            db_name: db_name.into(),
+            native: None,
+        }),
+        // ER_DB_CREATE_EXISTS: `Can't create database 'prisma'; database
+        // exists`
+        1007 => first_quoted(message).map(|db_name| Error::DatabaseAlreadyExists {
+            db_name: db_name.into(),
+            native: None,
+        }),
+        // ER_DBACCESS_DENIED_ERROR: `Access denied for user 'prisma'@'%' to
+        // database 'prisma'`
+        1044 => last_quoted(message).map(|db_name| Error::DatabaseAccessDenied {
+            db_name: db_name.into(),
+            native: None,
+        }),
+        // ER_ACCESS_DENIED_ERROR: `Access denied for user 'root'@'localhost'
+        // (using password: YES)`
+        1045 => first_quoted(message).map(|user| Error::AuthenticationFailed {
+            user: user.into(),
+            native: None,
+        }),
+        // ER_QUERY_TIMEOUT, the `max_execution_time` was exceeded.
+        3024 => Some(Error::Timeout),
+        // ER_STATEMENT_TIMEOUT, the `max_statement_time` of MariaDB was
+        // exceeded.
+        1969 => Some(Error::Timeout),
+        // ER_QUERY_INTERRUPTED, by `KILL QUERY`.
+        1317 => Some(Error::QueryCancelled),
+        // ER_LOCK_DEADLOCK
+        1213 => Some(Error::TransactionWriteConflict),
+        // ER_LOCK_WAIT_TIMEOUT
+        1205 => Some(Error::LockTimeout),
+        // CR_SERVER_GONE_ERROR and CR_SERVER_LOST
+        2006 | 2013 => Some(Error::ConnectionClosed),
+        _ => None,
+    }
+}
+
+/// The first single-quoted part of the message.
+fn first_quoted(message: &str) -> Option<&str> {
+    let start = message.find('\'')? + 1;
+    let len = message[start..].find('\'')?;
+
+    Some(&message[start..start + len])
+}
+
+/// The last single-quoted part of the message.
+fn last_quoted(message: &str) -> Option<&str> {
+    let end = message.rfind('\'')?;
+    let start = message[..end].rfind('\'')? + 1;
+
+    Some(&message[start..end])
+}
+
+/// The backquoted identifier following the given prefix in the message.
+fn backquoted_after<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = message.find(&format!("{}`", prefix))? + prefix.len() + 1;
+    let len = message[start..].find('`')?;
+
+    Some(&message[start..start + len])
+}
 
-                let field_name: String = splitted[0].into();
+fn is_closed_connection(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
 
-                Error::NullConstraintViolation { field_name }
+    match e.kind() {
+        ErrorKind::BrokenPipe
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::UnexpectedEof => true,
+        _ => false,
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Error {
+    fn from(e: std::string::FromUtf8Error) -> Error {
+        Error::QueryError(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_violations_give_the_full_key_name() {
+        let messages = &[
+            (
+                "Duplicate entry 'musti@example.com-1' for key 'users.email_tenant_unique'",
+                "users.email_tenant_unique",
+            ),
+            ("Duplicate entry '1' for key 'PRIMARY'", "PRIMARY"),
+            (
+                "Duplicate entry 'it's musti' for key 'cats.name and tag'",
+                "cats.name and tag",
+            ),
+            (
+                "Doppelter Eintrag 'musti' für Schlüssel 'cats.name_unique'",
+                "cats.name_unique",
+            ),
+        ];
+
+        for &(message, key) in messages {
+            match server_error(1062, message) {
+                Some(Error::UniqueConstraintViolation { field_name, .. }) => {
+                    assert_eq!(key, field_name)
+                }
+                e => panic!("Expected `UniqueConstraintViolation`, got {:?}", e),
             }
-            my::error::Error::MySqlError(MySqlError {
-                ref message, code, ..
-            }) if code == 1049 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted.last().map(|s| s.split('\'').collect()).unwrap();
-                let db_name: String = splitted[1].into();
+        }
+    }
 
-                Error::DatabaseDoesNotExist { db_name }
+    #[test]
+    fn null_violations_give_the_column() {
+        let messages = &[
+            (1048, "Column 'first name' cannot be null"),
+            (
+                1263,
+                "Column set to default value; NULL supplied to NOT NULL column 'first name' at row 1",
+            ),
+        ];
+
+        for &(code, message) in messages {
+            match server_error(code, message) {
+                Some(Error::NullConstraintViolation { field_name, .. }) => {
+                    assert_eq!("first name", field_name)
+                }
+                e => panic!("Expected `NullConstraintViolation`, got {:?}", e),
             }
-            my::error::Error::MySqlError(MySqlError {
-                ref message, code, ..
-            }) if code == 1007 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted[3].split('\'').collect();
-                let db_name: String = splitted[1].into();
+        }
+    }
 
-                Error::DatabaseAlreadyExists { db_name }
+    #[test]
+    fn foreign_key_and_check_violations_give_the_constraint() {
+        let messages = &[
+            (
+                1451,
+                "Cannot delete or update a parent row: a foreign key constraint fails (`prisma`.`cats`, CONSTRAINT `cats owner` FOREIGN KEY (`owner_id`) REFERENCES `owners` (`id`))",
+            ),
+            (
+                1452,
+                "Cannot add or update a child row: a foreign key constraint fails (`prisma`.`cats`, CONSTRAINT `cats owner` FOREIGN KEY (`owner_id`) REFERENCES `owners` (`id`))",
+            ),
+        ];
+
+        for &(code, message) in messages {
+            match server_error(code, message) {
+                Some(Error::ForeignKeyConstraintViolation { constraint, .. }) => {
+                    assert_eq!("cats owner", constraint)
+                }
+                e => panic!("Expected `ForeignKeyConstraintViolation`, got {:?}", e),
             }
-            my::error::Error::MySqlError(MySqlError {
-                ref message, code, ..
-            }) if code == 1044 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted.last().map(|s| s.split('\'').collect()).unwrap();
-                let db_name: String = splitted[1].into();
+        }
 
-                Error::DatabaseAccessDenied { db_name }
+        match server_error(3819, "Check constraint 'lives_positive' is violated.") {
+            Some(Error::CheckConstraintViolation { constraint, .. }) => {
+                assert_eq!("lives_positive", constraint)
             }
-            my::error::Error::MySqlError(MySqlError {
-                ref message, code, ..
-            }) if code == 1045 => {
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted[4].split('@').collect();
-                let splitted: Vec<&str> = splitted[0].split("'").collect();
-                let user: String = splitted[1].into();
+            e => panic!("Expected `CheckConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn missing_tables_and_columns_give_the_name() {
+        match server_error(1146, "Table 'prisma.cats' doesn't exist") {
+            Some(Error::TableDoesNotExist { table, .. }) => assert_eq!("prisma.cats", table),
+            e => panic!("Expected `TableDoesNotExist`, got {:?}", e),
+        }
+
+        match server_error(1054, "Unknown column 'cats.nme' in 'field list'") {
+            Some(Error::ColumnDoesNotExist { column, .. }) => assert_eq!("cats.nme", column),
+            e => panic!("Expected `ColumnDoesNotExist`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn database_errors_give_the_database() {
+        match server_error(1049, "Unknown database 'my.db'") {
+            Some(Error::DatabaseDoesNotExist { db_name, .. }) => assert_eq!("my.db", db_name),
+            e => panic!("Expected `DatabaseDoesNotExist`, got {:?}", e),
+        }
+
+        match server_error(1008, "Can't drop database 'my db'; database doesn't exist") {
+            Some(Error::DatabaseDoesNotExist { db_name, .. }) => assert_eq!("my db", db_name),
+            e => panic!("Expected `DatabaseDoesNotExist`, got {:?}", e),
+        }
+
+        match server_error(1007, "Can't create database 'my db'; database exists") {
+            Some(Error::DatabaseAlreadyExists { db_name, .. }) => assert_eq!("my db", db_name),
+            e => panic!("Expected `DatabaseAlreadyExists`, got {:?}", e),
+        }
+
+        match server_error(
+            1044,
+            "Access denied for user 'prisma'@'%' to database 'my.db'",
+        ) {
+            Some(Error::DatabaseAccessDenied { db_name, .. }) => assert_eq!("my.db", db_name),
+            e => panic!("Expected `DatabaseAccessDenied`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn authentication_errors_give_the_user() {
+        let message = "Access denied for user 'prisma user'@'localhost' (using password: YES)";
+
+        match server_error(1045, message) {
+            Some(Error::AuthenticationFailed { user, .. }) => assert_eq!("prisma user", user),
+            e => panic!("Expected `AuthenticationFailed`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn statement_timeouts_of_mysql_and_mariadb_are_timeouts() {
+        let messages = &[
+            (
+                3024,
+                "Query execution was interrupted, maximum statement execution time exceeded",
+            ),
+            (
+                1969,
+                "Query execution was interrupted (max_statement_time exceeded)",
+            ),
+        ];
 
-                Error::AuthenticationFailed { user }
+        for &(code, message) in messages {
+            match server_error(code, message) {
+                Some(Error::Timeout) => (),
+                e => panic!("Expected `Timeout`, got {:?}", e),
             }
-            e => Error::QueryError(e.into()),
         }
     }
-}
 
-impl From<std::string::FromUtf8Error> for Error {
-    fn from(e: std::string::FromUtf8Error) -> Error {
-        Error::QueryError(e.into())
+    #[test]
+    fn transient_errors_are_classified() {
+        let transient = &[
+            (
+                1213,
+                "Deadlock found when trying to get lock; try restarting transaction",
+            ),
+            (
+                1205,
+                "Lock wait timeout exceeded; try restarting transaction",
+            ),
+            (2006, "MySQL server has gone away"),
+            (2013, "Lost connection to MySQL server during query"),
+        ];
+
+        for &(code, message) in transient {
+            assert!(
+                server_error(code, message).unwrap().is_transient(),
+                "{}",
+                code
+            );
+        }
+
+        let duplicate = server_error(1062, "Duplicate entry '1' for key 'PRIMARY'").unwrap();
+        assert!(!duplicate.is_transient());
+
+        let statement_timeout = server_error(
+            3024,
+            "Query execution was interrupted, maximum statement execution time exceeded",
+        )
+        .unwrap();
+        assert!(!statement_timeout.is_transient());
+    }
+
+    #[test]
+    fn messages_without_names_are_not_mapped() {
+        let codes = &[
+            1062, 1048, 1263, 1451, 1452, 3819, 1049, 1008, 1007, 1044, 1045,
+        ];
+
+        for &code in codes {
+            assert!(server_error(code, "").is_none());
+            assert!(server_error(code, "Something went wrong with 'this").is_none());
+        }
     }
 }
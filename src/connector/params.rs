@@ -0,0 +1,73 @@
+use crate::error::Error;
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+/// A password or another secret of the connection, hidden from the `Debug`
+/// and `Display` output.
+///
+/// ```
+/// # use prisma_query::connector::Secret;
+/// let password = Secret::new("hunter2");
+///
+/// assert_eq!("********", format!("{:?}", password));
+/// assert_eq!("hunter2", password.expose());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new<S: Into<String>>(secret: S) -> Self {
+        Secret(secret.into())
+    }
+
+    /// The secret in plain text, to be given to the driver.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "********")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "********")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(s: String) -> Self {
+        Secret(s)
+    }
+}
+
+impl<'a> From<&'a str> for Secret {
+    fn from(s: &'a str) -> Self {
+        Secret(s.to_string())
+    }
+}
+
+/// The number of pooled connections, if not given.
+pub(crate) fn default_connection_limit() -> u32 {
+    u32::try_from(num_cpus::get_physical() * 2 + 1).unwrap()
+}
+
+pub(crate) fn missing(field: &str) -> Error {
+    invalid(field, "the value is missing")
+}
+
+pub(crate) fn invalid<S: Into<String>>(field: &str, message: S) -> Error {
+    Error::InvalidConnectionParameter {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Parses the value of a connection string parameter.
+pub(crate) fn parse_param<T: FromStr>(field: &str, value: &str) -> crate::Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid(field, format!("`{}` is not a valid value", value)))
+}
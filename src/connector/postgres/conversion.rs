@@ -3,9 +3,9 @@ use crate::{
     connector::queryable::{ToColumnNames, ToRow},
 };
 #[cfg(feature = "chrono-0_4")]
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use postgres::{
-    types::{FromSql, ToSql, Type as PostgresType},
+    types::{FromSql, Kind, ToSql, Type as PostgresType},
     Statement as PostgresStatement,
 };
 use rust_decimal::Decimal;
@@ -37,15 +37,46 @@ fn accepts(ty: &PostgresType) -> bool {
         || <i64 as FromSql>::accepts(ty)
 }
 
+/// The label of an enum value, not accepted as a `String` by the driver.
+struct EnumLabel(String);
+
+impl<'a> FromSql<'a> for EnumLabel {
+    fn from_sql(
+        _: &PostgresType,
+        raw: &'a [u8],
+    ) -> Result<EnumLabel, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(EnumLabel(String::from_utf8(raw.to_vec())?))
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        is_enum(ty)
+    }
+}
+
+fn is_enum(ty: &PostgresType) -> bool {
+    match ty.kind() {
+        Kind::Enum(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "array")]
+fn is_enum_array(ty: &PostgresType) -> bool {
+    match ty.kind() {
+        Kind::Array(member) => is_enum(member),
+        _ => false,
+    }
+}
+
 impl<'a> FromSql<'a> for Id {
     fn from_sql(
         ty: &PostgresType,
         raw: &'a [u8],
     ) -> Result<Id, Box<dyn std::error::Error + Sync + Send>> {
         let res = match *ty {
-            PostgresType::INT2 => Id::Int(i16::from_sql(ty, raw)? as usize),
-            PostgresType::INT4 => Id::Int(i32::from_sql(ty, raw)? as usize),
-            PostgresType::INT8 => Id::Int(i64::from_sql(ty, raw)? as usize),
+            PostgresType::INT2 => Id::Int(i64::from(i16::from_sql(ty, raw)?)),
+            PostgresType::INT4 => Id::Int(i64::from(i32::from_sql(ty, raw)?)),
+            PostgresType::INT8 => Id::Int(i64::from_sql(ty, raw)?),
             #[cfg(feature = "uuid-0_7")]
             PostgresType::UUID => Id::UUID(Uuid::from_sql(ty, raw)?),
             _ => Id::String(String::from_sql(ty, raw)?),
@@ -88,6 +119,15 @@ impl ToRow for PostgresRow {
                     }
                     None => ParameterizedValue::Null,
                 },
+                #[cfg(feature = "rust_decimal-1")]
+                PostgresType::NUMERIC => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Decimal = val;
+                        ParameterizedValue::Decimal(val)
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(not(feature = "rust_decimal-1"))]
                 PostgresType::NUMERIC => match row.try_get(i)? {
                     Some(val) => {
                         let val: Decimal = val;
@@ -119,6 +159,45 @@ impl ToRow for PostgresRow {
                     }
                     None => ParameterizedValue::Null,
                 },
+                #[cfg(feature = "chrono-0_4")]
+                PostgresType::TIMESTAMPTZ => match row.try_get(i)? {
+                    Some(val) => {
+                        let dt: DateTime<Utc> = val;
+                        ParameterizedValue::DateTime(dt)
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(feature = "chrono-0_4")]
+                PostgresType::DATE => match row.try_get(i)? {
+                    Some(val) => {
+                        let date: NaiveDate = val;
+                        ParameterizedValue::Date(date)
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(feature = "chrono-0_4")]
+                PostgresType::TIME => match row.try_get(i)? {
+                    Some(val) => {
+                        let time: NaiveTime = val;
+                        ParameterizedValue::Time(time)
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                PostgresType::BYTEA => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<u8> = val;
+                        ParameterizedValue::Bytes(val.into())
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(feature = "json-1")]
+                PostgresType::JSON | PostgresType::JSONB => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: serde_json::Value = val;
+                        ParameterizedValue::Json(val)
+                    }
+                    None => ParameterizedValue::Null,
+                },
                 #[cfg(feature = "uuid-0_7")]
                 PostgresType::UUID => match row.try_get(i)? {
                     Some(val) => {
@@ -211,7 +290,47 @@ impl ToRow for PostgresRow {
                     }
                     None => ParameterizedValue::Null,
                 },
-                #[cfg(feature = "array")]
+                #[cfg(all(feature = "array", feature = "chrono-0_4"))]
+                PostgresType::TIMESTAMPTZ_ARRAY => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<DateTime<Utc>> = val;
+                        ParameterizedValue::Array(
+                            val.into_iter().map(ParameterizedValue::DateTime).collect(),
+                        )
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(all(feature = "array", feature = "chrono-0_4"))]
+                PostgresType::DATE_ARRAY => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<NaiveDate> = val;
+                        ParameterizedValue::Array(
+                            val.into_iter().map(ParameterizedValue::Date).collect(),
+                        )
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(all(feature = "array", feature = "chrono-0_4"))]
+                PostgresType::TIME_ARRAY => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<NaiveTime> = val;
+                        ParameterizedValue::Array(
+                            val.into_iter().map(ParameterizedValue::Time).collect(),
+                        )
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(all(feature = "array", feature = "rust_decimal-1"))]
+                PostgresType::NUMERIC_ARRAY => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<Decimal> = val;
+                        ParameterizedValue::Array(
+                            val.into_iter().map(ParameterizedValue::Decimal).collect(),
+                        )
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(all(feature = "array", not(feature = "rust_decimal-1")))]
                 PostgresType::NUMERIC_ARRAY => match row.try_get(i)? {
                     Some(val) => {
                         let val: Vec<Decimal> = val;
@@ -251,6 +370,25 @@ impl ToRow for PostgresRow {
                     }
                     None => ParameterizedValue::Null,
                 },
+                ref ty if is_enum(ty) => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: EnumLabel = val;
+                        ParameterizedValue::Text(val.0.into())
+                    }
+                    None => ParameterizedValue::Null,
+                },
+                #[cfg(feature = "array")]
+                ref ty if is_enum_array(ty) => match row.try_get(i)? {
+                    Some(val) => {
+                        let val: Vec<EnumLabel> = val;
+                        ParameterizedValue::Array(
+                            val.into_iter()
+                                .map(|x| ParameterizedValue::Text(x.0.into()))
+                                .collect(),
+                        )
+                    }
+                    None => ParameterizedValue::Null,
+                },
                 _ => match row.try_get(i)? {
                     Some(val) => {
                         let val: String = val;
@@ -0,0 +1,251 @@
+use crate::{
+    ast::{hex, Column, ParameterizedValue, Table, TableType},
+    error::Error,
+    visitor::{self, Visitor},
+};
+use std::io::{self, Read};
+
+/// The amount of encoded rows buffered before handing them to the driver.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The `COPY ... FROM STDIN` statement for the table and columns.
+pub(crate) fn copy_statement(table: &Table, columns: &[Column]) -> crate::Result<String> {
+    let table_name = match table.typ {
+        TableType::Table(ref name) => name,
+        TableType::Query(_) => {
+            return Err(Error::QueryError(failure::err_msg(
+                "COPY needs a table, not a query",
+            )))
+        }
+    };
+
+    let table = match table.database {
+        Some(ref database) => {
+            visitor::Postgres::delimited_identifiers(vec![&*database, &*table_name])
+        }
+        None => visitor::Postgres::delimited_identifiers(vec![&*table_name]),
+    };
+
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|c| visitor::Postgres::delimited_identifiers(vec![&*c.name]))
+        .collect();
+
+    Ok(format!(
+        "COPY {} ({}) FROM STDIN",
+        table,
+        columns.join(", ")
+    ))
+}
+
+/// Encodes the rows lazily in the text format of `COPY`, one chunk at a time.
+pub(crate) struct CopyReader<'a, I> {
+    rows: I,
+    width: usize,
+    buffer: Vec<u8>,
+    position: usize,
+    error: Option<Error>,
+    _values: std::marker::PhantomData<ParameterizedValue<'a>>,
+}
+
+impl<'a, I> CopyReader<'a, I>
+where
+    I: Iterator<Item = Vec<ParameterizedValue<'a>>>,
+{
+    pub(crate) fn new(rows: I, width: usize) -> Self {
+        Self {
+            rows,
+            width,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+            position: 0,
+            error: None,
+            _values: std::marker::PhantomData,
+        }
+    }
+
+    /// The error that aborted the stream, if the rows were invalid.
+    pub(crate) fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        self.buffer.clear();
+        self.position = 0;
+
+        while self.buffer.len() < CHUNK_SIZE {
+            let row = match self.rows.next() {
+                Some(row) => row,
+                None => break,
+            };
+
+            if row.len() != self.width {
+                let message = format!(
+                    "Expected {} values in a row, got {}",
+                    self.width,
+                    row.len()
+                );
+
+                self.error = Some(Error::QueryError(failure::err_msg(message.clone())));
+
+                // Failing the read makes the driver abort the COPY.
+                return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+            }
+
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    self.buffer.push(b'\t');
+                }
+
+                encode_value(value, &mut self.buffer);
+            }
+
+            self.buffer.push(b'\n');
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, I> Read for CopyReader<'a, I>
+where
+    I: Iterator<Item = Vec<ParameterizedValue<'a>>>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.buffer.len() {
+            self.fill_buffer()?;
+        }
+
+        let available = &self.buffer[self.position..];
+        let len = available.len().min(buf.len());
+
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+fn encode_value(value: &ParameterizedValue, buffer: &mut Vec<u8>) {
+    match text(value) {
+        Some(s) => escape(&s, buffer),
+        None => buffer.extend_from_slice(b"\\N"),
+    }
+}
+
+/// The value in the Postgres input syntax, `None` for `NULL`.
+fn text(value: &ParameterizedValue) -> Option<String> {
+    match value {
+        ParameterizedValue::Null => None,
+        ParameterizedValue::Integer(i) => Some(i.to_string()),
+        ParameterizedValue::Real(f) => Some(f.to_string()),
+        ParameterizedValue::Text(s) => Some(s.to_string()),
+        ParameterizedValue::Boolean(b) => Some(String::from(if *b { "t" } else { "f" })),
+        ParameterizedValue::Char(c) => Some(c.to_string()),
+        ParameterizedValue::Bytes(bytes) => Some(format!("\\x{}", hex(bytes))),
+        #[cfg(feature = "array")]
+        ParameterizedValue::Array(values) => Some(array_literal(values)),
+        #[cfg(feature = "json-1")]
+        ParameterizedValue::Json(json) => Some(json.to_string()),
+        #[cfg(feature = "uuid-0_7")]
+        ParameterizedValue::Uuid(uuid) => Some(uuid.to_hyphenated().to_string()),
+        #[cfg(feature = "chrono-0_4")]
+        ParameterizedValue::DateTime(dt) => Some(dt.to_rfc3339()),
+        #[cfg(feature = "chrono-0_4")]
+        ParameterizedValue::Date(date) => Some(date.to_string()),
+        #[cfg(feature = "chrono-0_4")]
+        ParameterizedValue::Time(time) => Some(time.to_string()),
+        #[cfg(feature = "rust_decimal-1")]
+        ParameterizedValue::Decimal(d) => Some(d.to_string()),
+    }
+}
+
+/// Escapes the characters with a special meaning in the text format.
+fn escape(s: &str, buffer: &mut Vec<u8>) {
+    for byte in s.bytes() {
+        match byte {
+            b'\\' => buffer.extend_from_slice(b"\\\\"),
+            b'\n' => buffer.extend_from_slice(b"\\n"),
+            b'\r' => buffer.extend_from_slice(b"\\r"),
+            b'\t' => buffer.extend_from_slice(b"\\t"),
+            b => buffer.push(b),
+        }
+    }
+}
+
+/// An array in the Postgres input syntax, e.g. `{"a","b",NULL}`.
+#[cfg(feature = "array")]
+fn array_literal(values: &[ParameterizedValue]) -> String {
+    let elements: Vec<String> = values
+        .iter()
+        .map(|value| match (value, text(value)) {
+            (_, None) => String::from("NULL"),
+            (ParameterizedValue::Array(_), Some(nested)) => nested,
+            (_, Some(s)) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        })
+        .collect();
+
+    format!("{{{}}}", elements.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(rows: Vec<Vec<ParameterizedValue<'static>>>, width: usize) -> String {
+        let mut reader = CopyReader::new(rows.into_iter(), width);
+        let mut result = String::new();
+
+        reader.read_to_string(&mut result).unwrap();
+        result
+    }
+
+    #[test]
+    fn copy_statement_quotes_the_identifiers() {
+        let statement =
+            copy_statement(&Table::from(("app", "users")), &[Column::from("id"), Column::from("name")])
+                .unwrap();
+
+        assert_eq!("COPY \"app\".\"users\" (\"id\", \"name\") FROM STDIN", statement);
+    }
+
+    #[test]
+    fn values_are_encoded_in_the_text_format() {
+        let rows = vec![
+            vec![1.into(), "musti".into(), ParameterizedValue::Null],
+            vec![2.into(), "tab\tnew\nline\\".into(), true.into()],
+        ];
+
+        assert_eq!(
+            "1\tmusti\t\\N\n2\ttab\\tnew\\nline\\\\\tt\n",
+            encode(rows, 3)
+        );
+    }
+
+    #[test]
+    fn bytes_are_encoded_as_hex() {
+        let bytes = ParameterizedValue::from(vec![0u8, 159, 255]);
+        assert_eq!("\\\\x009fff\n", encode(vec![vec![bytes]], 1));
+    }
+
+    #[cfg(feature = "array")]
+    #[test]
+    fn arrays_are_encoded_as_array_literals() {
+        let array = ParameterizedValue::Array(vec![
+            "a \"b\"".into(),
+            ParameterizedValue::Null,
+            1.into(),
+        ]);
+
+        assert_eq!("{\"a \\\\\"b\\\\\"\",NULL,\"1\"}\n", encode(vec![vec![array]], 1));
+    }
+
+    #[test]
+    fn a_row_of_the_wrong_width_aborts_the_stream() {
+        let rows = vec![vec![1.into(), 2.into()]];
+        let mut reader = CopyReader::new(rows.into_iter(), 3);
+        let mut result = String::new();
+
+        assert!(reader.read_to_string(&mut result).is_err());
+        assert!(reader.take_error().is_some());
+    }
+}
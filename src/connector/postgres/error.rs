@@ -1,91 +1,245 @@
-use crate::error::Error;
+use crate::{
+    connector::Backend,
+    error::{Error, NativeError},
+};
 
 impl From<tokio_postgres::error::Error> for Error {
     fn from(e: tokio_postgres::error::Error) -> Error {
+        use std::error::Error as _;
         use tokio_postgres::error::DbError;
 
-        match e.code().map(|c| c.code()) {
-            // Don't look at me, I'm hideous ;((
-            Some("23505") => {
-                let error = e.into_source().unwrap(); // boom
-                let db_error = error.downcast_ref::<DbError>().unwrap(); // BOOM
-                let detail = db_error.detail().unwrap(); // KA-BOOM
+        let db_error = e
+            .source()
+            .and_then(|source| source.downcast_ref::<DbError>());
 
-                let splitted: Vec<&str> = detail.split(")=(").collect();
-                let splitted: Vec<&str> = splitted[0].split(" (").collect();
-                let field_name = splitted[1].replace("\"", "");
+        if let Some(db_error) = db_error {
+            let fields = DbErrorFields::from(db_error);
+            let code = fields.code.to_string();
+            let message = fields.message.to_string();
 
-                Error::UniqueConstraintViolation { field_name }
-            }
-            // Even lipstick will not save this...
-            Some("23502") => {
-                let error = e.into_source().unwrap(); // boom
-                let db_error = error.downcast_ref::<DbError>().unwrap(); // BOOM
-                let detail = db_error.detail().unwrap(); // KA-BOOM
+            let mapped = fields.into_error();
+            let native = NativeError::new(Backend::Postgres, Some(code), Some(message), e);
 
-                let splitted: Vec<&str> = detail.split(")=(").collect();
-                let splitted: Vec<&str> = splitted[0].split(" (").collect();
-                let field_name = splitted[1].replace("\"", "");
+            return match mapped {
+                Some(mapped) => mapped.with_native(native),
+                None => native.into(),
+            };
+        }
 
-                Error::NullConstraintViolation { field_name }
-            }
-            Some("3D000") => {
-                let error = e.into_source().unwrap(); // boom
-                let db_error = error.downcast_ref::<DbError>().unwrap(); // BOOM
-                let message = db_error.message();
+        if e.is_closed() {
+            return Error::ConnectionClosed;
+        }
 
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted[1].split('"').collect();
-                let db_name = splitted[1].into();
+        // This is necessary, on top of the other conversions, for the cases where a
+        // native_tls error comes wrapped in a tokio_postgres error.
+        if let Some(tls_error) = try_extracting_tls_error(&e) {
+            return tls_error;
+        }
 
-                Error::DatabaseDoesNotExist { db_name }
-            }
-            Some("28P01") => {
-                let error = e.into_source().unwrap(); // boom
-                let db_error = error.downcast_ref::<DbError>().unwrap(); // BOOM
-                let message = db_error.message();
+        let reason = format!("{}", e);
 
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted.last().unwrap().split('"').collect();
-                let user = splitted[1].into();
+        if timed_out(&e) {
+            return if reason.starts_with("error connecting to server") {
+                Error::ConnectTimeout
+            } else {
+                Error::Timeout
+            };
+        }
 
-                Error::AuthenticationFailed { user }
+        match reason.as_str() {
+            "error connecting to server: timed out" => Error::ConnectTimeout, // sigh...
+            _ => Error::QueryError(e.into()),
+        }
+    }
+}
+
+/// True if an I/O error in the sources of the error timed out.
+fn timed_out(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = e.source();
+
+    while let Some(e) = source {
+        if let Some(io_error) = e.downcast_ref::<std::io::Error>() {
+            match io_error.kind() {
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => return true,
+                _ => (),
             }
-            Some("42P04") => {
-                let error = e.into_source().unwrap(); // boom
-                let db_error = error.downcast_ref::<DbError>().unwrap(); // BOOM
-                let message = db_error.message();
+        }
 
-                let splitted: Vec<&str> = message.split_whitespace().collect();
-                let splitted: Vec<&str> = splitted[1].split('"').collect();
-                let db_name = splitted[1].into();
+        source = e.source();
+    }
 
-                Error::DatabaseAlreadyExists { db_name }
+    false
+}
+
+/// The fields of an error sent by the server, see the [PostgreSQL
+/// documentation](https://www.postgresql.org/docs/current/protocol-error-fields.html).
+#[derive(Debug, Default)]
+pub(crate) struct DbErrorFields<'a> {
+    pub(crate) code: &'a str,
+    pub(crate) message: &'a str,
+    pub(crate) detail: Option<&'a str>,
+    pub(crate) table: Option<&'a str>,
+    pub(crate) column: Option<&'a str>,
+    pub(crate) constraint: Option<&'a str>,
+}
+
+impl<'a> From<&'a tokio_postgres::error::DbError> for DbErrorFields<'a> {
+    fn from(e: &'a tokio_postgres::error::DbError) -> Self {
+        Self {
+            code: e.code().code(),
+            message: e.message(),
+            detail: e.detail(),
+            table: e.table(),
+            column: e.column(),
+            constraint: e.constraint(),
+        }
+    }
+}
+
+impl<'a> DbErrorFields<'a> {
+    /// The error matching the SQLSTATE code, or `None` if the code has no
+    /// variant of its own or the fields lack the needed details.
+    pub(crate) fn into_error(self) -> Option<Error> {
+        match self.code {
+            // unique_violation, the detail naming the columns of the key:
+            // `Key (email, tenant)=(musti@example.com, 1) already exists.`
+            "23505" => {
+                self.detail
+                    .and_then(key_columns)
+                    .map(|columns| Error::UniqueConstraintViolation {
+                        field_name: columns.replace('"', ""),
+                        constraint: self.constraint.map(String::from),
+                        native: None,
+                    })
             }
-            _ => {
-                // This is necessary, on top of the other conversions, for the cases where a
-                // native_tls error comes wrapped in a tokio_postgres error.
-                if let Some(tls_error) = try_extracting_tls_error(&e) {
-                    return tls_error;
-                }
-
-                let reason = format!("{}", e);
-
-                match reason.as_str() {
-                    "error connecting to server: timed out" => Error::ConnectTimeout, // sigh...
-                    _ => Error::QueryError(e.into()),
-                }
+            // not_null_violation
+            "23502" => self.column.map(|column| Error::NullConstraintViolation {
+                field_name: column.into(),
+                native: None,
+            }),
+            // foreign_key_violation
+            "23503" => self
+                .constraint
+                .map(|constraint| Error::ForeignKeyConstraintViolation {
+                    constraint: constraint.into(),
+                    native: None,
+                }),
+            // check_violation
+            "23514" => self
+                .constraint
+                .map(|constraint| Error::CheckConstraintViolation {
+                    constraint: constraint.into(),
+                    native: None,
+                }),
+            // undefined_table: `relation "public.cats" does not exist`
+            "42P01" => quoted(self.message).map(|table| Error::TableDoesNotExist {
+                table: table.into(),
+                native: None,
+            }),
+            // undefined_column: `column "name" does not exist`. A qualified
+            // column is not quoted, `column cats.nme does not exist`, and
+            // falls through with the translated messages.
+            "42703" => quoted(self.message).map(|column| Error::ColumnDoesNotExist {
+                column: column.into(),
+                native: None,
+            }),
+            // query_canceled, raised when the `statement_timeout` is exceeded
+            // or the query is cancelled with a `CancelToken`. The message is
+            // translated to the language of the server, so the connector
+            // tells the two apart by the requests of its tokens.
+            "57014" => Some(Error::QueryCancelled),
+            // admin_shutdown, crash_shutdown and cannot_connect_now: the
+            // server is closing the connection, e.g. after
+            // `pg_terminate_backend`.
+            "57P01" | "57P02" | "57P03" => Some(Error::ConnectionClosed),
+            // serialization_failure and deadlock_detected
+            "40001" | "40P01" => Some(Error::TransactionWriteConflict),
+            // lock_not_available, past the `lock_timeout` or with `NOWAIT`.
+            "55P03" => Some(Error::LockTimeout),
+            // invalid_catalog_name
+            "3D000" => quoted(self.message).map(|db_name| Error::DatabaseDoesNotExist {
+                db_name: db_name.into(),
+                native: None,
+            }),
+            // invalid_schema_name
+            "3F000" => quoted(self.message).map(|schema| Error::SchemaDoesNotExist {
+                schema: schema.into(),
+                native: None,
+            }),
+            // duplicate_database
+            "42P04" => quoted(self.message).map(|db_name| Error::DatabaseAlreadyExists {
+                db_name: db_name.into(),
+                native: None,
+            }),
+            // duplicate_schema
+            "42P06" => quoted(self.message).map(|schema| Error::SchemaAlreadyExists {
+                schema: schema.into(),
+                native: None,
+            }),
+            // invalid_password and invalid_authorization_specification
+            "28P01" | "28000" => quoted_after(self.message, "user ")
+                .or_else(|| quoted_after(self.message, "role "))
+                .map(|user| Error::AuthenticationFailed {
+                    user: user.into(),
+                    native: None,
+                }),
+            // insufficient_privilege, only mapped when connecting to a
+            // database, e.g. `permission denied for database prisma`. The
+            // privileges of tables, schemas and functions fall through.
+            "42501" if self.message.starts_with("permission denied for database ") => {
+                let db_name = &self.message["permission denied for database ".len()..];
+
+                Some(Error::DatabaseAccessDenied {
+                    db_name: db_name.trim_matches('"').into(),
+                    native: None,
+                })
             }
+            _ => None,
         }
     }
 }
 
+/// The first double-quoted identifier in the message.
+fn quoted(message: &str) -> Option<&str> {
+    let start = message.find('"')? + 1;
+    let len = message[start..].find('"')?;
+
+    Some(&message[start..start + len])
+}
+
+/// The columns of the key in the detail of a unique violation.
+fn key_columns(detail: &str) -> Option<&str> {
+    if !detail.starts_with("Key (") {
+        return None;
+    }
+
+    let start = "Key (".len();
+    let len = detail[start..].find(")=(")?;
+
+    Some(&detail[start..start + len])
+}
+
+/// The double-quoted identifier following the given prefix in the message.
+fn quoted_after<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = message.find(&format!("{}\"", prefix))? + prefix.len();
+    quoted(&message[start..])
+}
+
 fn try_extracting_tls_error(err: &tokio_postgres::error::Error) -> Option<Error> {
     use std::error::Error;
 
-    err.source()
-        .and_then(|err| err.downcast_ref::<native_tls::Error>())
-        .map(|err| err.into())
+    // The TLS error can be nested arbitrarily deep in the handshake errors.
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        if let Some(tls_error) = err.downcast_ref::<native_tls::Error>() {
+            return Some(tls_error.into());
+        }
+
+        source = err.source();
+    }
+
+    None
 }
 
 impl From<native_tls::Error> for Error {
@@ -101,3 +255,266 @@ impl From<&native_tls::Error> for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(code: &'static str, message: &'static str) -> Option<Error> {
+        DbErrorFields {
+            code,
+            message,
+            ..Default::default()
+        }
+        .into_error()
+    }
+
+    #[test]
+    fn unique_violations_name_the_columns_and_the_constraint() {
+        let fields = DbErrorFields {
+            code: "23505",
+            message: "duplicate key value violates unique constraint \"users_email_tenant_key\"",
+            detail: Some("Key (email, tenant)=(musti@example.com, 1) already exists."),
+            table: Some("users"),
+            constraint: Some("users_email_tenant_key"),
+            ..Default::default()
+        };
+
+        match fields.into_error() {
+            Some(Error::UniqueConstraintViolation {
+                field_name,
+                constraint,
+                ..
+            }) => {
+                assert_eq!("email, tenant", field_name);
+                assert_eq!(
+                    Some("users_email_tenant_key"),
+                    constraint.as_ref().map(String::as_str)
+                );
+            }
+            e => panic!("Expected `UniqueConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn null_violations_name_the_column() {
+        let fields = DbErrorFields {
+            code: "23502",
+            message: "null value in column \"name\" violates not-null constraint",
+            detail: Some("Failing row contains (1, null)."),
+            table: Some("cats"),
+            column: Some("name"),
+            ..Default::default()
+        };
+
+        match fields.into_error() {
+            Some(Error::NullConstraintViolation { field_name, .. }) => {
+                assert_eq!("name", field_name)
+            }
+            e => panic!("Expected `NullConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn foreign_key_and_check_violations_name_the_constraint() {
+        let fields = DbErrorFields {
+            code: "23503",
+            message: "update or delete on table \"owners\" violates foreign key constraint \"cats_owner_id_fkey\" on table \"cats\"",
+            detail: Some("Key (id)=(1) is still referenced from table \"cats\"."),
+            table: Some("cats"),
+            constraint: Some("cats_owner_id_fkey"),
+            ..Default::default()
+        };
+
+        match fields.into_error() {
+            Some(Error::ForeignKeyConstraintViolation { constraint, .. }) => {
+                assert_eq!("cats_owner_id_fkey", constraint)
+            }
+            e => panic!("Expected `ForeignKeyConstraintViolation`, got {:?}", e),
+        }
+
+        let fields = DbErrorFields {
+            code: "23514",
+            message: "new row for relation \"cats\" violates check constraint \"lives_positive\"",
+            table: Some("cats"),
+            constraint: Some("lives_positive"),
+            ..Default::default()
+        };
+
+        match fields.into_error() {
+            Some(Error::CheckConstraintViolation { constraint, .. }) => {
+                assert_eq!("lives_positive", constraint)
+            }
+            e => panic!("Expected `CheckConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn missing_tables_and_columns_are_named() {
+        match map("42P01", "relation \"public.cats\" does not exist") {
+            Some(Error::TableDoesNotExist { table, .. }) => assert_eq!("public.cats", table),
+            e => panic!("Expected `TableDoesNotExist`, got {:?}", e),
+        }
+
+        let messages = &[
+            ("column \"nme\" does not exist", "nme"),
+            ("column \"nme\" of relation \"cats\" does not exist", "nme"),
+        ];
+
+        for &(message, expected) in messages {
+            match map("42703", message) {
+                Some(Error::ColumnDoesNotExist { column, .. }) => assert_eq!(expected, column),
+                e => panic!("Expected `ColumnDoesNotExist`, got {:?}", e),
+            }
+        }
+
+        assert!(map("42703", "column cats.nme does not exist").is_none());
+        assert!(map("42703", "la colonne cats.nme n'existe pas").is_none());
+    }
+
+    #[test]
+    fn database_errors_name_the_database() {
+        match map("3D000", "database \"my db\" does not exist") {
+            Some(Error::DatabaseDoesNotExist { db_name, .. }) => assert_eq!("my db", db_name),
+            e => panic!("Expected `DatabaseDoesNotExist`, got {:?}", e),
+        }
+
+        match map("42P04", "database \"prisma\" already exists") {
+            Some(Error::DatabaseAlreadyExists { db_name, .. }) => assert_eq!("prisma", db_name),
+            e => panic!("Expected `DatabaseAlreadyExists`, got {:?}", e),
+        }
+
+        match map("3F000", "schema \"nope\" does not exist") {
+            Some(Error::SchemaDoesNotExist { schema, .. }) => assert_eq!("nope", schema),
+            e => panic!("Expected `SchemaDoesNotExist`, got {:?}", e),
+        }
+
+        match map("42P06", "schema \"prisma\" already exists") {
+            Some(Error::SchemaAlreadyExists { schema, .. }) => assert_eq!("prisma", schema),
+            e => panic!("Expected `SchemaAlreadyExists`, got {:?}", e),
+        }
+
+        match map("42501", "permission denied for database prisma") {
+            Some(Error::DatabaseAccessDenied { db_name, .. }) => assert_eq!("prisma", db_name),
+            e => panic!("Expected `DatabaseAccessDenied`, got {:?}", e),
+        }
+
+        assert!(map("42501", "permission denied for table users").is_none());
+        assert!(map("42501", "permission denied for schema app").is_none());
+        assert!(map("42501", "must be owner of table users").is_none());
+    }
+
+    #[test]
+    fn authentication_errors_name_the_user() {
+        let messages = &[
+            ("28P01", "password authentication failed for user \"postgres\""),
+            ("28000", "role \"postgres\" does not exist"),
+            (
+                "28000",
+                "no pg_hba.conf entry for host \"10.0.0.1\", user \"postgres\", database \"prisma\", SSL off",
+            ),
+        ];
+
+        for &(code, message) in messages {
+            match map(code, message) {
+                Some(Error::AuthenticationFailed { user, .. }) => assert_eq!("postgres", user),
+                e => panic!("Expected `AuthenticationFailed`, got {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn cancelled_queries_are_not_told_apart_by_the_message() {
+        let messages = &[
+            "canceling statement due to statement timeout",
+            "canceling statement due to user request",
+            "Abbruch der Anweisung wegen Zeitüberschreitung",
+        ];
+
+        for message in messages {
+            match map("57014", message) {
+                Some(Error::QueryCancelled) => (),
+                e => panic!("Expected `QueryCancelled`, got {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn admin_shutdowns_close_the_connection() {
+        let messages = &[
+            (
+                "57P01",
+                "terminating connection due to administrator command",
+            ),
+            (
+                "57P02",
+                "terminating connection because of crash of another server process",
+            ),
+            ("57P03", "the database system is shutting down"),
+        ];
+
+        for &(code, message) in messages {
+            match map(code, message) {
+                Some(Error::ConnectionClosed) => (),
+                e => panic!("Expected `ConnectionClosed`, got {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn transient_errors_are_classified() {
+        let transient = &[
+            (
+                "40001",
+                "could not serialize access due to concurrent update",
+            ),
+            ("40P01", "deadlock detected"),
+            ("55P03", "canceling statement due to lock timeout"),
+        ];
+
+        for &(code, message) in transient {
+            assert!(map(code, message).unwrap().is_transient(), "{}", code);
+        }
+
+        let cancelled = map("57014", "canceling statement due to user request").unwrap();
+        assert!(!cancelled.is_transient());
+    }
+
+    #[test]
+    fn missing_details_fall_back_to_a_generic_error() {
+        assert!(map("23505", "duplicate key value violates unique constraint").is_none());
+        assert!(map("3D000", "la base de données n'existe pas").is_none());
+        assert!(map("42601", "syntax error at or near \"SELEC\"").is_none());
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(std::io::Error);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "error connecting to server: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for Wrapped {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn io_timeouts_in_the_sources_are_found() {
+        use std::io::{Error as IoError, ErrorKind};
+
+        let timed_out_error = Wrapped(IoError::new(ErrorKind::TimedOut, "connection timed out"));
+        let would_block = Wrapped(IoError::new(ErrorKind::WouldBlock, "resource unavailable"));
+        let refused = Wrapped(IoError::new(
+            ErrorKind::ConnectionRefused,
+            "connection refused",
+        ));
+
+        assert!(timed_out(&timed_out_error));
+        assert!(timed_out(&would_block));
+        assert!(!timed_out(&refused));
+    }
+}
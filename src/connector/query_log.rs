@@ -0,0 +1,152 @@
+use super::{ExecuteResult, ResultSet};
+use crate::{ast::ParameterizedValue, error::Error};
+use std::{fmt, sync::Arc, time::Duration};
+
+/// A query run by a connector, given to the [QueryLogger](struct.QueryLogger.html)
+/// after the query has finished.
+#[derive(Debug)]
+pub struct QueryInfo<'a> {
+    /// The SQL sent to the database.
+    pub sql: &'a str,
+    /// The number of parameters of the query.
+    pub param_count: usize,
+    /// The parameters of the query, `None` if the logger redacts them.
+    pub params: Option<&'a [ParameterizedValue<'a>]>,
+    /// The time it took to run the query.
+    pub duration: Duration,
+    /// The number of returned or affected rows, if known.
+    pub rows: Option<u64>,
+    /// The error, if the query failed.
+    pub error: Option<&'a Error>,
+}
+
+/// Something that happened to the connection of a connector outside of the
+/// queries, given to the hook set with
+/// [on_connection_event](struct.QueryLogger.html#method.on_connection_event).
+#[derive(Debug)]
+pub enum ConnectionEvent<'a> {
+    /// The connection was closed outside of a transaction. It is opened
+    /// again to retry the failed statement once.
+    Reconnecting,
+    /// Opening the connection again failed, giving the error to the caller.
+    ReconnectFailed(&'a Error),
+}
+
+/// A hook called after every query, raw command and transaction boundary run
+/// on a connection.
+///
+/// ```
+/// # use prisma_query::connector::*;
+/// # use std::sync::{Arc, Mutex};
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let collector = seen.clone();
+///
+/// let mut conn = Sqlite::new("db/test.db").unwrap();
+///
+/// conn.set_logger(QueryLogger::new(move |info: &QueryInfo| {
+///     collector.lock().unwrap().push(info.sql.to_string());
+/// }));
+///
+/// conn.query_raw("SELECT 1", &[]).unwrap();
+///
+/// assert_eq!(vec!["SELECT 1"], *seen.lock().unwrap());
+/// ```
+#[derive(Clone)]
+pub struct QueryLogger {
+    hook: Arc<dyn Fn(&QueryInfo) + Send + Sync>,
+    event_hook: Option<Arc<dyn Fn(&ConnectionEvent) + Send + Sync>>,
+    redact_params: bool,
+}
+
+impl QueryLogger {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&QueryInfo) + Send + Sync + 'static,
+    {
+        Self {
+            hook: Arc::new(hook),
+            event_hook: None,
+            redact_params: false,
+        }
+    }
+
+    /// Calls the hook when a connector reconnects, or gives up reconnecting.
+    pub fn on_connection_event<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.event_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Leaves the parameter values out of the logged queries, only giving
+    /// their count.
+    pub fn redact_params(mut self) -> Self {
+        self.redact_params = true;
+        self
+    }
+
+    pub(crate) fn log<T>(
+        &self,
+        sql: &str,
+        params: &[ParameterizedValue],
+        duration: Duration,
+        res: &crate::Result<T>,
+    ) where
+        T: RowCount,
+    {
+        let info = QueryInfo {
+            sql,
+            param_count: params.len(),
+            params: if self.redact_params { None } else { Some(params) },
+            duration,
+            rows: res.as_ref().ok().and_then(RowCount::row_count),
+            error: res.as_ref().err(),
+        };
+
+        (self.hook)(&info)
+    }
+
+    pub(crate) fn log_event(&self, event: &ConnectionEvent) {
+        if let Some(ref hook) = self.event_hook {
+            hook(event)
+        }
+    }
+}
+
+impl fmt::Debug for QueryLogger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("QueryLogger")
+            .field("redact_params", &self.redact_params)
+            .finish()
+    }
+}
+
+/// The number of rows a query returned or affected.
+pub(crate) trait RowCount {
+    fn row_count(&self) -> Option<u64>;
+}
+
+impl RowCount for ResultSet {
+    fn row_count(&self) -> Option<u64> {
+        Some(self.len() as u64)
+    }
+}
+
+impl RowCount for ExecuteResult {
+    fn row_count(&self) -> Option<u64> {
+        Some(self.rows_affected)
+    }
+}
+
+impl RowCount for u64 {
+    fn row_count(&self) -> Option<u64> {
+        Some(*self)
+    }
+}
+
+impl RowCount for () {
+    fn row_count(&self) -> Option<u64> {
+        None
+    }
+}
@@ -1,6 +1,10 @@
-use super::{ResultSet, Transaction};
-use crate::ast::*;
-use std::ops::DerefMut;
+use super::{
+    batch, script, upsert, BatchInsertResult, BatchLimits, CancelToken, Capabilities,
+    ColumnDescription, ExecuteResult, OwnedTransaction, ResultRow, ResultSet, RetryPolicy,
+    RetryingQueryable, ServerVersion, Transaction, UpdateAssignment, UpsertOutcome,
+};
+use crate::{ast::*, error::Error};
+use std::{convert::TryFrom, ops::DerefMut, time::Duration};
 
 pub trait ToRow {
     fn to_result_row(&self) -> crate::Result<Vec<ParameterizedValue<'static>>>;
@@ -13,33 +17,306 @@ pub trait ToColumnNames {
 /// Represents a connection or a transaction that can be queried.
 pub trait Queryable {
     /// Executes the given query and returns the ID of the last inserted row.
-    fn execute(&mut self, q: Query) -> crate::Result<Option<Id>>;
+    fn execute(&mut self, q: Query) -> crate::Result<Option<Id>> {
+        Ok(self.execute_with_result(q)?.last_insert_id)
+    }
+
+    /// Executes the given query, returning the number of affected rows, the
+    /// ID of the last inserted row and the rows of a `RETURNING` clause in
+    /// one [ExecuteResult](struct.ExecuteResult.html). Required, `execute`
+    /// being built on it.
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult>;
 
     /// Executes the given query and returns the result set.
     fn query(&mut self, q: Query) -> crate::Result<ResultSet>;
 
+    /// Executes the query, expecting a single row of a single column, and
+    /// converts the value to the given type.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (name TEXT); INSERT INTO cats VALUES ('musti')").unwrap();
+    ///
+    /// let count: i64 = conn
+    ///     .query_scalar(Select::from_table("cats").value(count(asterisk())).into())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(1, count);
+    /// ```
+    fn query_scalar<T>(&mut self, q: Query) -> crate::Result<T>
+    where
+        Self: Sized,
+        T: TryFrom<ParameterizedValue<'static>, Error = Error>,
+    {
+        let value = self.query(q)?.into_single()?.single_value()?;
+        T::try_from(value)
+    }
+
     /// Executes a query given as SQL, interpolating the given parameters and
     /// returning a set of results.
     fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet>;
 
+    /// Executes the given query, cancelling it with an `Error::Timeout` if it
+    /// runs longer than the timeout. Any session setting changed for the
+    /// timeout is restored afterwards.
+    ///
+    /// On Postgres the timeout is a `statement_timeout`, local to a
+    /// transaction. On MySQL it is the `max_execution_time` of the session,
+    /// limiting only `SELECT` statements, so inserts, updates and deletes fail
+    /// with `Error::UnsupportedOperation`. MariaDB uses its
+    /// `max_statement_time`, limiting every statement. SQLite interrupts the
+    /// query from a watchdog thread shared by the connections.
+    fn query_with_timeout(&mut self, _: Query, _: Duration) -> crate::Result<ResultSet> {
+        Err(Error::UnsupportedOperation("query_with_timeout"))
+    }
+
+    /// Executes the given query, calling the function with each row as it is
+    /// read from the database, without collecting a result set. Returns the
+    /// number of visited rows.
+    ///
+    /// An error from the function stops reading the rows and is returned,
+    /// leaving the connection usable. The query is not run again after
+    /// reconnecting, as some of the rows might have been visited already.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (age INTEGER)").unwrap();
+    /// conn.raw_cmd("INSERT INTO cats VALUES (3), (7)").unwrap();
+    ///
+    /// let mut total = 0;
+    ///
+    /// let visited = conn
+    ///     .query_each(Select::from_table("cats").into(), &mut |row| {
+    ///         total += row["age"].as_i64().unwrap_or(0);
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(2, visited);
+    /// assert_eq!(10, total);
+    /// ```
+    ///
+    /// Without an implementation of its own, the connector reads the whole
+    /// result set before visiting the rows.
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        let mut count = 0;
+
+        for row in self.query(q)? {
+            f(row)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
     /// Executes a query given as SQL, interpolating the given parameters and
     /// returning the number of affected rows.
     fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64>;
 
     /// Turns off all foreign key constraints.
-    fn turn_off_fk_constraints(&mut self) -> crate::Result<()>;
+    #[deprecated(note = "use `set_foreign_key_checks` or `without_foreign_keys`")]
+    fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+        self.set_foreign_key_checks(false)
+    }
 
     /// Turns on all foreign key constraints.
-    fn turn_on_fk_constraints(&mut self) -> crate::Result<()>;
+    #[deprecated(note = "use `set_foreign_key_checks` or `without_foreign_keys`")]
+    fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+        self.set_foreign_key_checks(true)
+    }
+
+    /// True if foreign keys are checked on the connection.
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        Err(Error::UnsupportedOperation("foreign_key_checks"))
+    }
+
+    /// Turns checking the foreign keys on or off for the connection. Prefer
+    /// [without_foreign_keys](#method.without_foreign_keys), which can't leave
+    /// the checks off by accident.
+    ///
+    /// SQLite can't change the checks inside of a transaction, failing with
+    /// `Error::UnsupportedOperation`.
+    fn set_foreign_key_checks(&mut self, _: bool) -> crate::Result<()> {
+        Err(Error::UnsupportedOperation("set_foreign_key_checks"))
+    }
+
+    /// Sets the schema unqualified table names resolve to: the `search_path`
+    /// of PostgreSQL or the default database of MySQL. The schema is set
+    /// again when the connection is reopened after being closed.
+    ///
+    /// A pooled connection keeps the schema when returned to the pool, so a
+    /// pool shared between tenants must set the schema every time a
+    /// connection is taken from it. In a PostgreSQL transaction, rolling
+    /// back undoes the change. SQLite has no default to change and fails.
+    fn set_schema(&mut self, _: &str) -> crate::Result<()> {
+        Err(Error::UnsupportedOperation("set_schema"))
+    }
+
+    /// A token for cancelling the queries of the connection from another
+    /// thread. Take the token before running the query.
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        Err(Error::UnsupportedOperation("cancel_token"))
+    }
+
+    /// Runs the given function with foreign key checks off, restoring the
+    /// previous state afterwards, also if the function fails. On SQLite,
+    /// fails inside of a transaction without running the function.
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE owners (id INTEGER PRIMARY KEY)").unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (owner_id INTEGER REFERENCES owners (id))").unwrap();
+    ///
+    /// conn.without_foreign_keys(|conn| conn.raw_cmd("INSERT INTO cats VALUES (1)")).unwrap();
+    ///
+    /// assert!(conn.foreign_key_checks().unwrap());
+    /// ```
+    fn without_foreign_keys<F, T>(&mut self, f: F) -> crate::Result<T>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> crate::Result<T>,
+    {
+        let enabled = self.foreign_key_checks()?;
+
+        if enabled {
+            self.set_foreign_key_checks(false)?;
+        }
+
+        let res = f(self);
+
+        if enabled {
+            let restored = self.set_foreign_key_checks(true);
+            return res.and_then(|t| restored.map(|_| t));
+        }
+
+        res
+    }
 
     /// Starts a new transaction
     fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>>;
 
+    /// Starts a new transaction owning the connection, which is handed back
+    /// when the transaction is committed or rolled back. If starting the
+    /// transaction fails, the connection is dropped.
+    fn into_transaction(self) -> crate::Result<OwnedTransaction<Self>>
+    where
+        Self: Sized,
+    {
+        OwnedTransaction::new(self)
+    }
+
+    /// Wraps the connection into a
+    /// [RetryingQueryable](struct.RetryingQueryable.html), running the
+    /// operations failing with a transient error again.
+    fn with_retries(self, policy: RetryPolicy) -> RetryingQueryable<Self>
+    where
+        Self: Sized,
+    {
+        RetryingQueryable::new(self, policy)
+    }
+
     /// Runs a command in the database, for queries that can't be run using
     /// prepared statements.
     fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()>;
 
+    /// Executes a script of semicolon-separated statements without
+    /// parameters, e.g. a migration. The first failing statement aborts the
+    /// script with an `Error::ScriptError`, holding the index of the
+    /// statement.
+    ///
+    /// The statements run one after another, so for an all-or-nothing
+    /// script, call this method on a [Transaction](struct.Transaction.html).
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// # let mut conn = Sqlite::new("db/test.db").unwrap();
+    /// let script = "CREATE TABLE script_doc (id, name); INSERT INTO script_doc VALUES (1, 'musti');";
+    /// conn.execute_script(script).unwrap();
+    ///
+    /// match conn.execute_script("SELECT 1; SELECT * FROM nope") {
+    ///     Err(prisma_query::error::Error::ScriptError { statement_index, .. }) => {
+    ///         assert_eq!(1, statement_index)
+    ///     }
+    ///     res => panic!("Expected `ScriptError`, got {:?}", res),
+    /// }
+    /// ```
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        for (i, statement) in script::split_statements(sql).into_iter().enumerate() {
+            self.raw_cmd(statement).map_err(|e| e.in_script(i))?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the connection is alive with the lightest possible round trip
+    /// to the database, failing with `Error::ConnectionClosed` if the server
+    /// is known to have closed the connection, or with
+    /// `Error::ConnectionError` or `Error::Timeout`.
+    fn ping(&mut self) -> crate::Result<()> {
+        self.raw_cmd("SELECT 1").map_err(|e| e.into_connection_error())
+    }
+
+    /// The version of the database server.
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        Err(Error::UnsupportedOperation("version"))
+    }
+
+    /// The optional features supported by the database server, depending on
+    /// its version.
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// # let mut conn = Sqlite::new("db/test.db").unwrap();
+    /// let version = conn.version().unwrap();
+    /// let capabilities = conn.capabilities().unwrap();
+    ///
+    /// assert_eq!(Backend::Sqlite, version.backend);
+    /// assert_eq!(version.at_least(3, 35, 0), capabilities.returning);
+    /// ```
+    fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        Ok(self.version()?.capabilities())
+    }
+
+    /// True if the table exists in the default schema of the connection. On
+    /// SQLite, the main and the attached databases are searched.
+    fn table_exists(&mut self, _: &str) -> crate::Result<bool> {
+        Err(Error::UnsupportedOperation("table_exists"))
+    }
+
+    /// The names of the tables in the schema, in alphabetical order.
+    fn list_tables(&mut self, _: &str) -> crate::Result<Vec<String>> {
+        Err(Error::UnsupportedOperation("list_tables"))
+    }
+
+    /// The columns of the table in the default schema of the connection, in
+    /// the order of the table definition. Empty if the table doesn't exist.
+    /// On SQLite, the main and the attached databases are searched.
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name VARCHAR(255) NOT NULL DEFAULT 'musti')").unwrap();
+    ///
+    /// let columns = conn.describe_table("cats").unwrap();
+    ///
+    /// assert_eq!("name", columns[1].name);
+    /// assert_eq!(ColumnType::Text, columns[1].column_type);
+    /// assert!(!columns[1].nullable);
+    /// assert_eq!(Some("'musti'"), columns[1].default.as_ref().map(|s| s.as_str()));
+    /// ```
+    fn describe_table(&mut self, _: &str) -> crate::Result<Vec<ColumnDescription>> {
+        Err(Error::UnsupportedOperation("describe_table"))
+    }
+
     /// Empties the given set of tables.
+    #[allow(deprecated)]
     fn empty_tables(&mut self, tables: Vec<Table>) -> crate::Result<()> {
         self.turn_off_fk_constraints()?;
 
@@ -57,6 +334,34 @@ pub trait Queryable {
         self.execute(q.into())
     }
 
+    /// Inserts a row, returning it as the database sees it, with the
+    /// generated id, defaults and changes made by triggers. Without columns,
+    /// all columns of the table are returned.
+    ///
+    /// Postgres uses a `RETURNING` clause. SQLite selects the row by its
+    /// `rowid`, and MySQL by `LAST_INSERT_ID()`, requiring an auto-increment
+    /// column. The insert and the select run in a transaction, unless one is
+    /// already open.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, lives INTEGER DEFAULT 9)").unwrap();
+    ///
+    /// let insert = Insert::single_into("cats").value("name", "musti");
+    /// let row = conn.insert_returning(insert.into(), Vec::new()).unwrap();
+    ///
+    /// assert_eq!(Some(1), row["id"].as_i64());
+    /// assert_eq!(Some(9), row["lives"].as_i64());
+    /// ```
+    fn insert_returning<'a>(
+        &mut self,
+        _: Insert<'a>,
+        _: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        Err(Error::UnsupportedOperation("insert_returning"))
+    }
+
     /// For updating data.
     fn update(&mut self, q: Update) -> crate::Result<()> {
         self.execute(q.into())?;
@@ -68,6 +373,143 @@ pub trait Queryable {
         self.execute(q.into())?;
         Ok(())
     }
+
+    /// The per-statement limits of the database, used for splitting batch
+    /// operations.
+    fn batch_limits(&self) -> BatchLimits {
+        BatchLimits::default()
+    }
+
+    /// Inserts a large amount of rows, splitting them into multi-row `INSERT`
+    /// statements the database can handle. A row is never split between two
+    /// statements.
+    ///
+    /// The statements are executed one by one, so for an all-or-nothing
+    /// insert, call this method on a [Transaction](struct.Transaction.html).
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new("db/test.db").unwrap();
+    /// # conn.raw_cmd("CREATE TABLE batch_doc (id, name)").unwrap();
+    /// let rows: Vec<Row> = (0..5000).map(|i| Row::from((i, "musti"))).collect();
+    ///
+    /// let mut tx = conn.start_transaction().unwrap();
+    /// let result = tx.insert_many("batch_doc".into(), vec!["id".into(), "name".into()], rows).unwrap();
+    /// tx.commit().unwrap();
+    ///
+    /// assert_eq!(5000, result.count);
+    /// ```
+    fn insert_many<'a>(
+        &mut self,
+        table: Table<'a>,
+        columns: Vec<Column<'a>>,
+        rows: Vec<Row<'a>>,
+    ) -> crate::Result<BatchInsertResult> {
+        let mut result = BatchInsertResult::default();
+
+        for chunk in batch::chunk_rows(rows, self.batch_limits()) {
+            let count = chunk.len() as u64;
+
+            let insert = chunk
+                .into_iter()
+                .fold(Insert::multi_into(table.clone(), columns.clone()), |insert, row| {
+                    insert.values(row)
+                });
+
+            if let Some(id) = self.insert(insert.into())? {
+                result.last_insert_ids.push(id);
+            }
+
+            result.count += count;
+        }
+
+        Ok(result)
+    }
+
+    /// Updates many rows with different values, matching the rows by the key
+    /// column. Each column is set to a `CASE` on the key, so one statement
+    /// updates as many rows as the parameter limits allow. Keys without a
+    /// row are ignored.
+    ///
+    /// Returns the number of affected rows, as counted by the database. Like
+    /// [insert_many](trait.Queryable.html#method.insert_many), the statements
+    /// are not run in a transaction of their own.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// # conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)").unwrap();
+    /// # conn.raw_cmd("INSERT INTO cats (id, name) VALUES (1, 'a'), (2, 'b')").unwrap();
+    /// let assignments = vec![
+    ///     (ParameterizedValue::from(1), vec![(Column::from("name"), DatabaseValue::from("musti"))]),
+    ///     (ParameterizedValue::from(2), vec![(Column::from("name"), DatabaseValue::from("naukio"))]),
+    ///     (ParameterizedValue::from(3), vec![(Column::from("name"), DatabaseValue::from("nobody"))]),
+    /// ];
+    ///
+    /// let count = conn.update_many("cats".into(), "id".into(), assignments).unwrap();
+    ///
+    /// assert_eq!(2, count);
+    /// ```
+    fn update_many<'a>(
+        &mut self,
+        table: Table<'a>,
+        key_column: Column<'a>,
+        assignments: Vec<UpdateAssignment<'a>>,
+    ) -> crate::Result<u64> {
+        let assignments: Vec<_> = assignments
+            .into_iter()
+            .filter(|(_, values)| !values.is_empty())
+            .collect();
+
+        let mut count = 0;
+
+        for chunk in batch::chunk_assignments(assignments, self.batch_limits()) {
+            let update = batch::update_statement(table.clone(), key_column.clone(), chunk);
+            count += self.execute_with_result(update.into())?.rows_affected;
+        }
+
+        Ok(count)
+    }
+
+    /// Inserts a row, or updates the row conflicting with it in the unique
+    /// columns. The values of the insert are available in the updates with
+    /// [excluded](../ast/fn.excluded.html).
+    ///
+    /// The outcome is reported in the `upsert` field of the result: MySQL
+    /// tells it in the number of affected rows, Postgres in the system columns
+    /// of the row, and SQLite not at all, giving `UpsertOutcome::Unknown`.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE visits (page TEXT PRIMARY KEY, count INT)").unwrap();
+    ///
+    /// for _ in 0..3 {
+    ///     conn.upsert(
+    ///         "visits".into(),
+    ///         vec!["page".into()],
+    ///         vec![("page".into(), "index".into()), ("count".into(), 1.into())],
+    ///         vec![("count".into(), excluded("count"))],
+    ///     ).unwrap();
+    /// }
+    ///
+    /// let rows = conn.query_raw("SELECT count FROM visits", &[]).unwrap();
+    /// assert_eq!(Some(1), rows.first().and_then(|row| row[0].as_i64()));
+    /// ```
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        let insert = upsert::statement(table, unique_columns, values, updates)?;
+
+        let mut result = self.execute_with_result(insert.into())?;
+        result.upsert = Some(UpsertOutcome::Unknown);
+
+        Ok(result)
+    }
 }
 
 impl<Q: Queryable> Queryable for dyn DerefMut<Target = Q> {
@@ -75,6 +517,29 @@ impl<Q: Queryable> Queryable for dyn DerefMut<Target = Q> {
         self.deref_mut().execute(q)
     }
 
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        self.deref_mut().execute_with_result(q)
+    }
+
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        self.deref_mut().insert_returning(insert, columns)
+    }
+
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        self.deref_mut()
+            .upsert(table, unique_columns, values, updates)
+    }
+
     fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
         self.deref_mut().query(q)
     }
@@ -83,18 +548,48 @@ impl<Q: Queryable> Queryable for dyn DerefMut<Target = Q> {
         self.deref_mut().query_raw(sql, params)
     }
 
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        self.deref_mut().query_with_timeout(q, timeout)
+    }
+
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        self.deref_mut().query_each(q, f)
+    }
+
     fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
         self.deref_mut().execute_raw(sql, params)
     }
 
+    #[allow(deprecated)]
     fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
         self.deref_mut().turn_off_fk_constraints()
     }
 
+    #[allow(deprecated)]
     fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
         self.deref_mut().turn_on_fk_constraints()
     }
 
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        self.deref_mut().foreign_key_checks()
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        self.deref_mut().set_foreign_key_checks(enabled)
+    }
+
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        self.deref_mut().set_schema(schema)
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        self.deref_mut().cancel_token()
+    }
+
     fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
         self.deref_mut().start_transaction()
     }
@@ -102,4 +597,276 @@ impl<Q: Queryable> Queryable for dyn DerefMut<Target = Q> {
     fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
         self.deref_mut().raw_cmd(cmd)
     }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        self.deref_mut().execute_script(sql)
+    }
+
+    fn ping(&mut self) -> crate::Result<()> {
+        self.deref_mut().ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        self.deref_mut().version()
+    }
+
+    fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        self.deref_mut().capabilities()
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        self.deref_mut().table_exists(table)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        self.deref_mut().list_tables(schema)
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        self.deref_mut().describe_table(table)
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        (**self).batch_limits()
+    }
+}
+
+/// Forwards to the boxed connection, so boxed connections can be wrapped in
+/// other `Queryable` layers.
+impl<Q: Queryable + ?Sized> Queryable for Box<Q> {
+    fn execute(&mut self, q: Query) -> crate::Result<Option<Id>> {
+        (**self).execute(q)
+    }
+
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        (**self).execute_with_result(q)
+    }
+
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        (**self).insert_returning(insert, columns)
+    }
+
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        (**self).upsert(table, unique_columns, values, updates)
+    }
+
+    fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
+        (**self).query(q)
+    }
+
+    fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet> {
+        (**self).query_raw(sql, params)
+    }
+
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        (**self).query_with_timeout(q, timeout)
+    }
+
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        (**self).query_each(q, f)
+    }
+
+    fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
+        (**self).execute_raw(sql, params)
+    }
+
+    #[allow(deprecated)]
+    fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+        (**self).turn_off_fk_constraints()
+    }
+
+    #[allow(deprecated)]
+    fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+        (**self).turn_on_fk_constraints()
+    }
+
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        (**self).foreign_key_checks()
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        (**self).set_foreign_key_checks(enabled)
+    }
+
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        (**self).set_schema(schema)
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        (**self).cancel_token()
+    }
+
+    fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
+        (**self).start_transaction()
+    }
+
+    fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        (**self).raw_cmd(cmd)
+    }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        (**self).execute_script(sql)
+    }
+
+    fn ping(&mut self) -> crate::Result<()> {
+        (**self).ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        (**self).version()
+    }
+
+    fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        (**self).capabilities()
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        (**self).table_exists(table)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        (**self).list_tables(schema)
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        (**self).describe_table(table)
+    }
+
+    fn empty_tables(&mut self, tables: Vec<Table>) -> crate::Result<()> {
+        (**self).empty_tables(tables)
+    }
+
+    fn insert(&mut self, q: Insert) -> crate::Result<Option<Id>> {
+        (**self).insert(q)
+    }
+
+    fn update(&mut self, q: Update) -> crate::Result<()> {
+        (**self).update(q)
+    }
+
+    fn delete(&mut self, q: Delete) -> crate::Result<()> {
+        (**self).delete(q)
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        (**self).batch_limits()
+    }
+
+    fn insert_many<'a>(
+        &mut self,
+        table: Table<'a>,
+        columns: Vec<Column<'a>>,
+        rows: Vec<Row<'a>>,
+    ) -> crate::Result<BatchInsertResult> {
+        (**self).insert_many(table, columns, rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fails to compile if `Queryable` stops being usable as a trait object.
+    #[allow(dead_code)]
+    fn assert_object_safe(_: &dyn Queryable) {}
+
+    /// A connector implementing only the methods without a default.
+    #[derive(Default)]
+    struct Minimal {
+        commands: Vec<String>,
+    }
+
+    impl Queryable for Minimal {
+        fn execute_with_result(&mut self, _: Query) -> crate::Result<ExecuteResult> {
+            Ok(ExecuteResult::default())
+        }
+
+        fn query(&mut self, _: Query) -> crate::Result<ResultSet> {
+            self.query_raw("", &[])
+        }
+
+        fn query_raw(&mut self, _: &str, _: &[ParameterizedValue]) -> crate::Result<ResultSet> {
+            let rows = vec![
+                vec![ParameterizedValue::Integer(1)],
+                vec![ParameterizedValue::Integer(2)],
+            ];
+            ResultSet::new(vec![String::from("id")], rows)
+        }
+
+        fn execute_raw(&mut self, _: &str, _: &[ParameterizedValue]) -> crate::Result<u64> {
+            Ok(0)
+        }
+
+        fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
+            Err(Error::UnsupportedOperation("start_transaction"))
+        }
+
+        fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+            self.commands.push(cmd.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn methods_added_later_have_defaults() {
+        let mut conn = Minimal::default();
+
+        match conn.version() {
+            Err(Error::UnsupportedOperation("version")) => (),
+            res => panic!("Expected `UnsupportedOperation`, got {:?}", res),
+        }
+
+        let mut ids = Vec::new();
+
+        let visited = conn
+            .query_each(Select::from_table("cats").into(), &mut |row| {
+                ids.push(row["id"].as_i64().unwrap());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(2, visited);
+        assert_eq!(vec![1, 2], ids);
+
+        conn.execute_script("SELECT 1; SELECT 2;").unwrap();
+        assert_eq!(vec!["SELECT 1", "SELECT 2"], conn.commands);
+    }
+
+    #[test]
+    fn execute_is_built_on_execute_with_result() {
+        let mut conn = Minimal::default();
+        let insert = Insert::single_into("cats").value("name", "Musti");
+
+        assert_eq!(None, conn.execute(insert.into()).unwrap());
+    }
+
+    #[cfg(feature = "rusqlite-0_19")]
+    #[test]
+    fn boxed_connections_are_queryable() {
+        use crate::connector::Sqlite;
+
+        fn count<Q: Queryable>(conn: &mut Q) -> usize {
+            conn.query_raw("SELECT 1", &[]).unwrap().len()
+        }
+
+        let mut conn: Box<dyn Queryable> = Box::new(Sqlite::new_in_memory().unwrap());
+        assert_eq!(1, count(&mut conn));
+
+        let mut tx = conn.start_transaction().unwrap();
+        assert_eq!(1, count(&mut tx));
+    }
 }
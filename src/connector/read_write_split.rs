@@ -0,0 +1,289 @@
+use super::{
+    BatchLimits, CancelToken, ColumnDescription, ExecuteResult, Queryable, ResultRow, ResultSet,
+    ServerVersion, Transaction,
+};
+use crate::ast::*;
+use std::time::Duration;
+
+/// Routes the queries between a primary database and a read replica:
+/// `SELECT` and `UNION ALL` queries go to the replica, everything else to the
+/// primary. The routing is decided from the query AST, so raw SQL given to
+/// [query_raw](trait.Queryable.html#tymethod.query_raw) always goes to the
+/// primary.
+///
+/// In a transaction, all statements go to the primary until the transaction
+/// ends. For reading your own writes outside of a transaction, query the
+/// [primary](#method.primary) directly.
+///
+/// ```
+/// # use prisma_query::{ast::*, connector::*};
+/// let primary = Sqlite::new_in_memory().unwrap();
+/// let replica = Sqlite::new_in_memory().unwrap();
+///
+/// let mut conn = ReadWriteSplit::new(primary, replica);
+/// conn.primary().raw_cmd("CREATE TABLE cats (name TEXT)").unwrap();
+/// conn.replica().raw_cmd("CREATE TABLE cats (name TEXT)").unwrap();
+///
+/// conn.insert(Insert::single_into("cats").value("name", "musti").into()).unwrap();
+///
+/// // The replica of the example doesn't replicate anything.
+/// let from_replica = conn.query(Select::from_table("cats").into()).unwrap();
+/// assert!(from_replica.is_empty());
+///
+/// let from_primary = conn.primary().query(Select::from_table("cats").into()).unwrap();
+/// assert_eq!(1, from_primary.len());
+/// ```
+#[derive(Debug)]
+pub struct ReadWriteSplit<P, R> {
+    primary: P,
+    replica: R,
+    in_transaction: bool,
+}
+
+impl<P, R> ReadWriteSplit<P, R>
+where
+    P: Queryable,
+    R: Queryable,
+{
+    pub fn new(primary: P, replica: R) -> Self {
+        Self {
+            primary,
+            replica,
+            in_transaction: false,
+        }
+    }
+
+    /// The primary connection, e.g. for reading rows just written.
+    pub fn primary(&mut self) -> &mut P {
+        &mut self.primary
+    }
+
+    pub fn replica(&mut self) -> &mut R {
+        &mut self.replica
+    }
+
+    pub fn into_inner(self) -> (P, R) {
+        (self.primary, self.replica)
+    }
+
+    /// True if the query only reads and no transaction is open.
+    fn reads_from_replica(&self, q: &Query) -> bool {
+        match q {
+            Query::Select(_) | Query::UnionAll(_) => !self.in_transaction,
+            _ => false,
+        }
+    }
+
+    /// Follows the transactions started and ended with raw commands, e.g. by
+    /// an [OwnedTransaction](struct.OwnedTransaction.html).
+    fn track_command(&mut self, cmd: &str, res: &crate::Result<()>) {
+        let cmd = cmd.trim_start().to_uppercase();
+
+        if cmd.starts_with("BEGIN") || cmd.starts_with("START TRANSACTION") {
+            self.in_transaction = self.in_transaction || res.is_ok();
+        } else if cmd.starts_with("COMMIT") || cmd.starts_with("ROLLBACK") {
+            self.in_transaction = false;
+        }
+    }
+}
+
+impl<P, R> Queryable for ReadWriteSplit<P, R>
+where
+    P: Queryable,
+    R: Queryable,
+{
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        self.primary.execute_with_result(q)
+    }
+
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        self.primary.insert_returning(insert, columns)
+    }
+
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        self.primary.upsert(table, unique_columns, values, updates)
+    }
+
+    fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
+        if self.reads_from_replica(&q) {
+            self.replica.query(q)
+        } else {
+            self.primary.query(q)
+        }
+    }
+
+    fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet> {
+        self.primary.query_raw(sql, params)
+    }
+
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        if self.reads_from_replica(&q) {
+            self.replica.query_with_timeout(q, timeout)
+        } else {
+            self.primary.query_with_timeout(q, timeout)
+        }
+    }
+
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        if self.reads_from_replica(&q) {
+            self.replica.query_each(q, f)
+        } else {
+            self.primary.query_each(q, f)
+        }
+    }
+
+    fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
+        self.primary.execute_raw(sql, params)
+    }
+
+    #[allow(deprecated)]
+    fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+        self.primary.turn_off_fk_constraints()
+    }
+
+    #[allow(deprecated)]
+    fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+        self.primary.turn_on_fk_constraints()
+    }
+
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        self.primary.foreign_key_checks()
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        self.primary.set_foreign_key_checks(enabled)
+    }
+
+    /// Sets the schema on both connections.
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        self.primary.set_schema(schema)?;
+        self.replica.set_schema(schema)
+    }
+
+    /// Cancels the queries running on either connection.
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        let primary = self.primary.cancel_token()?;
+        let replica = self.replica.cancel_token()?;
+
+        Ok(CancelToken::new(move || {
+            primary.cancel()?;
+            replica.cancel()
+        }))
+    }
+
+    /// A transaction of the primary.
+    fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
+        self.primary.start_transaction()
+    }
+
+    fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        let res = self.primary.raw_cmd(cmd);
+        self.track_command(cmd, &res);
+
+        res
+    }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        self.primary.execute_script(sql)
+    }
+
+    /// Pings both connections.
+    fn ping(&mut self) -> crate::Result<()> {
+        self.primary.ping()?;
+        self.replica.ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        self.primary.version()
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        self.primary.table_exists(table)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        self.primary.list_tables(schema)
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        self.primary.describe_table(table)
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        self.primary.batch_limits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::Sqlite;
+
+    fn split() -> ReadWriteSplit<Sqlite, Sqlite> {
+        let mut conn = ReadWriteSplit::new(
+            Sqlite::new_in_memory().unwrap(),
+            Sqlite::new_in_memory().unwrap(),
+        );
+
+        conn.primary()
+            .raw_cmd("CREATE TABLE cats (name TEXT); INSERT INTO cats VALUES ('primary')")
+            .unwrap();
+
+        conn.replica()
+            .raw_cmd("CREATE TABLE cats (name TEXT); INSERT INTO cats VALUES ('replica')")
+            .unwrap();
+
+        conn
+    }
+
+    fn name(conn: &mut dyn Queryable) -> Option<String> {
+        let res = conn.query(Select::from_table("cats").into()).unwrap();
+        res.first()
+            .and_then(|row| row["name"].as_str().map(String::from))
+    }
+
+    #[test]
+    fn selects_go_to_the_replica_and_writes_to_the_primary() {
+        let mut conn = split();
+        assert_eq!(Some("replica".into()), name(&mut conn));
+
+        conn.update(Update::table("cats").set("name", "updated"))
+            .unwrap();
+
+        assert_eq!(Some("replica".into()), name(&mut conn));
+        assert_eq!(Some("updated".into()), name(conn.primary()));
+    }
+
+    #[test]
+    fn transactions_are_pinned_to_the_primary() {
+        let mut conn = split();
+
+        {
+            let mut tx = conn.start_transaction().unwrap();
+            assert_eq!(Some("primary".into()), name(&mut tx));
+        }
+
+        conn.raw_cmd("BEGIN").unwrap();
+        assert_eq!(Some("primary".into()), name(&mut conn));
+
+        conn.raw_cmd("COMMIT").unwrap();
+        assert_eq!(Some("replica".into()), name(&mut conn));
+
+        let mut tx = conn.into_transaction().unwrap();
+        assert_eq!(Some("primary".into()), name(&mut tx));
+    }
+}
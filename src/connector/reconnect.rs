@@ -0,0 +1,323 @@
+use super::{ConnectionEvent, QueryLogger};
+use crate::error::Error;
+
+/// The state needed for re-establishing a dropped connection: the parameters
+/// it was opened with, and whether a transaction is open.
+#[derive(Debug)]
+pub(crate) struct Reconnect<P> {
+    params: Option<P>,
+    /// Parameters changed inside the open transaction, replacing `params`
+    /// when it commits.
+    pending: Option<P>,
+    enabled: bool,
+    in_transaction: bool,
+}
+
+impl<P> Reconnect<P> {
+    /// Reconnecting is possible with parameters, if enabled.
+    pub(crate) fn new(params: Option<P>) -> Self {
+        Self {
+            params,
+            pending: None,
+            enabled: false,
+            in_transaction: false,
+        }
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub(crate) fn params(&self) -> Option<&P> {
+        self.params.as_ref()
+    }
+
+    pub(crate) fn params_mut(&mut self) -> Option<&mut P> {
+        self.params.as_mut()
+    }
+
+    pub(crate) fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    /// Follows the transaction state from the commands run on the connection.
+    /// A failed `COMMIT` or `ROLLBACK` ends the transaction as well, dropping
+    /// the parameters changed in it.
+    pub(crate) fn track_command<T>(&mut self, cmd: &str, res: &crate::Result<T>) {
+        let cmd = cmd.trim_start().to_uppercase();
+
+        if cmd.starts_with("BEGIN") || cmd.starts_with("START TRANSACTION") {
+            self.in_transaction = self.in_transaction || res.is_ok();
+        } else if cmd.starts_with("COMMIT") || cmd.starts_with("ROLLBACK") {
+            let pending = self.pending.take();
+
+            if cmd.starts_with("COMMIT") && res.is_ok() && pending.is_some() {
+                self.params = pending;
+            }
+
+            self.in_transaction = false;
+        }
+    }
+
+    /// Reconnecting is enabled, possible with the stored parameters and
+    /// would not lose an open transaction.
+    pub(crate) fn can_reconnect(&self) -> bool {
+        self.enabled && self.params.is_some() && !self.in_transaction
+    }
+
+    fn should_reconnect(&self, error: &Error) -> bool {
+        match error {
+            Error::ConnectionClosed => self.can_reconnect(),
+            _ => false,
+        }
+    }
+}
+
+impl<P: Clone> Reconnect<P> {
+    /// The parameters to change for a setting that is rolled back with the
+    /// transaction. Inside a transaction, the change only replaces the
+    /// stored parameters once the transaction commits.
+    pub(crate) fn params_after_commit(&mut self) -> Option<&mut P> {
+        if !self.in_transaction {
+            return self.params.as_mut();
+        }
+
+        if self.pending.is_none() {
+            self.pending = self.params.clone();
+        }
+
+        self.pending.as_mut()
+    }
+}
+
+/// A connector able to open its connection again.
+pub(crate) trait Reconnectable: Sized {
+    type Params;
+
+    fn reconnect_state(&mut self) -> &mut Reconnect<Self::Params>;
+
+    /// The logger told about reconnecting.
+    fn logger(&self) -> Option<&QueryLogger>;
+
+    /// Replaces the connection with a new one opened with the stored
+    /// parameters.
+    fn reconnect(&mut self) -> crate::Result<()>;
+}
+
+/// Runs the operation, running it once more on a new connection if the
+/// connection was closed outside of a transaction.
+///
+/// The server might have applied a statement before the connection was
+/// lost, applying it twice with the retry.
+pub(crate) fn with_reconnect<C, T, F>(conn: &mut C, mut f: F) -> crate::Result<T>
+where
+    C: Reconnectable,
+    F: FnMut(&mut C) -> crate::Result<T>,
+{
+    match f(conn) {
+        Err(ref e) if conn.reconnect_state().should_reconnect(e) => {
+            log_event(conn, &ConnectionEvent::Reconnecting);
+
+            if let Err(e) = conn.reconnect() {
+                log_event(conn, &ConnectionEvent::ReconnectFailed(&e));
+                return Err(e);
+            }
+
+            f(conn)
+        }
+        res => res,
+    }
+}
+
+fn log_event<C: Reconnectable>(conn: &C, event: &ConnectionEvent) {
+    if let Some(logger) = conn.logger() {
+        logger.log_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    struct Flaky {
+        reconnect: Reconnect<()>,
+        logger: Option<QueryLogger>,
+        closed: bool,
+        reconnect_fails: bool,
+        reconnects: usize,
+        runs: usize,
+    }
+
+    impl Flaky {
+        fn new(closed: bool) -> Self {
+            let mut reconnect = Reconnect::new(Some(()));
+            reconnect.set_enabled(true);
+
+            Self {
+                reconnect,
+                logger: None,
+                closed,
+                reconnect_fails: false,
+                reconnects: 0,
+                runs: 0,
+            }
+        }
+
+        fn run(&mut self) -> crate::Result<()> {
+            with_reconnect(self, |conn| {
+                conn.runs += 1;
+
+                if conn.closed {
+                    Err(Error::ConnectionClosed)
+                } else {
+                    Ok(())
+                }
+            })
+        }
+
+        /// Collects the events as their debug output.
+        fn log_events(&mut self) -> Arc<Mutex<Vec<String>>> {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let collector = events.clone();
+
+            let logger = QueryLogger::new(|_| ()).on_connection_event(move |event| {
+                collector.lock().unwrap().push(format!("{:?}", event));
+            });
+
+            self.logger = Some(logger);
+            events
+        }
+    }
+
+    impl Reconnectable for Flaky {
+        type Params = ();
+
+        fn reconnect_state(&mut self) -> &mut Reconnect<()> {
+            &mut self.reconnect
+        }
+
+        fn logger(&self) -> Option<&QueryLogger> {
+            self.logger.as_ref()
+        }
+
+        fn reconnect(&mut self) -> crate::Result<()> {
+            self.reconnects += 1;
+
+            if self.reconnect_fails {
+                Err(Error::ConnectTimeout)
+            } else {
+                self.closed = false;
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn parameters_changed_in_a_transaction_wait_for_the_commit() {
+        let mut reconnect = Reconnect::new(Some(1));
+
+        reconnect.track_command("BEGIN", &Ok(()));
+        *reconnect.params_after_commit().unwrap() = 2;
+        assert_eq!(Some(&1), reconnect.params());
+
+        reconnect.track_command("COMMIT", &Ok(()));
+        assert_eq!(Some(&2), reconnect.params());
+
+        reconnect.track_command("BEGIN", &Ok(()));
+        *reconnect.params_after_commit().unwrap() = 3;
+        reconnect.track_command("ROLLBACK", &Ok(()));
+        assert_eq!(Some(&2), reconnect.params());
+
+        *reconnect.params_after_commit().unwrap() = 4;
+        assert_eq!(Some(&4), reconnect.params());
+    }
+
+    #[test]
+    fn an_operation_failing_on_a_closed_connection_is_retried_once() {
+        let mut conn = Flaky::new(true);
+        let events = conn.log_events();
+
+        conn.run().unwrap();
+
+        assert_eq!(1, conn.reconnects);
+        assert_eq!(2, conn.runs);
+        assert_eq!(vec!["Reconnecting"], *events.lock().unwrap());
+    }
+
+    #[test]
+    fn a_connection_closed_again_is_not_retried_twice() {
+        let mut conn = Flaky::new(false);
+
+        let res = with_reconnect(&mut conn, |conn| {
+            conn.runs += 1;
+            Err::<(), _>(Error::ConnectionClosed)
+        });
+
+        match res {
+            Err(Error::ConnectionClosed) => (),
+            res => panic!("Expected `ConnectionClosed`, got {:?}", res),
+        }
+
+        assert_eq!(2, conn.runs);
+        assert_eq!(1, conn.reconnects);
+    }
+
+    #[test]
+    fn giving_up_reconnecting_is_logged() {
+        let mut conn = Flaky::new(true);
+        conn.reconnect_fails = true;
+        let events = conn.log_events();
+
+        match conn.run() {
+            Err(Error::ConnectTimeout) => (),
+            res => panic!("Expected `ConnectTimeout`, got {:?}", res),
+        }
+
+        assert_eq!(1, conn.runs);
+
+        assert_eq!(
+            vec!["Reconnecting", "ReconnectFailed(ConnectTimeout)"],
+            *events.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut conn = Flaky::new(false);
+
+        let res = with_reconnect(&mut conn, |conn| {
+            conn.runs += 1;
+            Err::<(), _>(Error::Timeout)
+        });
+
+        assert!(res.is_err());
+        assert_eq!(1, conn.runs);
+        assert_eq!(0, conn.reconnects);
+    }
+
+    #[test]
+    fn a_closed_connection_is_not_reopened_in_a_transaction() {
+        let mut conn = Flaky::new(true);
+        conn.reconnect.track_command("BEGIN", &Ok(()));
+
+        assert!(conn.run().is_err());
+        assert_eq!(1, conn.runs);
+        assert_eq!(0, conn.reconnects);
+
+        conn.reconnect.track_command("ROLLBACK", &Ok(()));
+
+        conn.run().unwrap();
+        assert_eq!(1, conn.reconnects);
+    }
+
+    #[test]
+    fn reconnecting_is_opt_in() {
+        let mut conn = Flaky::new(true);
+        conn.reconnect.set_enabled(false);
+
+        assert!(conn.run().is_err());
+        assert_eq!(1, conn.runs);
+        assert_eq!(0, conn.reconnects);
+    }
+}
@@ -4,9 +4,10 @@ mod result_row;
 pub use index::*;
 pub use result_row::*;
 
-use crate::ast::ParameterizedValue;
+use crate::{ast::ParameterizedValue, error::Error};
 use std::{
     collections::{btree_map::Keys, BTreeMap},
+    slice,
     sync::Arc,
 };
 
@@ -14,34 +15,84 @@ use std::{
 use serde_json::{Map, Value};
 
 /// Encapsulates a set of results and their respective column names.
+///
+/// The column names and their lookup map are stored once and shared by the
+/// rows, and the values of all rows are stored one row after another in a
+/// single vector.
 #[derive(Debug)]
 pub struct ResultSet {
-    pub(crate) rows: Vec<Vec<ParameterizedValue<'static>>>,
+    pub(crate) columns: Arc<[String]>,
     pub(crate) name_to_index: Arc<BTreeMap<String, usize>>,
+    pub(crate) values: Vec<ParameterizedValue<'static>>,
+    pub(crate) len: usize,
 }
 
 impl ResultSet {
-    /// Creates a new instance, bound to the given column names and result rows.
-    pub fn new(names: Vec<String>, rows: Vec<Vec<ParameterizedValue<'static>>>) -> ResultSet {
-        ResultSet {
-            name_to_index: Arc::new(Self::build_name_map(names)),
-            rows,
+    /// Creates a new instance, bound to the given column names and result
+    /// rows. Fails with `Error::RowWidthMismatch` if a row doesn't have a
+    /// value for every column.
+    ///
+    /// ```
+    /// # use prisma_query::{connector::*, error::Error};
+    /// let names = vec!["id".to_string(), "name".to_string()];
+    ///
+    /// match ResultSet::new(names, vec![vec![1.into()]]) {
+    ///     Err(Error::RowWidthMismatch { expected: 2, found: 1 }) => (),
+    ///     res => panic!("Expected a row width mismatch, got {:?}", res),
+    /// }
+    /// ```
+    pub fn new(
+        names: Vec<String>,
+        rows: Vec<Vec<ParameterizedValue<'static>>>,
+    ) -> crate::Result<ResultSet> {
+        let mut result = ResultSet {
+            name_to_index: Arc::new(Self::build_name_map(&names)),
+            columns: names.into(),
+            values: Vec::new(),
+            len: 0,
+        };
+
+        for row in rows {
+            result.push(row)?;
         }
+
+        Ok(result)
     }
 
-    /// An iterator of column names.
+    /// Adds a row to the end of the set.
+    pub(crate) fn push(&mut self, row: Vec<ParameterizedValue<'static>>) -> crate::Result<()> {
+        if row.len() != self.columns.len() {
+            return Err(Error::RowWidthMismatch {
+                expected: self.columns.len(),
+                found: row.len(),
+            });
+        }
+
+        self.values.extend(row);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// An iterator of column names, in alphabetical order. See
+    /// [column_names](#method.column_names) for the order of the query.
     pub fn columns(&self) -> Keys<'_, String, usize> {
         self.name_to_index.keys()
     }
 
+    /// An iterator of column names, in the order of the columns.
+    pub fn column_names(&self) -> slice::Iter<'_, String> {
+        self.columns.iter()
+    }
+
     /// Returns the number of rows in the `ResultSet`.
     pub fn len(&self) -> usize {
-        self.rows.len()
+        self.len
     }
 
     /// Returns true if the `ResultSet` contains no rows.
     pub fn is_empty(&self) -> bool {
-        self.rows.is_empty()
+        self.len == 0
     }
 
     /// Returns the first row of the `ResultSet`, or None if the set is empty.
@@ -49,21 +100,51 @@ impl ResultSet {
         self.get(0)
     }
 
+    /// The only row of the set, for queries expected to return exactly one
+    /// row. Fails with `Error::NotFound` if there are no rows, and with
+    /// `Error::TooManyRows` if there are more than one.
+    ///
+    /// ```
+    /// # use prisma_query::{connector::*, error::Error};
+    /// let names = vec!["id".to_string()];
+    ///
+    /// let result_set = ResultSet::new(names.clone(), vec![vec![1.into()]]).unwrap();
+    /// assert_eq!(Some(1), result_set.into_single().unwrap()[0].as_i64());
+    ///
+    /// match ResultSet::new(names, Vec::new()).unwrap().into_single() {
+    ///     Err(Error::NotFound) => (),
+    ///     res => panic!("Expected no rows, got {:?}", res),
+    /// }
+    /// ```
+    pub fn into_single(self) -> crate::Result<ResultRow> {
+        match self.len {
+            0 => Err(Error::NotFound),
+            1 => Ok(self.into_iter().next().unwrap()),
+            count => Err(Error::TooManyRows { count }),
+        }
+    }
+
     /// Returns a reference to a row in a given position.
     pub fn get(&self, index: usize) -> Option<ResultRowRef> {
-        self.rows.get(index).map(|row| ResultRowRef {
-            name_to_index: Arc::clone(&self.name_to_index),
-            values: row,
+        if index >= self.len {
+            return None;
+        }
+
+        let width = self.columns.len();
+
+        Some(ResultRowRef {
+            name_to_index: &self.name_to_index,
+            values: &self.values[index * width..(index + 1) * width],
         })
     }
 
     /// Creates a lookup map for column names.
-    fn build_name_map(names: Vec<String>) -> BTreeMap<String, usize> {
+    pub(crate) fn build_name_map(names: &[String]) -> BTreeMap<String, usize> {
         names
-            .into_iter()
+            .iter()
             .enumerate()
             .fold(BTreeMap::new(), |mut acc, (i, name)| {
-                acc.insert(name, i);
+                acc.insert(name.clone(), i);
                 acc
             })
     }
@@ -76,7 +157,9 @@ impl IntoIterator for ResultSet {
     fn into_iter(self) -> Self::IntoIter {
         ResultSetIterator {
             name_to_index: self.name_to_index,
-            internal_iterator: self.rows.into_iter(),
+            width: self.columns.len(),
+            remaining: self.len,
+            internal_iterator: self.values.into_iter(),
         }
     }
 }
@@ -85,27 +168,36 @@ impl IntoIterator for ResultSet {
 /// Might become lazy one day.
 pub struct ResultSetIterator {
     pub(crate) name_to_index: Arc<BTreeMap<String, usize>>,
-    pub(crate) internal_iterator: std::vec::IntoIter<Vec<ParameterizedValue<'static>>>,
+    pub(crate) width: usize,
+    pub(crate) remaining: usize,
+    pub(crate) internal_iterator: std::vec::IntoIter<ParameterizedValue<'static>>,
 }
 
 impl Iterator for ResultSetIterator {
     type Item = ResultRow;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.internal_iterator.next() {
-            Some(row) => Some(ResultRow {
-                name_to_index: Arc::clone(&self.name_to_index),
-                values: row,
-            }),
-            None => None,
+        if self.remaining == 0 {
+            return None;
         }
+
+        self.remaining -= 1;
+
+        let values = self.internal_iterator.by_ref().take(self.width).collect();
+        Some(ResultRow::new(Arc::clone(&self.name_to_index), values))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
+impl ExactSizeIterator for ResultSetIterator {}
+
 #[cfg(feature = "json-1")]
 impl From<ResultSet> for Value {
     fn from(result_set: ResultSet) -> Self {
-        let columns: Vec<String> = result_set.columns().map(ToString::to_string).collect();
+        let columns: Vec<String> = result_set.column_names().map(ToString::to_string).collect();
         let mut result = Vec::new();
 
         for row in result_set.into_iter() {
@@ -122,3 +214,154 @@ impl From<ResultSet> for Value {
         Value::Array(result)
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    /// Counts the allocations of the current thread, so the tests running
+    /// in parallel don't disturb each other.
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = Cell::new(0);
+        static LIVE_ALLOCATIONS: Cell<isize> = Cell::new(0);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            let _ = LIVE_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let _ = LIVE_ALLOCATIONS.try_with(|count| count.set(count.get() - 1));
+
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    pub(crate) fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    pub(crate) fn live_allocations() -> isize {
+        LIVE_ALLOCATIONS.with(Cell::get)
+    }
+
+    const COLUMNS: usize = 50;
+    const ROWS: usize = 1000;
+
+    fn names() -> Vec<String> {
+        (0..COLUMNS).map(|i| format!("column_{}", i)).collect()
+    }
+
+    #[test]
+    fn rows_are_stored_without_a_heap_block_each() {
+        let names = names();
+        let live_before = live_allocations();
+
+        let mut result = ResultSet::new(names, Vec::new()).unwrap();
+
+        for i in 0..ROWS {
+            let row = (0..COLUMNS)
+                .map(|j| ParameterizedValue::from((i * COLUMNS + j) as i64))
+                .collect();
+
+            result.push(row).unwrap();
+        }
+
+        // The names, their map and the value vector, instead of a block per
+        // row as with a vector of rows.
+        let retained = live_allocations() - live_before;
+        assert!(retained < 2 * COLUMNS as isize + 10, "{}", retained);
+
+        assert_eq!(ROWS, result.len());
+        assert_eq!(
+            Some(&ParameterizedValue::from((ROWS * COLUMNS - 1) as i64)),
+            result
+                .get(ROWS - 1)
+                .and_then(|row| row.at(COLUMNS - 1).cloned())
+                .as_ref()
+        );
+    }
+
+    #[test]
+    fn reading_rows_does_not_allocate() {
+        let rows = (0..ROWS)
+            .map(|i| vec![ParameterizedValue::from(i as i64); COLUMNS])
+            .collect();
+
+        let result = ResultSet::new(names(), rows).unwrap();
+        let before = allocations();
+
+        let mut sum = 0;
+
+        for i in 0..result.len() {
+            let row = result.get(i).unwrap();
+            sum += row["column_0"].as_i64().unwrap() + row[COLUMNS - 1].as_i64().unwrap();
+        }
+
+        assert_eq!(0, allocations() - before);
+        assert_eq!((ROWS * (ROWS - 1)) as i64, sum);
+    }
+
+    #[test]
+    fn into_single_requires_exactly_one_row() {
+        let result = |count: i64| {
+            let rows = (0..count).map(|i| vec![i.into()]).collect();
+            ResultSet::new(vec!["id".to_string()], rows)
+                .unwrap()
+                .into_single()
+        };
+
+        match result(0) {
+            Err(Error::NotFound) => (),
+            res => panic!("Expected no rows, got {:?}", res),
+        }
+
+        assert_eq!(Some(0), result(1).unwrap()[0].as_i64());
+
+        match result(2) {
+            Err(Error::TooManyRows { count: 2 }) => (),
+            res => panic!("Expected too many rows, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn columns_are_in_the_order_of_the_query() {
+        let names = vec!["b".to_string(), "a".to_string()];
+        let result = ResultSet::new(names, vec![vec![1.into(), 2.into()]]).unwrap();
+
+        assert_eq!(vec!["b", "a"], result.column_names().collect::<Vec<_>>());
+        assert_eq!(vec!["a", "b"], result.columns().collect::<Vec<_>>());
+
+        let rows: Vec<_> = result.into_iter().collect();
+        assert_eq!(1, rows.len());
+        assert_eq!(Some(2), rows[0]["a"].as_i64());
+    }
+
+    #[test]
+    fn rows_must_have_a_value_for_every_column() {
+        let mut result = ResultSet::new(vec!["id".to_string()], Vec::new()).unwrap();
+
+        match result.push(vec![1.into(), 2.into()]) {
+            Err(Error::RowWidthMismatch {
+                expected: 1,
+                found: 2,
+            }) => (),
+            res => panic!("Expected a row width mismatch, got {:?}", res),
+        }
+
+        assert!(result.is_empty());
+    }
+}
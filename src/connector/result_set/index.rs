@@ -1,4 +1,4 @@
-use super::{ResultRow, ResultRowRef};
+use super::{BorrowedRow, ResultRow, ResultRowRef};
 use crate::ast::ParameterizedValue;
 use std::ops;
 
@@ -37,6 +37,18 @@ impl ValueIndex<ResultRow, ParameterizedValue<'static>> for &str {
     }
 }
 
+impl<'a> ValueIndex<BorrowedRow<'a>, ParameterizedValue<'a>> for usize {
+    fn index_into<'v>(self, row: &'v BorrowedRow<'a>) -> &'v ParameterizedValue<'a> {
+        row.at(self).unwrap()
+    }
+}
+
+impl<'a> ValueIndex<BorrowedRow<'a>, ParameterizedValue<'a>> for &str {
+    fn index_into<'v>(self, row: &'v BorrowedRow<'a>) -> &'v ParameterizedValue<'a> {
+        row.get(self).unwrap()
+    }
+}
+
 impl<'a, I: ValueIndex<ResultRowRef<'a>, ParameterizedValue<'static>> + 'static> ops::Index<I>
     for ResultRowRef<'a>
 {
@@ -54,3 +66,13 @@ impl<I: ValueIndex<ResultRow, ParameterizedValue<'static>> + 'static> ops::Index
         index.index_into(self)
     }
 }
+
+impl<'a, I: ValueIndex<BorrowedRow<'a>, ParameterizedValue<'a>> + 'static> ops::Index<I>
+    for BorrowedRow<'a>
+{
+    type Output = ParameterizedValue<'a>;
+
+    fn index(&self, index: I) -> &ParameterizedValue<'a> {
+        index.index_into(self)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ast::ParameterizedValue;
+use crate::{ast::ParameterizedValue, error::Error};
 use std::{collections::BTreeMap, sync::Arc};
 
 /// An owned version of a `Row` in a `ResultSet`. See
@@ -26,7 +26,7 @@ impl IntoIterator for ResultRow {
 /// let names = vec!["id".to_string(), "name".to_string()];
 /// let rows = vec![vec!["1234".into(), "Musti".into()]];
 ///
-/// let result_set = ResultSet::new(names, rows);
+/// let result_set = ResultSet::new(names, rows).unwrap();
 /// let row = result_set.first().unwrap();
 ///
 /// assert_eq!(row[0], row["id"]);
@@ -34,11 +34,31 @@ impl IntoIterator for ResultRow {
 /// ```
 #[derive(Debug)]
 pub struct ResultRowRef<'a> {
-    pub(crate) name_to_index: Arc<BTreeMap<String, usize>>,
-    pub(crate) values: &'a Vec<ParameterizedValue<'static>>,
+    pub(crate) name_to_index: &'a BTreeMap<String, usize>,
+    pub(crate) values: &'a [ParameterizedValue<'static>],
+}
+
+/// A row borrowing its text and binary data from the database driver, valid
+/// only while the row is being read. Accessed like a
+/// [ResultRowRef](struct.ResultRowRef.html), and copied into an owned row
+/// with [into_owned](#method.into_owned) to keep it.
+#[derive(Debug)]
+pub struct BorrowedRow<'a> {
+    pub(crate) name_to_index: &'a Arc<BTreeMap<String, usize>>,
+    pub(crate) values: Vec<ParameterizedValue<'a>>,
 }
 
 impl ResultRow {
+    pub(crate) fn new(
+        name_to_index: Arc<BTreeMap<String, usize>>,
+        values: Vec<ParameterizedValue<'static>>,
+    ) -> Self {
+        Self {
+            name_to_index,
+            values,
+        }
+    }
+
     /// Take a value from a certain position in the row, if having a value in
     /// that position. Usage documentation in
     /// [ResultRowRef](struct.ResultRowRef.html).
@@ -60,10 +80,27 @@ impl ResultRow {
         }
     }
 
+    /// The value of a row of exactly one column, e.g. of a `COUNT(*)` query.
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// let result_set = ResultSet::new(vec!["count".to_string()], vec![vec![3.into()]]).unwrap();
+    /// let value = result_set.into_single().unwrap().single_value().unwrap();
+    ///
+    /// assert_eq!(Some(3), value.as_i64());
+    /// ```
+    pub fn single_value(self) -> crate::Result<ParameterizedValue<'static>> {
+        match self.values.len() {
+            0 => Err(Error::ResultIndexOutOfBounds(0)),
+            1 => Ok(self.values.into_iter().next().unwrap()),
+            _ => Err(Error::ConversionError("The row has more than one column")),
+        }
+    }
+
     /// Make a referring [ResultRowRef](struct.ResultRowRef.html).
     pub fn as_ref(&self) -> ResultRowRef {
         ResultRowRef {
-            name_to_index: Arc::clone(&self.name_to_index),
+            name_to_index: &self.name_to_index,
             values: &self.values,
         }
     }
@@ -77,7 +114,7 @@ impl<'a> ResultRowRef<'a> {
     /// # use prisma_query::connector::*;
     /// # let names = vec!["id".to_string(), "name".to_string()];
     /// # let rows = vec![vec!["1234".into(), "Musti".into()]];
-    /// # let result_set = ResultSet::new(names, rows);
+    /// # let result_set = ResultSet::new(names, rows).unwrap();
     /// # let row = result_set.first().unwrap();
     /// assert_eq!(Some(&row[0]), row.at(0));
     /// ```
@@ -95,7 +132,7 @@ impl<'a> ResultRowRef<'a> {
     /// # use prisma_query::connector::*;
     /// # let names = vec!["id".to_string(), "name".to_string()];
     /// # let rows = vec![vec!["1234".into(), "Musti".into()]];
-    /// # let result_set = ResultSet::new(names, rows);
+    /// # let result_set = ResultSet::new(names, rows).unwrap();
     /// # let row = result_set.first().unwrap();
     /// assert_eq!(Some(&row["id"]), row.get("id"));
     /// ```
@@ -107,3 +144,46 @@ impl<'a> ResultRowRef<'a> {
         }
     }
 }
+
+impl<'a> BorrowedRow<'a> {
+    pub(crate) fn new(
+        name_to_index: &'a Arc<BTreeMap<String, usize>>,
+        values: Vec<ParameterizedValue<'a>>,
+    ) -> Self {
+        Self {
+            name_to_index,
+            values,
+        }
+    }
+
+    /// Take a value from a certain position in the row, if having a value in
+    /// that position.
+    pub fn at(&self, i: usize) -> Option<&ParameterizedValue<'a>> {
+        self.values.get(i)
+    }
+
+    /// Take a value with the given column name from the row.
+    pub fn get(&self, name: &str) -> Option<&ParameterizedValue<'a>> {
+        self.name_to_index.get(name).map(|idx| &self.values[*idx])
+    }
+
+    /// Copies the borrowed data into an owned row.
+    pub fn into_owned(self) -> ResultRow {
+        let values = self
+            .values
+            .into_iter()
+            .map(ParameterizedValue::into_owned)
+            .collect();
+
+        ResultRow::new(Arc::clone(self.name_to_index), values)
+    }
+}
+
+impl<'a> IntoIterator for BorrowedRow<'a> {
+    type Item = ParameterizedValue<'a>;
+    type IntoIter = std::vec::IntoIter<ParameterizedValue<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
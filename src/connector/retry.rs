@@ -0,0 +1,563 @@
+use super::{
+    BatchLimits, CancelToken, ColumnDescription, ExecuteResult, Queryable, ResultRow, ResultSet,
+    ServerVersion, Transaction,
+};
+use crate::ast::*;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How many times and how often the operations failing with a
+/// [transient](../error/enum.Error.html#method.is_transient) error are run.
+///
+/// The wait before each retry doubles, starting from `initial_backoff` up to
+/// `max_backoff`, and is randomized to between half and all of it, so that
+/// the connections in a deadlock don't retry at the same moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of attempts, the first one included.
+    pub max_attempts: u32,
+    /// The wait before the first retry.
+    pub initial_backoff: Duration,
+    /// The upper limit of the wait.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The wait before the given retry, counting from one.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        let half = backoff / 2;
+        let jitter = random() % (backoff.as_nanos() as u64 - half.as_nanos() as u64 + 1);
+
+        half + Duration::from_nanos(jitter)
+    }
+
+    /// Runs the operation until it succeeds, fails with an error that is not
+    /// transient, or the attempts run out.
+    pub(crate) fn run<T, F>(&self, mut f: F) -> crate::Result<T>
+    where
+        F: FnMut() -> crate::Result<T>,
+    {
+        let mut attempt = 1;
+
+        loop {
+            match f() {
+                Err(ref e) if e.is_transient() && attempt < self.max_attempts => {
+                    #[cfg(not(feature = "tracing-log"))]
+                    warn!("Attempt {} failed, retrying: {}", attempt, e);
+                    #[cfg(feature = "tracing-log")]
+                    tracing::warn!(message = "Attempt failed, retrying", attempt, error = %e);
+
+                    thread::sleep(self.backoff(attempt));
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+/// A pseudo-random number for the jitter, from the randomly seeded hasher of
+/// the standard library.
+fn random() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(nanos);
+    hasher.finish()
+}
+
+/// Runs the operations of the wrapped connection again when they fail with a
+/// [transient](../error/enum.Error.html#method.is_transient) error, such as
+/// a deadlock, a serialization failure, a timeout waiting for a lock or a
+/// closed connection, following the [RetryPolicy](struct.RetryPolicy.html).
+///
+/// Nothing is retried in a transaction, as the database has rolled it back
+/// already. Use [transaction_with_retries](#method.transaction_with_retries)
+/// to run the whole transaction again instead. Neither is
+/// [query_each](trait.Queryable.html#tymethod.query_each), having already
+/// given rows to the callback, nor a script.
+///
+/// A write that failed due to a closed connection might have been committed
+/// before the connection closed, so retried writes should be idempotent.
+///
+/// ```
+/// # use prisma_query::{ast::*, connector::*};
+/// let mut conn = Sqlite::new_in_memory().unwrap().with_retries(RetryPolicy::default());
+/// conn.raw_cmd("CREATE TABLE cats (name TEXT)").unwrap();
+///
+/// let name = conn
+///     .transaction_with_retries(|tx| {
+///         tx.insert(Insert::single_into("cats").value("name", "musti").into())?;
+///         Ok("musti")
+///     })
+///     .unwrap();
+///
+/// assert_eq!("musti", name);
+/// ```
+#[derive(Debug)]
+pub struct RetryingQueryable<Q> {
+    inner: Q,
+    policy: RetryPolicy,
+    in_transaction: bool,
+}
+
+impl<Q> RetryingQueryable<Q>
+where
+    Q: Queryable,
+{
+    pub fn new(inner: Q, policy: RetryPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            in_transaction: false,
+        }
+    }
+
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    pub fn get_mut(&mut self) -> &mut Q {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> Q {
+        self.inner
+    }
+
+    /// Runs the closure in a transaction, committing it if the closure
+    /// succeeds. If the closure or the commit fails with a transient error,
+    /// the transaction is rolled back and the closure is run again in a new
+    /// one.
+    pub fn transaction_with_retries<F, T>(&mut self, mut f: F) -> crate::Result<T>
+    where
+        F: FnMut(&mut Transaction) -> crate::Result<T>,
+    {
+        let inner = &mut self.inner;
+
+        self.policy.run(|| {
+            let mut tx = inner.start_transaction()?;
+            let res = f(&mut tx)?;
+            tx.commit()?;
+
+            Ok(res)
+        })
+    }
+
+    /// Runs the operation following the policy, or only once in a
+    /// transaction.
+    fn retry<T, F>(&mut self, mut f: F) -> crate::Result<T>
+    where
+        F: FnMut(&mut Q) -> crate::Result<T>,
+    {
+        let inner = &mut self.inner;
+
+        if self.in_transaction {
+            f(inner)
+        } else {
+            self.policy.run(|| f(inner))
+        }
+    }
+
+    /// Follows the transactions started and ended with raw commands, e.g. by
+    /// an [OwnedTransaction](struct.OwnedTransaction.html).
+    fn track_command(&mut self, cmd: &str, res: &crate::Result<()>) {
+        let cmd = cmd.trim_start().to_uppercase();
+
+        if cmd.starts_with("BEGIN") || cmd.starts_with("START TRANSACTION") {
+            self.in_transaction = self.in_transaction || res.is_ok();
+        } else if cmd.starts_with("COMMIT") || cmd.starts_with("ROLLBACK") {
+            self.in_transaction = false;
+        }
+    }
+}
+
+impl<Q> Queryable for RetryingQueryable<Q>
+where
+    Q: Queryable,
+{
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        self.retry(|conn| conn.execute_with_result(q.clone()))
+    }
+
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        self.retry(|conn| conn.insert_returning(insert.clone(), columns.clone()))
+    }
+
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        self.retry(|conn| {
+            conn.upsert(
+                table.clone(),
+                unique_columns.clone(),
+                values.clone(),
+                updates.clone(),
+            )
+        })
+    }
+
+    fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
+        self.retry(|conn| conn.query(q.clone()))
+    }
+
+    fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet> {
+        self.retry(|conn| conn.query_raw(sql, params))
+    }
+
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        self.retry(|conn| conn.query_with_timeout(q.clone(), timeout))
+    }
+
+    /// Not retried, the callback might have seen some of the rows already.
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        self.inner.query_each(q, f)
+    }
+
+    fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
+        self.retry(|conn| conn.execute_raw(sql, params))
+    }
+
+    #[allow(deprecated)]
+    fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+        self.retry(|conn| conn.turn_off_fk_constraints())
+    }
+
+    #[allow(deprecated)]
+    fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+        self.retry(|conn| conn.turn_on_fk_constraints())
+    }
+
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        self.retry(|conn| conn.foreign_key_checks())
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        self.retry(|conn| conn.set_foreign_key_checks(enabled))
+    }
+
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        self.retry(|conn| conn.set_schema(schema))
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        self.retry(|conn| conn.cancel_token())
+    }
+
+    /// A transaction of the wrapped connection, its statements not retried.
+    fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
+        self.inner.start_transaction()
+    }
+
+    fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        let res = self.retry(|conn| conn.raw_cmd(cmd));
+        self.track_command(cmd, &res);
+
+        res
+    }
+
+    /// Not retried, some of the statements might have been run already.
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        self.inner.execute_script(sql)
+    }
+
+    /// Not retried, to report the state of the connection as it is.
+    fn ping(&mut self) -> crate::Result<()> {
+        self.inner.ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        self.retry(|conn| conn.version())
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        self.retry(|conn| conn.table_exists(table))
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        self.retry(|conn| conn.list_tables(schema))
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        self.retry(|conn| conn.describe_table(table))
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        self.inner.batch_limits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connector::Sqlite, error::Error};
+
+    /// Fails the given number of statements with the error before letting
+    /// them through to SQLite.
+    struct Flaky {
+        inner: Sqlite,
+        failures: usize,
+        error: fn() -> Error,
+        calls: usize,
+    }
+
+    impl Flaky {
+        fn new(failures: usize, error: fn() -> Error) -> Self {
+            Self {
+                inner: Sqlite::new_in_memory().unwrap(),
+                failures,
+                error,
+                calls: 0,
+            }
+        }
+
+        fn call(&mut self) -> crate::Result<()> {
+            self.calls += 1;
+
+            if self.failures > 0 {
+                self.failures -= 1;
+                Err((self.error)())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl Queryable for Flaky {
+        fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+            self.call()?;
+            self.inner.execute_with_result(q)
+        }
+
+        fn insert_returning<'a>(
+            &mut self,
+            insert: Insert<'a>,
+            columns: Vec<Column<'a>>,
+        ) -> crate::Result<ResultRow> {
+            self.call()?;
+            self.inner.insert_returning(insert, columns)
+        }
+
+        fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
+            self.call()?;
+            self.inner.query(q)
+        }
+
+        fn query_raw(
+            &mut self,
+            sql: &str,
+            params: &[ParameterizedValue],
+        ) -> crate::Result<ResultSet> {
+            self.call()?;
+            self.inner.query_raw(sql, params)
+        }
+
+        fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+            self.call()?;
+            self.inner.query_with_timeout(q, timeout)
+        }
+
+        fn query_each(
+            &mut self,
+            q: Query,
+            f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+        ) -> crate::Result<u64> {
+            self.call()?;
+            self.inner.query_each(q, f)
+        }
+
+        fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
+            self.call()?;
+            self.inner.execute_raw(sql, params)
+        }
+
+        #[allow(deprecated)]
+        fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+            self.inner.turn_off_fk_constraints()
+        }
+
+        #[allow(deprecated)]
+        fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+            self.inner.turn_on_fk_constraints()
+        }
+
+        fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+            self.inner.foreign_key_checks()
+        }
+
+        fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+            self.inner.set_foreign_key_checks(enabled)
+        }
+
+        fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+            self.inner.set_schema(schema)
+        }
+
+        fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+            self.inner.cancel_token()
+        }
+
+        fn start_transaction<'a>(&'a mut self) -> crate::Result<Transaction<'a>> {
+            Transaction::new(self)
+        }
+
+        fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+            self.inner.raw_cmd(cmd)
+        }
+
+        fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+            self.inner.execute_script(sql)
+        }
+
+        fn version(&mut self) -> crate::Result<ServerVersion> {
+            self.inner.version()
+        }
+
+        fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+            self.inner.table_exists(table)
+        }
+
+        fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+            self.inner.list_tables(schema)
+        }
+
+        fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+            self.inner.describe_table(table)
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        }
+    }
+
+    fn deadlock() -> Error {
+        Error::TransactionWriteConflict
+    }
+
+    #[test]
+    fn transient_errors_are_retried() {
+        let mut conn = Flaky::new(2, deadlock).with_retries(policy(3));
+
+        assert!(conn.query_raw("SELECT 1", &[]).is_ok());
+        assert_eq!(3, conn.get_mut().calls);
+    }
+
+    #[test]
+    fn retries_give_up_after_max_attempts() {
+        let mut conn = Flaky::new(5, || Error::ConnectionClosed).with_retries(policy(3));
+
+        match conn.query_raw("SELECT 1", &[]) {
+            Err(Error::ConnectionClosed) => (),
+            res => panic!("Expected a closed connection, got {:?}", res),
+        }
+
+        assert_eq!(3, conn.get_mut().calls);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut conn = Flaky::new(1, || Error::NotFound).with_retries(policy(3));
+
+        assert!(conn.query_raw("SELECT 1", &[]).is_err());
+        assert_eq!(1, conn.get_mut().calls);
+    }
+
+    #[test]
+    fn statements_in_a_transaction_are_not_retried() {
+        let mut conn = Flaky::new(0, deadlock).with_retries(policy(3));
+
+        conn.raw_cmd("BEGIN").unwrap();
+        conn.get_mut().failures = 1;
+
+        assert!(conn.query_raw("SELECT 1", &[]).is_err());
+        assert_eq!(1, conn.get_mut().calls);
+
+        conn.raw_cmd("ROLLBACK").unwrap();
+        conn.get_mut().failures = 1;
+
+        assert!(conn.query_raw("SELECT 1", &[]).is_ok());
+        assert_eq!(3, conn.get_mut().calls);
+    }
+
+    #[test]
+    fn transaction_with_retries_runs_the_whole_transaction_again() {
+        let mut conn = Flaky::new(0, deadlock).with_retries(policy(3));
+        conn.raw_cmd("CREATE TABLE cats (name TEXT)").unwrap();
+
+        let mut attempts = 0;
+
+        conn.transaction_with_retries(|tx| {
+            attempts += 1;
+            tx.insert(Insert::single_into("cats").value("name", "musti").into())?;
+
+            if attempts == 1 {
+                Err(deadlock())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+
+        assert_eq!(2, attempts);
+
+        let res = conn.query_raw("SELECT name FROM cats", &[]).unwrap();
+        assert_eq!(1, res.len());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+
+        for (retry, full) in &[(1, 100), (2, 200), (3, 300), (8, 300), (40, 300)] {
+            let backoff = policy.backoff(*retry);
+            let full = Duration::from_millis(*full);
+
+            assert!(backoff >= full / 2 && backoff <= full, "{:?}", backoff);
+        }
+    }
+}
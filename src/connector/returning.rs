@@ -0,0 +1,58 @@
+//! Reading back inserted rows, with `RETURNING` or with a separate query.
+use super::{ExecuteResult, Queryable, ResultRow};
+use crate::{
+    ast::{Column, Comparable, Insert, ParameterizedValue, Select},
+    error::Error,
+};
+
+/// Adds a `RETURNING` clause of the columns, or of all columns if none are
+/// given.
+pub(crate) fn with_returning<'a>(insert: Insert<'a>, columns: Vec<Column<'a>>) -> Insert<'a> {
+    if columns.is_empty() {
+        insert.returning_all()
+    } else {
+        insert.returning(columns)
+    }
+}
+
+/// The first row of the `RETURNING` clause, `Error::NotFound` if nothing was
+/// inserted.
+pub(crate) fn returned_row(result: ExecuteResult) -> crate::Result<ResultRow> {
+    result
+        .returning
+        .and_then(|rows| rows.into_iter().next())
+        .ok_or(Error::NotFound)
+}
+
+/// Executes the insert and selects the row by the id the database reports,
+/// for databases without `RETURNING`. The id is matched against the given
+/// column. Fails with `Error::NotFound` if nothing was inserted, e.g. with
+/// `INSERT IGNORE`, as the reported id would belong to an earlier insert.
+pub(crate) fn insert_and_select<'a, Q>(
+    conn: &mut Q,
+    insert: Insert<'a>,
+    columns: Vec<Column<'a>>,
+    id_column: Column<'a>,
+) -> crate::Result<ResultRow>
+where
+    Q: Queryable + ?Sized,
+{
+    let table = insert.table.clone();
+
+    let result = conn.execute_with_result(insert.into())?;
+
+    if result.rows_affected == 0 {
+        return Err(Error::NotFound);
+    }
+
+    let id = result.last_insert_id.ok_or(Error::NotFound)?;
+
+    let select = Select::from_table(table)
+        .columns(columns)
+        .so_that(id_column.equals(ParameterizedValue::from(id)));
+
+    conn.query(select.into())?
+        .into_iter()
+        .next()
+        .ok_or(Error::NotFound)
+}
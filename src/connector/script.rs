@@ -0,0 +1,144 @@
+/// Splits an SQL script into statements on the semicolons outside of string
+/// literals, quoted identifiers, comments and Postgres dollar-quoted strings.
+/// Statements with only whitespace are skipped.
+pub(crate) fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b';' => {
+                push_statement(&sql[start..i], &mut statements);
+                start = i + 1;
+                i += 1;
+            }
+            quote @ b'\'' | quote @ b'"' | quote @ b'`' => {
+                i = skip_quoted(bytes, i + 1, quote);
+            }
+            b'[' => {
+                i = skip_quoted(bytes, i + 1, b']');
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map(|pos| i + pos + 1)
+                    .unwrap_or_else(|| bytes.len());
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map(|pos| i + 2 + pos + 2)
+                    .unwrap_or_else(|| bytes.len());
+            }
+            b'$' => match dollar_tag(&sql[i..]) {
+                Some(tag) => {
+                    let body = i + tag.len();
+
+                    i = sql[body..]
+                        .find(tag)
+                        .map(|pos| body + pos + tag.len())
+                        .unwrap_or_else(|| bytes.len());
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+
+    push_statement(&sql[start..], &mut statements);
+
+    statements
+}
+
+fn push_statement<'a>(statement: &'a str, statements: &mut Vec<&'a str>) {
+    let statement = statement.trim();
+
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+}
+
+/// The position after the closing quote, a doubled quote being an escaped one.
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+
+            return i + 1;
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// The opening tag of a dollar-quoted string, e.g. `$$` or `$body$`. A
+/// positional parameter, such as `$1`, is not a tag.
+fn dollar_tag(s: &str) -> Option<&str> {
+    let end = s[1..].find('$')? + 1;
+    let tag = &s[1..end];
+
+    let valid = tag
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_alphabetic() || (i > 0 && c.is_ascii_digit()));
+
+    if valid {
+        Some(&s[..=end])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements_are_split_on_semicolons() {
+        let script = "CREATE TABLE a (id int);\n INSERT INTO a VALUES (1) ;;\n";
+
+        assert_eq!(
+            vec!["CREATE TABLE a (id int)", "INSERT INTO a VALUES (1)"],
+            split_statements(script)
+        );
+    }
+
+    #[test]
+    fn semicolons_in_literals_and_comments_are_ignored() {
+        let script = r#"
+            -- first; statement
+            INSERT INTO "a;b" VALUES ('it''s; fine', `c;d`, [e;f]);
+            /* second; statement */
+            SELECT 1
+        "#;
+
+        let statements = split_statements(script);
+
+        assert_eq!(2, statements.len());
+        assert!(statements[0].ends_with("[e;f])"));
+        assert!(statements[1].ends_with("SELECT 1"));
+    }
+
+    #[test]
+    fn dollar_quoted_strings_are_kept_whole() {
+        let script = "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql; \
+                      SELECT $1::int4; SELECT $$a;b$$";
+
+        assert_eq!(
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql",
+                "SELECT $1::int4",
+                "SELECT $$a;b$$",
+            ],
+            split_statements(script)
+        );
+    }
+}
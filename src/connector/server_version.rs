@@ -0,0 +1,222 @@
+use super::Queryable;
+use crate::error::Error;
+use std::fmt;
+
+/// The database server behind a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Mysql,
+    MariaDb,
+    Postgres,
+    Sqlite,
+}
+
+/// The version of the database server, as reported by the server and parsed
+/// into its numeric parts. Missing parts are zero.
+///
+/// ```
+/// # use prisma_query::connector::{Backend, ServerVersion};
+/// let version = ServerVersion::parse(Backend::Postgres, "PostgreSQL 15.4 (Debian 15.4-1.pgdg120+1) on x86_64-pc-linux-gnu");
+///
+/// assert_eq!((15, 4, 0), (version.major, version.minor, version.patch));
+/// assert!(version.at_least(9, 5, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerVersion {
+    pub backend: Backend,
+    pub raw: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl ServerVersion {
+    /// Parses the version string of the server. A MySQL string from a
+    /// MariaDB server is detected and parsed as MariaDB.
+    pub fn parse(backend: Backend, raw: &str) -> Self {
+        let backend = match backend {
+            Backend::Mysql | Backend::MariaDb if raw.contains("MariaDB") => Backend::MariaDb,
+            backend => backend,
+        };
+
+        let version = match backend {
+            // Replication-compatible servers report e.g. `5.5.5-10.4.6-MariaDB`.
+            Backend::MariaDb => raw.trim_start_matches("5.5.5-"),
+            Backend::Postgres => raw.trim_start_matches("PostgreSQL "),
+            _ => raw,
+        };
+
+        let (major, minor, patch) = parse_numbers(version);
+
+        Self {
+            backend,
+            raw: raw.to_string(),
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// True if the server is of the given version or newer.
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+
+    /// The features supported by the server.
+    pub fn capabilities(&self) -> Capabilities {
+        match self.backend {
+            Backend::Postgres => Capabilities {
+                returning: true,
+                skip_locked: self.at_least(9, 5, 0),
+                window_functions: self.at_least(8, 4, 0),
+            },
+            Backend::Mysql => Capabilities {
+                returning: false,
+                skip_locked: self.at_least(8, 0, 1),
+                window_functions: self.at_least(8, 0, 0),
+            },
+            Backend::MariaDb => Capabilities {
+                returning: self.at_least(10, 5, 0),
+                skip_locked: self.at_least(10, 6, 0),
+                window_functions: self.at_least(10, 2, 0),
+            },
+            Backend::Sqlite => Capabilities {
+                returning: self.at_least(3, 35, 0),
+                skip_locked: false,
+                window_functions: self.at_least(3, 25, 0),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+/// Optional SQL features, depending on the database and its version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    /// `INSERT ... RETURNING`
+    pub returning: bool,
+    /// `SELECT ... FOR UPDATE SKIP LOCKED`
+    pub skip_locked: bool,
+    /// `OVER (...)`
+    pub window_functions: bool,
+}
+
+/// Reads the version string from the first column of the query.
+pub(crate) fn query_version<Q>(
+    conn: &mut Q,
+    backend: Backend,
+    sql: &str,
+) -> crate::Result<ServerVersion>
+where
+    Q: Queryable + ?Sized,
+{
+    let rows = conn.query_raw(sql, &[])?;
+
+    let raw = rows
+        .first()
+        .and_then(|row| row[0].as_str().map(String::from))
+        .ok_or(Error::NotFound)?;
+
+    Ok(ServerVersion::parse(backend, &raw))
+}
+
+/// The numbers of the first dot-separated version in the string, e.g.
+/// `(12, 0, 0)` from `12beta2`.
+fn parse_numbers(s: &str) -> (u32, u32, u32) {
+    let start = match s.find(|c: char| c.is_ascii_digit()) {
+        Some(start) => start,
+        None => return (0, 0, 0),
+    };
+
+    let mut numbers = [0u32; 3];
+    let mut rest = &s[start..];
+
+    for number in numbers.iter_mut() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or_else(|| rest.len());
+
+        *number = rest[..end].parse().unwrap_or(0);
+
+        match rest[end..].chars().next() {
+            Some('.') if rest[end + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                rest = &rest[end + 1..];
+            }
+            _ => break,
+        }
+    }
+
+    (numbers[0], numbers[1], numbers[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(backend: Backend, raw: &str) -> (Backend, u32, u32, u32) {
+        let version = ServerVersion::parse(backend, raw);
+        (version.backend, version.major, version.minor, version.patch)
+    }
+
+    #[test]
+    fn postgres_versions_are_parsed() {
+        assert_eq!(
+            (Backend::Postgres, 15, 4, 0),
+            version(
+                Backend::Postgres,
+                "PostgreSQL 15.4 (Debian 15.4-1.pgdg120+1) on x86_64-pc-linux-gnu"
+            )
+        );
+
+        assert_eq!(
+            (Backend::Postgres, 9, 6, 15),
+            version(Backend::Postgres, "PostgreSQL 9.6.15 on x86_64-pc-linux-gnu")
+        );
+
+        assert_eq!(
+            (Backend::Postgres, 12, 0, 0),
+            version(Backend::Postgres, "PostgreSQL 12beta2 on x86_64-pc-linux-gnu")
+        );
+    }
+
+    #[test]
+    fn mysql_and_mariadb_versions_are_parsed() {
+        assert_eq!((Backend::Mysql, 8, 0, 16), version(Backend::Mysql, "8.0.16"));
+        assert_eq!((Backend::Mysql, 5, 7, 27), version(Backend::Mysql, "5.7.27-log"));
+
+        assert_eq!(
+            (Backend::MariaDb, 10, 4, 6),
+            version(Backend::Mysql, "10.4.6-MariaDB-1:10.4.6+maria~bionic")
+        );
+
+        assert_eq!(
+            (Backend::MariaDb, 10, 3, 17),
+            version(Backend::Mysql, "5.5.5-10.3.17-MariaDB")
+        );
+    }
+
+    #[test]
+    fn sqlite_versions_are_parsed() {
+        assert_eq!((Backend::Sqlite, 3, 28, 0), version(Backend::Sqlite, "3.28.0"));
+    }
+
+    #[test]
+    fn capabilities_depend_on_the_version() {
+        let old = ServerVersion::parse(Backend::Sqlite, "3.28.0").capabilities();
+        let new = ServerVersion::parse(Backend::Sqlite, "3.35.5").capabilities();
+
+        assert!(!old.returning);
+        assert!(new.returning);
+
+        let mysql = ServerVersion::parse(Backend::Mysql, "8.0.16").capabilities();
+        let mariadb = ServerVersion::parse(Backend::Mysql, "10.3.17-MariaDB").capabilities();
+
+        assert!(mysql.skip_locked);
+        assert!(!mariadb.skip_locked);
+    }
+}
@@ -1,25 +1,329 @@
 mod conversion;
 mod error;
+mod watchdog;
 
 use crate::{
-    ast::{Id, ParameterizedValue, Query},
-    connector::{metrics, queryable::*, ResultSet, Transaction},
+    ast::{Column, Id, Insert, ParameterizedValue, Query},
+    connector::{
+        introspection, metrics, params, queryable::*, returning, script, server_version,
+        statement_cache::DEFAULT_STATEMENT_CACHE_SIZE, Backend, BorrowedRow, CancelToken,
+        ColumnDescription, ColumnType, ExecuteResult, QueryLogger, ResultRow, ResultSet,
+        ServerVersion, Transaction,
+    },
     error::Error,
     visitor::{self, Visitor},
 };
+use percent_encoding::percent_decode;
 use rusqlite::NO_PARAMS;
-use std::{collections::HashSet, convert::TryFrom, path::PathBuf};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 /// A connector interface for the SQLite database
+///
+/// Prepared statements are cached per connection. The cache size can be set
+/// with the `statement_cache_size` connection string parameter, zero disabling
+/// the cache.
+///
+/// The connection is opened to an in-memory `main` database, and database
+/// files are attached to it under a name, used as the `database` of a
+/// [Table](../ast/struct.Table.html). Unqualified table names are looked up
+/// from the `main` database first, then from the attached ones in the order
+/// of attaching, so the first attached database works as the default.
+///
+/// Date-times are stored as `YYYY-MM-DD HH:MM:SS.SSS` text in UTC, the rest
+/// of the precision being dropped, so they compare in order with the values
+/// of `CURRENT_TIMESTAMP` and the SQLite date functions. Columns declared as
+/// `DATETIME` or `TIMESTAMP` are read back as date-times, including their
+/// text values in RFC 3339 or in the `YYYY-MM-DD HH:MM:SS` format, taken as
+/// UTC, and integer milliseconds since the Unix epoch. Dates and times of
+/// day are stored as ISO 8601 text, ordered as such, and read back from
+/// columns declared as `DATE` or `TIME`.
+///
+/// JSON and UUIDs are stored as text, and read back as such from columns
+/// declared as `JSON` or `UUID`.
+///
+/// Decimals are stored as text, which a column declared as `DECIMAL` or
+/// `NUMERIC` converts into a floating point number, keeping 15 significant
+/// digits. Such columns are read back as decimals. SQLite computes arithmetic
+/// and aggregates such as `SUM` in floating point too.
 pub struct Sqlite {
     pub(crate) client: rusqlite::Connection,
     pub(crate) file_path: PathBuf,
+    pub(crate) pragmas: SqlitePragmas,
+    logger: Option<QueryLogger>,
+    application_name: Option<String>,
+}
+
+/// Pragmas applied to every connection right after opening it. Besides the
+/// struct, they can be set with the connection string parameters of the same
+/// name, `busy_timeout` given in milliseconds.
+///
+/// The `journal_mode` and `synchronous` pragmas are set per database, and
+/// applied to the attached databases.
+///
+/// ```
+/// # use prisma_query::connector::SqliteParams;
+/// # use std::{convert::TryFrom, time::Duration};
+/// let params = SqliteParams::try_from("file:db/test.db?journal_mode=WAL&busy_timeout=5000").unwrap();
+///
+/// assert_eq!(Some("WAL"), params.pragmas.journal_mode.as_ref().map(|s| s.as_str()));
+/// assert_eq!(Some(Duration::from_secs(5)), params.pragmas.busy_timeout);
+/// assert!(params.pragmas.foreign_keys);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlitePragmas {
+    pub journal_mode: Option<String>,
+    pub synchronous: Option<String>,
+    /// How long to wait for a lock before failing with `Error::Timeout`.
+    pub busy_timeout: Option<Duration>,
+    pub foreign_keys: bool,
+}
+
+impl Default for SqlitePragmas {
+    fn default() -> Self {
+        Self {
+            journal_mode: None,
+            synchronous: None,
+            busy_timeout: None,
+            foreign_keys: true,
+        }
+    }
+}
+
+impl SqlitePragmas {
+    /// Applies the pragmas of the whole connection.
+    fn apply_to_connection(&self, client: &rusqlite::Connection) -> crate::Result<()> {
+        if let Some(timeout) = self.busy_timeout {
+            client.busy_timeout(timeout)?;
+        }
+
+        let foreign_keys = if self.foreign_keys { "ON" } else { "OFF" };
+        client.execute_batch(&format!("PRAGMA foreign_keys = {}", foreign_keys))?;
+
+        Ok(())
+    }
+
+    /// Applies the pragmas set per database.
+    fn apply_to_database(&self, client: &rusqlite::Connection, db_name: &str) -> crate::Result<()> {
+        if let Some(ref mode) = self.journal_mode {
+            client.query_row(
+                &format!("PRAGMA \"{}\".journal_mode = {}", db_name, mode),
+                NO_PARAMS,
+                |_| Ok(()),
+            )?;
+        }
+
+        if let Some(ref synchronous) = self.synchronous {
+            client.execute_batch(&format!(
+                "PRAGMA \"{}\".synchronous = {}",
+                db_name, synchronous
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pragma values interpolated into the statements may only contain
+/// alphanumeric characters and underscores.
+fn validate_pragma(key: &str, value: &str) -> crate::Result<()> {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(params::invalid(
+            key,
+            format!("`{}` is not a valid value", value),
+        ))
+    }
 }
 
+#[derive(Debug)]
 pub struct SqliteParams {
     pub connection_limit: u32,
+    /// The path of the database file, or the URI of an in-memory database.
     pub file_path: PathBuf,
     pub schema: Option<String>,
+    pub statement_cache_size: usize,
+    pub pragmas: SqlitePragmas,
+    /// Not sent anywhere, kept for diagnostics.
+    pub application_name: Option<String>,
+}
+
+/// The name SQLite gives to the database the connection was opened with.
+pub(crate) const MAIN_DATABASE: &str = "main";
+
+/// The default `SQLITE_MAX_ATTACHED` of SQLite.
+pub const MAX_ATTACHED_DATABASES: usize = 10;
+
+impl SqliteParams {
+    /// A builder for constructing the parameters from typed values, e.g.
+    /// from a configuration struct. Connection strings are parsed through the
+    /// same builder.
+    ///
+    /// ```
+    /// # use prisma_query::connector::SqliteParams;
+    /// # use std::path::PathBuf;
+    /// let params = SqliteParams::builder()
+    ///     .file_path("db/test.db")
+    ///     .connection_limit(1)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(PathBuf::from("db/test.db"), params.file_path);
+    /// assert_eq!(Some("test"), params.schema.as_ref().map(|s| s.as_str()));
+    ///
+    /// let params = SqliteParams::builder().in_memory().build().unwrap();
+    /// assert_eq!(PathBuf::from(":memory:"), params.file_path);
+    /// ```
+    pub fn builder() -> SqliteParamsBuilder {
+        SqliteParamsBuilder::default()
+    }
+}
+
+/// Typed construction of [SqliteParams](struct.SqliteParams.html). Either a
+/// file path or an in-memory database is required, the rest defaulting as in
+/// a connection string without the corresponding parameter.
+#[derive(Debug, Default)]
+pub struct SqliteParamsBuilder {
+    file_path: Option<String>,
+    in_memory: bool,
+    shared_cache: bool,
+    connection_limit: Option<u32>,
+    statement_cache_size: Option<usize>,
+    pragmas: SqlitePragmas,
+    application_name: Option<String>,
+}
+
+impl SqliteParamsBuilder {
+    /// The path of the database file, or the name of an in-memory database.
+    pub fn file_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.file_path = Some(path.into());
+        self
+    }
+
+    /// Keeps the database in memory, private to the connection unless named
+    /// and shared.
+    pub fn in_memory(mut self) -> Self {
+        self.in_memory = true;
+        self
+    }
+
+    /// Shares a named in-memory database by all connections using the name.
+    pub fn shared_cache(mut self, shared: bool) -> Self {
+        self.shared_cache = shared;
+        self
+    }
+
+    /// The maximum size of a connection pool.
+    pub fn connection_limit(mut self, limit: u32) -> Self {
+        self.connection_limit = Some(limit);
+        self
+    }
+
+    pub fn statement_cache_size(mut self, size: usize) -> Self {
+        self.statement_cache_size = Some(size);
+        self
+    }
+
+    pub fn pragmas(mut self, pragmas: SqlitePragmas) -> Self {
+        self.pragmas = pragmas;
+        self
+    }
+
+    pub fn application_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.application_name = Some(name.into());
+        self
+    }
+
+    /// Validates the values, failing with an
+    /// `Error::InvalidConnectionParameter` naming the missing or invalid
+    /// field.
+    pub fn build(self) -> crate::Result<SqliteParams> {
+        let connection_limit = self
+            .connection_limit
+            .unwrap_or_else(params::default_connection_limit);
+
+        if connection_limit == 0 {
+            return Err(params::invalid(
+                "connection_limit",
+                "at least one connection is needed",
+            ));
+        }
+
+        if let Some(ref mode) = self.pragmas.journal_mode {
+            validate_pragma("journal_mode", mode)?;
+        }
+
+        if let Some(ref synchronous) = self.pragmas.synchronous {
+            validate_pragma("synchronous", synchronous)?;
+        }
+
+        let path = match (self.file_path, self.in_memory) {
+            (Some(path), _) => path,
+            (None, true) => String::from(":memory:"),
+            (None, false) => return Err(params::missing("file_path")),
+        };
+
+        let in_memory = self.in_memory || path == ":memory:";
+
+        let (file_path, schema) = if in_memory {
+            match (path.as_str(), self.shared_cache) {
+                // A private database, living in the main database of the connection.
+                (":memory:", false) => (PathBuf::from(":memory:"), String::from(MAIN_DATABASE)),
+                (":memory:", true) => (
+                    PathBuf::from("file::memory:?cache=shared"),
+                    String::from("memory"),
+                ),
+                ("", _) => {
+                    return Err(params::invalid(
+                        "file_path",
+                        "the in-memory database has no name",
+                    ))
+                }
+                (name, true) => (
+                    PathBuf::from(format!("file:{}?mode=memory&cache=shared", name)),
+                    String::from(name),
+                ),
+                (name, false) => (
+                    PathBuf::from(format!("file:{}?mode=memory", name)),
+                    String::from(name),
+                ),
+            }
+        } else {
+            let path = PathBuf::from(path);
+
+            let schema = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(String::from)
+                .ok_or_else(|| params::invalid("file_path", "the path has no file name"))?;
+
+            if path.is_dir() {
+                return Err(params::invalid(
+                    "file_path",
+                    format!("{} is a directory", path.display()),
+                ));
+            }
+
+            (path, schema)
+        };
+
+        Ok(SqliteParams {
+            connection_limit,
+            file_path,
+            schema: Some(schema),
+            statement_cache_size: self
+                .statement_cache_size
+                .unwrap_or(DEFAULT_STATEMENT_CACHE_SIZE),
+            pragmas: self.pragmas,
+            application_name: self.application_name,
+        })
+    }
 }
 
 type ConnectionParams = (Vec<(String, String)>, Vec<(String, String)>);
@@ -27,57 +331,106 @@ type ConnectionParams = (Vec<(String, String)>, Vec<(String, String)>);
 impl TryFrom<&str> for SqliteParams {
     type Error = Error;
 
+    /// Parses a connection string in the form of `file:path/to.db?param=value`.
+    /// The `sqlite:` scheme, and the `//` authority separator after the scheme
+    /// are accepted as well.
+    ///
+    /// In-memory databases are given as `file::memory:`, or as
+    /// `file:name?mode=memory&cache=shared` for a database shared by all
+    /// connections using the same name. See
+    /// [Sqlite::new_in_memory](struct.Sqlite.html#method.new_in_memory) for
+    /// their lifetime.
     fn try_from(path: &str) -> crate::Result<Self> {
-        let path = path.trim_start_matches("file:");
-        let path_parts: Vec<&str> = path.split('?').collect();
-        let path = PathBuf::from(path_parts[0]);
-        let schema = path.file_stem().unwrap().to_str().unwrap().to_owned();
-
-        if path.is_dir() {
-            Err(Error::DatabaseUrlIsInvalid(
-                path.to_str().unwrap().to_string(),
-            ))
-        } else {
-            let official = vec![];
-            let mut connection_limit = num_cpus::get_physical() * 2 + 1;
-
-            if path_parts.len() > 1 {
-                let (_, unsupported): ConnectionParams = path_parts
-                    .last()
-                    .unwrap()
-                    .split('&')
-                    .map(|kv| {
-                        let splitted: Vec<&str> = kv.split('=').collect();
-                        (String::from(splitted[0]), String::from(splitted[1]))
-                    })
-                    .collect::<Vec<(String, String)>>()
-                    .into_iter()
-                    .partition(|(k, _)| official.contains(&k.as_str()));
-
-                for (k, v) in unsupported.into_iter() {
-                    match k.as_ref() {
-                        "connection_limit" => {
-                            let as_int: usize =
-                                v.parse().map_err(|_| Error::InvalidConnectionArguments)?;
-
-                            connection_limit = as_int;
-                        }
-                        _ => {
-                            #[cfg(not(feature = "tracing-log"))]
-                            trace!("Discarding connection string param: {}", k);
-                            #[cfg(feature = "tracing-log")]
-                            tracing::trace!(message = "Discarding connection string param", param = k.as_str());
-                        },
-                    };
+        let path = ["sqlite://", "file://", "sqlite:", "file:"]
+            .iter()
+            .find(|prefix| path.starts_with(*prefix))
+            .map(|prefix| &path[prefix.len()..])
+            .unwrap_or(path);
+
+        let path_parts: Vec<&str> = path.splitn(2, '?').collect();
+
+        let path = percent_decode(path_parts[0].as_bytes())
+            .decode_utf8()
+            .map_err(|_| Error::DatabaseUrlIsInvalid("The file path is not valid UTF-8".into()))?
+            .into_owned();
+
+        let official = vec![];
+        let mut builder = SqliteParams::builder().file_path(path);
+        let mut pragmas = SqlitePragmas::default();
+
+        if path_parts.len() > 1 {
+            let mut pairs = Vec::new();
+
+            for kv in path_parts[1].split('&').filter(|kv| !kv.is_empty()) {
+                let splitted: Vec<&str> = kv.splitn(2, '=').collect();
+
+                match splitted.as_slice() {
+                    [k, v] => pairs.push((String::from(*k), String::from(*v))),
+                    _ => {
+                        return Err(Error::DatabaseUrlIsInvalid(format!(
+                            "The connection string parameter `{}` has no value",
+                            kv
+                        )))
+                    }
                 }
             }
 
-            Ok(Self {
-                connection_limit: u32::try_from(connection_limit).unwrap(),
-                file_path: path,
-                schema: Some(schema),
-            })
+            let (_, unsupported): ConnectionParams = pairs
+                .into_iter()
+                .partition(|(k, _)| official.contains(&k.as_str()));
+
+            for (k, v) in unsupported.into_iter() {
+                match k.as_ref() {
+                    "connection_limit" => {
+                        builder = builder.connection_limit(params::parse_param(&k, &v)?);
+                    }
+                    "statement_cache_size" => {
+                        builder = builder.statement_cache_size(params::parse_param(&k, &v)?);
+                    }
+                    "mode" => {
+                        if v == "memory" {
+                            builder = builder.in_memory();
+                        }
+                    }
+                    "cache" => {
+                        builder = builder.shared_cache(v == "shared");
+                    }
+                    "journal_mode" => {
+                        pragmas.journal_mode = Some(v);
+                    }
+                    "synchronous" => {
+                        pragmas.synchronous = Some(v);
+                    }
+                    "busy_timeout" => {
+                        let as_int: u64 = params::parse_param(&k, &v)?;
+                        pragmas.busy_timeout = Some(Duration::from_millis(as_int));
+                    }
+                    "application_name" => {
+                        builder = builder.application_name(v);
+                    }
+                    "foreign_keys" => {
+                        pragmas.foreign_keys = match v.to_lowercase().as_str() {
+                            "true" | "on" | "1" => true,
+                            "false" | "off" | "0" => false,
+                            _ => {
+                                return Err(params::invalid(
+                                    &k,
+                                    format!("`{}` is not a valid value", v),
+                                ))
+                            }
+                        };
+                    }
+                    _ => {
+                        #[cfg(not(feature = "tracing-log"))]
+                        trace!("Discarding connection string param: {}", k);
+                        #[cfg(feature = "tracing-log")]
+                        tracing::trace!(message = "Discarding connection string param", param = k.as_str());
+                    },
+                };
+            }
         }
+
+        builder.pragmas(pragmas).build()
     }
 }
 
@@ -86,10 +439,7 @@ impl TryFrom<&str> for Sqlite {
 
     fn try_from(path: &str) -> crate::Result<Self> {
         let params = SqliteParams::try_from(path)?;
-        let client = metrics::connect("sqlite", rusqlite::Connection::open_in_memory)?;
-        let file_path = params.file_path;
-
-        Ok(Sqlite { client, file_path })
+        Self::from_params(params)
     }
 }
 
@@ -101,38 +451,388 @@ impl Sqlite {
         Self::try_from(file_path.into().to_str().unwrap())
     }
 
-    pub fn attach_database(&mut self, db_name: &str) -> crate::Result<()> {
-        let mut stmt = self.client.prepare("PRAGMA database_list")?;
+    /// Opens a connection to a private in-memory database, usable with the
+    /// `main` database name or without one.
+    ///
+    /// In-memory data lives as long as the connection. A shared database
+    /// (`file:name?mode=memory&cache=shared`) lives until the last connection
+    /// using it is closed, after which its data is gone.
+    ///
+    /// ```
+    /// # use prisma_query::connector::{Queryable, Sqlite};
+    /// let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY)").unwrap();
+    ///
+    /// let res = conn.query_raw("SELECT * FROM main.cats", &[]).unwrap();
+    /// assert!(res.is_empty());
+    /// ```
+    pub fn new_in_memory() -> crate::Result<Sqlite> {
+        let client = metrics::connect("sqlite", rusqlite::Connection::open_in_memory)?;
+        client.set_prepared_statement_cache_capacity(DEFAULT_STATEMENT_CACHE_SIZE);
 
-        let databases: HashSet<String> = stmt
-            .query_map(NO_PARAMS, |row| {
-                let name: String = row.get(1)?;
+        Self::from_client(client, PathBuf::from(":memory:"), SqlitePragmas::default())
+    }
 
-                Ok(name)
-            })?
-            .map(|res| res.unwrap())
-            .collect();
+    /// Opens a connection with the given parameters. The database file is not
+    /// attached before calling [attach_file](#method.attach_file).
+    pub fn from_params(params: SqliteParams) -> crate::Result<Sqlite> {
+        let client = metrics::connect("sqlite", rusqlite::Connection::open_in_memory)?;
+        client.set_prepared_statement_cache_capacity(params.statement_cache_size);
+
+        let mut conn = Self::from_client(client, params.file_path, params.pragmas)?;
+        conn.application_name = params.application_name;
+
+        Ok(conn)
+    }
+
+    pub(crate) fn from_client(
+        client: rusqlite::Connection,
+        file_path: PathBuf,
+        pragmas: SqlitePragmas,
+    ) -> crate::Result<Sqlite> {
+        pragmas.apply_to_connection(&client)?;
+
+        Ok(Sqlite {
+            client,
+            file_path,
+            pragmas,
+            logger: None,
+            application_name: None,
+        })
+    }
+
+    /// Calls the logger after every query run on the connection, including
+    /// raw commands and transaction boundaries.
+    pub fn set_logger(&mut self, logger: QueryLogger) {
+        self.logger = Some(logger);
+    }
+
+    /// The name the connection was identified with, if any.
+    pub fn application_name(&self) -> Option<&str> {
+        self.application_name.as_ref().map(|s| s.as_str())
+    }
+
+    /// Runs the closure with the underlying `rusqlite` connection, for the
+    /// driver features the connector doesn't cover.
+    ///
+    /// Whatever is done with the connection bypasses the connector: errors of
+    /// the driver are not mapped to the errors of this crate, and the queries
+    /// are not given to the logger.
+    ///
+    /// ```
+    /// # use prisma_query::connector::*;
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// let autocommit = conn.with_raw_connection(|client| client.is_autocommit());
+    /// assert!(autocommit);
+    /// ```
+    pub fn with_raw_connection<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&rusqlite::Connection) -> T,
+    {
+        f(&self.client)
+    }
+
+    /// Sets a pragma, e.g. `pragma("cache_size", "-2000")`. The key can be
+    /// prefixed with a database name, as in `tenant.journal_mode`.
+    pub fn pragma(&mut self, key: &str, value: &str) -> crate::Result<()> {
+        let valid_key = key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+
+        let valid_value = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        if !valid_key || !valid_value || key.is_empty() || value.is_empty() {
+            return Err(Error::QueryError(failure::format_err!(
+                "Invalid pragma `{} = {}`",
+                key,
+                value
+            )));
+        }
+
+        self.raw_cmd(&format!("PRAGMA {} = {}", key, value))
+    }
+
+    /// Sets the number of cached prepared statements, zero disabling the
+    /// cache.
+    pub fn set_statement_cache_size(&mut self, capacity: usize) {
+        self.client.set_prepared_statement_cache_capacity(capacity);
+    }
+
+    /// Attaches the database file of the connection under the given name.
+    pub fn attach_file(&mut self, db_name: &str) -> crate::Result<()> {
+        let file_path = self.file_path.clone();
+        self.attach_database(file_path, db_name)
+    }
+
+    /// Attaches the database file, or in-memory database URI, under the given
+    /// name. Attaching a name already in use does nothing.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::{Queryable, Sqlite}};
+    /// let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.attach_database(":memory:", "tenant_42").unwrap();
+    /// conn.raw_cmd("CREATE TABLE tenant_42.users (name TEXT)").unwrap();
+    ///
+    /// let insert = Insert::single_into(("tenant_42", "users")).value("name", "musti");
+    /// conn.execute(insert.into()).unwrap();
+    ///
+    /// conn.detach_database("tenant_42").unwrap();
+    /// ```
+    pub fn attach_database<P>(&mut self, path: P, db_name: &str) -> crate::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let databases = self.database_names()?;
 
         if !databases.contains(db_name) {
+            // `main` and `temp` do not count towards the limit.
+            let attached = databases
+                .iter()
+                .filter(|name| name.as_str() != MAIN_DATABASE && name.as_str() != "temp")
+                .count();
+
+            if attached >= MAX_ATTACHED_DATABASES {
+                return Err(Error::TooManyAttachedDatabases {
+                    limit: MAX_ATTACHED_DATABASES,
+                });
+            }
+
+            let path = path.as_ref().to_str().ok_or_else(|| {
+                Error::DatabaseUrlIsInvalid("The file path is not valid UTF-8".into())
+            })?;
+
             rusqlite::Connection::execute(
                 &self.client,
                 "ATTACH DATABASE ? AS ?",
-                &[self.file_path.to_str().unwrap(), db_name],
+                &[path, db_name],
             )?;
         }
 
-        rusqlite::Connection::execute(&self.client, "PRAGMA foreign_keys = ON", NO_PARAMS)?;
+        self.pragmas.apply_to_database(&self.client, db_name)?;
+
+        Ok(())
+    }
+
+    /// Creates the database file `<db_name>.db` next to the database file of
+    /// the connection, attaching it under the name. Fails with
+    /// `Error::DatabaseAlreadyExists` if the file exists.
+    ///
+    /// An in-memory connection has no directory for the files, use
+    /// [attach_database](#method.attach_database) instead.
+    pub fn create_database(&mut self, db_name: &str) -> crate::Result<()> {
+        let path = self.database_file(db_name)?;
+
+        if path.exists() {
+            return Err(Error::DatabaseAlreadyExists {
+                db_name: db_name.to_string(),
+                native: None,
+            });
+        }
+
+        // SQLite creates the file when attaching it.
+        self.attach_database(path, db_name)
+    }
+
+    /// Detaches the database and deletes the file `<db_name>.db` next to the
+    /// database file of the connection, along with its journal files. Fails
+    /// with `Error::DatabaseDoesNotExist` if the file is missing, unless
+    /// `if_exists` is set.
+    pub fn drop_database(&mut self, db_name: &str, if_exists: bool) -> crate::Result<()> {
+        let path = self.database_file(db_name)?;
+
+        if self.database_names()?.contains(db_name) {
+            self.detach_database(db_name)?;
+        }
+
+        if !path.exists() {
+            if if_exists {
+                return Ok(());
+            }
+
+            return Err(Error::DatabaseDoesNotExist {
+                db_name: db_name.to_string(),
+                native: None,
+            });
+        }
+
+        std::fs::remove_file(&path)?;
+
+        for suffix in &["-journal", "-wal", "-shm"] {
+            let mut journal = path.clone().into_os_string();
+            journal.push(suffix);
+
+            let journal = PathBuf::from(journal);
+
+            if journal.exists() {
+                std::fs::remove_file(journal)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The path of the file for a database created with this connection.
+    fn database_file(&self, db_name: &str) -> crate::Result<PathBuf> {
+        let valid_name = !db_name.is_empty()
+            && db_name != MAIN_DATABASE
+            && db_name != "temp"
+            && db_name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+
+        if !valid_name {
+            return Err(Error::QueryError(failure::format_err!(
+                "Invalid database name `{}`",
+                db_name
+            )));
+        }
+
+        let path = self.file_path.to_str().unwrap_or("");
+
+        if path == ":memory:" || path.starts_with("file:") {
+            return Err(Error::QueryError(failure::format_err!(
+                "An in-memory connection has no directory for database files"
+            )));
+        }
+
+        let directory = self.file_path.parent().unwrap_or_else(|| Path::new(""));
+
+        Ok(directory.join(format!("{}.db", db_name)))
+    }
+
+    /// Executes the given query like
+    /// [query_each](trait.Queryable.html#tymethod.query_each), but the rows
+    /// borrow their text and binary data from SQLite instead of copying it.
+    /// The rows are valid only inside the function, and copied with
+    /// [into_owned](struct.BorrowedRow.html#method.into_owned) to keep them.
+    ///
+    /// ```
+    /// # use prisma_query::{ast::*, connector::*};
+    /// # let mut conn = Sqlite::new_in_memory().unwrap();
+    /// conn.raw_cmd("CREATE TABLE cats (name TEXT)").unwrap();
+    /// conn.raw_cmd("INSERT INTO cats VALUES ('musti'), ('naukio')").unwrap();
+    ///
+    /// let mut length = 0;
+    ///
+    /// conn.query_each_borrowed(Select::from_table("cats").into(), &mut |row| {
+    ///     length += row["name"].as_str().map(str::len).unwrap_or(0);
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(11, length);
+    /// ```
+    pub fn query_each_borrowed(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(BorrowedRow<'_>) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        let (sql, params) = visitor::Sqlite::build(q);
+
+        metrics::query(
+            "sqlite.query_each_borrowed",
+            &sql,
+            &params,
+            self.logger.clone(),
+            || {
+                let mut stmt = self.client.prepare_cached(&sql)?;
+                let mut rows = stmt.query(&params)?;
+
+                let names = Arc::new(ResultSet::build_name_map(&rows.to_column_names()));
+                let mut visited = 0;
+
+                while let Some(row) = rows.next()? {
+                    f(BorrowedRow::new(&names, conversion::to_borrowed_row(row)))?;
+                    visited += 1;
+                }
+
+                Ok(visited)
+            },
+        )
+    }
+
+    /// Executes the given query like
+    /// [query_each_borrowed](#method.query_each_borrowed), interrupting it
+    /// with an `Error::Timeout` if it runs longer than the timeout.
+    pub fn query_each_borrowed_with_timeout(
+        &mut self,
+        q: Query,
+        timeout: Duration,
+        f: &mut dyn FnMut(BorrowedRow<'_>) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        self.with_timeout(timeout, |conn| conn.query_each_borrowed(q, f))
+    }
+
+    /// Runs the function interrupted by a watchdog thread shared by all the
+    /// connections if it runs longer than the timeout.
+    fn with_timeout<T, F>(&mut self, timeout: Duration, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&mut Self) -> crate::Result<T>,
+    {
+        let watched = watchdog::watch(self.client.get_interrupt_handle(), timeout);
+
+        let res = f(self);
+        let timed_out = watched.finish();
+
+        match res {
+            Err(Error::QueryCancelled) if timed_out => Err(Error::Timeout),
+            res => res,
+        }
+    }
+
+    /// Detaches the database attached under the given name.
+    pub fn detach_database(&mut self, db_name: &str) -> crate::Result<()> {
+        // Cached statements may refer to the detached database.
+        self.client.flush_prepared_statement_cache();
+        rusqlite::Connection::execute(&self.client, "DETACH DATABASE ?", &[db_name])?;
 
         Ok(())
     }
+
+    fn database_names(&self) -> crate::Result<HashSet<String>> {
+        let mut stmt = self.client.prepare("PRAGMA database_list")?;
+
+        let databases = stmt
+            .query_map(NO_PARAMS, |row| {
+                let name: String = row.get(1)?;
+
+                Ok(name)
+            })?
+            .collect::<Result<HashSet<String>, _>>()?;
+
+        Ok(databases)
+    }
 }
 
 impl Queryable for Sqlite {
-    fn execute(&mut self, q: Query) -> crate::Result<Option<Id>> {
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
         let (sql, params) = visitor::Sqlite::build(q);
-        self.execute_raw(&sql, &params)?;
+        let rows_affected = self.execute_raw(&sql, &params)?;
+
+        Ok(ExecuteResult {
+            rows_affected,
+            last_insert_id: Some(Id::Int(self.client.last_insert_rowid())),
+            returning: None,
+            upsert: None,
+        })
+    }
 
-        Ok(Some(Id::Int(self.client.last_insert_rowid() as usize)))
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        if !self.client.is_autocommit() {
+            return returning::insert_and_select(self, insert, columns, Column::from("rowid"));
+        }
+
+        let mut tx = self.start_transaction()?;
+        let row = returning::insert_and_select(&mut tx, insert, columns, Column::from("rowid"))?;
+        tx.commit()?;
+
+        Ok(row)
     }
 
     fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
@@ -141,22 +841,57 @@ impl Queryable for Sqlite {
     }
 
     fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet> {
-        metrics::query("sqlite.query_raw", sql, params, || {
+        metrics::query("sqlite.query_raw", sql, params, self.logger.clone(), || {
             let mut stmt = self.client.prepare_cached(sql)?;
             let mut rows = stmt.query(params)?;
 
-            let mut result = ResultSet::new(rows.to_column_names(), Vec::new());
+            let mut result = ResultSet::new(rows.to_column_names(), Vec::new())?;
 
             while let Some(row) = rows.next()? {
-                result.rows.push(row.to_result_row()?);
+                result.push(row.to_result_row()?)?;
             }
 
             Ok(result)
         })
     }
 
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        let (sql, params) = visitor::Sqlite::build(q);
+
+        metrics::query(
+            "sqlite.query_each",
+            &sql,
+            &params,
+            self.logger.clone(),
+            || {
+                let mut stmt = self.client.prepare_cached(&sql)?;
+                let mut rows = stmt.query(&params)?;
+
+                let names = Arc::new(ResultSet::build_name_map(&rows.to_column_names()));
+                let mut visited = 0;
+
+                while let Some(row) = rows.next()? {
+                    f(ResultRow::new(Arc::clone(&names), row.to_result_row()?))?;
+                    visited += 1;
+                }
+
+                Ok(visited)
+            },
+        )
+    }
+
+    /// The query is interrupted by a watchdog thread shared by all the
+    /// connections.
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        self.with_timeout(timeout, |conn| conn.query(q))
+    }
+
     fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
-        metrics::query("sqlite.execute_raw", sql, params, || {
+        metrics::query("sqlite.execute_raw", sql, params, self.logger.clone(), || {
             let mut stmt = self.client.prepare_cached(sql)?;
             let changes = stmt.execute(params)?;
 
@@ -174,22 +909,133 @@ impl Queryable for Sqlite {
         Ok(())
     }
 
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        let res = self.query_raw("PRAGMA foreign_keys", &[])?;
+        Ok(res.first().and_then(|row| row[0].as_i64()) == Some(1))
+    }
+
+    /// The pragma has no effect inside of a transaction, failing with
+    /// `Error::UnsupportedOperation` instead.
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        if !self.client.is_autocommit() {
+            return Err(Error::UnsupportedOperation(
+                "set_foreign_key_checks inside of a transaction",
+            ));
+        }
+
+        let value = if enabled { "ON" } else { "OFF" };
+        self.raw_cmd(&format!("PRAGMA foreign_keys = {}", value))
+    }
+
+    /// Unqualified names resolve to the `main` database and then to the
+    /// attached ones in order, which can't be changed. Qualify the tables with
+    /// the name of the attached database instead.
+    fn set_schema(&mut self, _: &str) -> crate::Result<()> {
+        Err(Error::UnsupportedOperation("set_schema"))
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        let interrupt = self.client.get_interrupt_handle();
+
+        Ok(CancelToken::new(move || {
+            interrupt.interrupt();
+            Ok(())
+        }))
+    }
+
     fn start_transaction<'b>(&'b mut self) -> crate::Result<Transaction<'b>> {
         Ok(Transaction::new(self)?)
     }
 
     fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
-        metrics::query("sqlite.raw_cmd", cmd, &[], || {
+        metrics::query("sqlite.raw_cmd", cmd, &[], self.logger.clone(), || {
             self.client.execute_batch(cmd)?;
             Ok(())
         })
     }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        metrics::query("sqlite.execute_script", sql, &[], self.logger.clone(), || {
+            for (i, statement) in script::split_statements(sql).into_iter().enumerate() {
+                self.client
+                    .execute_batch(statement)
+                    .map_err(|e| Error::from(e).in_script(i))?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        server_version::query_version(self, Backend::Sqlite, "SELECT sqlite_version()")
+    }
+
+    /// Looks for the table in the main and the attached databases.
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        for db_name in self.database_names()? {
+            let sql = format!(
+                "SELECT COUNT(*) FROM {}.sqlite_master WHERE type = 'table' AND name = ?",
+                visitor::Sqlite::delimited_identifiers(vec![&db_name])
+            );
+
+            let res = self.query_raw(&sql, &[table.into()])?;
+
+            if res.first().and_then(|row| row[0].as_i64()).unwrap_or(0) > 0 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        let sql = format!(
+            "SELECT name FROM {}.sqlite_master \
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            visitor::Sqlite::delimited_identifiers(vec![schema])
+        );
+
+        let res = self.query_raw(&sql, &[])?;
+        introspection::names(res)
+    }
+
+    /// Looks for the table in the main and the attached databases.
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        let sql = format!(
+            "PRAGMA table_info({})",
+            visitor::Sqlite::delimited_identifiers(vec![table])
+        );
+
+        let res = self.query_raw(&sql, &[])?;
+
+        // cid, name, type, notnull, dflt_value, pk
+        res.into_iter()
+            .map(|row| {
+                let name = row[1].as_str().unwrap_or("").to_string();
+                let data_type = row[2].as_str().unwrap_or("").to_string();
+
+                Ok(ColumnDescription {
+                    name,
+                    column_type: ColumnType::from_data_type(&data_type),
+                    data_type,
+                    nullable: row[3].as_i64() == Some(0),
+                    default: row[4].as_str().map(String::from),
+                })
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::connector::Queryable;
+    use crate::{
+        ast::{
+            asterisk, count, excluded, Column, Comparable, DatabaseValue, Delete, Insert,
+            OnConflict, Row, Select, Table, Update,
+        },
+        connector::{QueryInfo, Queryable, UpdateAssignment, UpsertOutcome},
+    };
 
     #[test]
     fn should_provide_a_database_connection() {
@@ -202,30 +1048,1131 @@ mod tests {
     }
 
     #[test]
-    fn should_provide_a_database_transaction() {
-        let mut connection = Sqlite::new(String::from("db/test.db")).unwrap();
-        let mut tx = connection.start_transaction().unwrap();
-        let res = tx.query_raw("SELECT * FROM sqlite_master", &[]).unwrap();
+    fn should_ping_the_database() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection.ping().unwrap();
 
-        assert!(res.is_empty());
+        let mut tx = connection.start_transaction().unwrap();
+        tx.ping().unwrap();
     }
 
-    #[allow(unused)]
-    const TABLE_DEF: &str = r#"
-    CREATE TABLE USER (
-        ID INT PRIMARY KEY     NOT NULL,
-        NAME           TEXT    NOT NULL,
-        AGE            INT     NOT NULL,
-        SALARY         REAL
-    );
-    "#;
+    #[test]
+    fn should_return_the_inserted_row() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
 
-    #[allow(unused)]
-    const CREATE_USER: &str = r#"
-    INSERT INTO USER (ID,NAME,AGE,SALARY)
+        connection
+            .raw_cmd(
+                "CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, lives INTEGER DEFAULT 9)",
+            )
+            .unwrap();
+
+        let insert = Insert::single_into("cats").value("name", "musti");
+        let row = connection
+            .insert_returning(insert.into(), vec!["id".into(), "lives".into()])
+            .unwrap();
+
+        assert_eq!(Some(1), row["id"].as_i64());
+        assert_eq!(Some(9), row["lives"].as_i64());
+        assert!(row.get("name").is_none());
+
+        let mut tx = connection.start_transaction().unwrap();
+        let insert = Insert::single_into("cats").value("name", "naukio");
+        let row = tx.insert_returning(insert.into(), Vec::new()).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(Some(2), row["id"].as_i64());
+        assert_eq!(Some("naukio"), row["name"].as_str());
+    }
+
+    #[test]
+    fn should_not_return_a_row_if_nothing_was_inserted() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)")
+            .unwrap();
+
+        let insert = Insert::single_into("cats")
+            .value("id", 1)
+            .value("name", "musti");
+        connection
+            .insert_returning(insert.into(), Vec::new())
+            .unwrap();
+
+        let insert = Insert::single_into("cats")
+            .value("id", 1)
+            .value("name", "naukio");
+        let insert = Insert::from(insert).on_conflict(OnConflict::DoNothing);
+
+        match connection.insert_returning(insert, Vec::new()) {
+            Err(Error::NotFound) => (),
+            res => panic!("Expected `NotFound`, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn setting_the_schema_is_not_supported() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        assert!(connection.set_schema("tenant").is_err());
+    }
+
+    #[test]
+    fn should_query_single_rows_and_scalars() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd(
+                "CREATE TABLE single_cats (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO single_cats VALUES (1, 'musti'), (2, 'naukio'), (3, 'musti');",
+            )
+            .unwrap();
+
+        let mut by_name = |name: &'static str| {
+            let select = Select::from_table("single_cats").so_that("name".equals(name));
+            connection.query(select.into()).unwrap().into_single()
+        };
+
+        match by_name("nobody") {
+            Err(crate::error::Error::NotFound) => (),
+            res => panic!("Expected no rows, got {:?}", res),
+        }
+
+        let row = by_name("naukio").unwrap();
+        assert_eq!(Some(2), row["id"].as_i64());
+
+        match by_name("musti") {
+            Err(crate::error::Error::TooManyRows { count: 2 }) => (),
+            res => panic!("Expected too many rows, got {:?}", res),
+        }
+
+        let count: i64 = connection
+            .query_scalar(
+                Select::from_table("single_cats")
+                    .value(count(asterisk()))
+                    .into(),
+            )
+            .unwrap();
+
+        assert_eq!(3, count);
+    }
+
+    #[test]
+    fn should_upsert_with_the_excluded_values() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, lives INTEGER)")
+            .unwrap();
+
+        for name in &["musti", "naukio"] {
+            let result = connection
+                .upsert(
+                    "cats".into(),
+                    vec!["id".into()],
+                    vec![
+                        ("id".into(), 1.into()),
+                        ("name".into(), (*name).into()),
+                        ("lives".into(), 9.into()),
+                    ],
+                    vec![
+                        ("name".into(), excluded("name")),
+                        ("lives".into(), 8.into()),
+                    ],
+                )
+                .unwrap();
+
+            assert_eq!(1, result.rows_affected);
+            assert_eq!(Some(UpsertOutcome::Unknown), result.upsert);
+        }
+
+        let res = connection
+            .query_raw("SELECT name, lives FROM cats", &[])
+            .unwrap();
+
+        assert_eq!(1, res.len());
+        assert_eq!(Some("naukio"), res.first().and_then(|row| row[0].as_str()));
+        assert_eq!(Some(8), res.first().and_then(|row| row[1].as_i64()));
+
+        let missing_updates = connection.upsert(
+            "cats".into(),
+            vec!["id".into()],
+            vec![("id".into(), 1.into())],
+            Vec::new(),
+        );
+
+        assert!(missing_updates.is_err());
+    }
+
+    #[test]
+    fn should_report_the_affected_rows_of_a_write() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)")
+            .unwrap();
+
+        let insert = Insert::multi_into("cats", vec!["name"])
+            .values(vec!["musti"])
+            .values(vec!["naukio"]);
+
+        let result = connection.execute_with_result(insert.into()).unwrap();
+
+        assert_eq!(2, result.rows_affected);
+        assert_eq!(Some(Id::Int(2)), result.last_insert_id);
+
+        let delete = Delete::from_table("cats").so_that("name".equals("musti"));
+        let result = connection.execute_with_result(delete.into()).unwrap();
+
+        assert_eq!(1, result.rows_affected);
+        assert!(result.returning.is_none());
+    }
+
+    #[test]
+    fn should_report_negative_ids() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT)")
+            .unwrap();
+
+        let insert = Insert::single_into("cats")
+            .value("id", -5)
+            .value("name", "musti");
+
+        let result = connection.execute_with_result(insert.into()).unwrap();
+
+        assert_eq!(Some(Id::Int(-5)), result.last_insert_id);
+    }
+
+    #[test]
+    fn should_provide_a_database_transaction() {
+        let mut connection = Sqlite::new(String::from("db/test.db")).unwrap();
+        let mut tx = connection.start_transaction().unwrap();
+        let res = tx.query_raw("SELECT * FROM sqlite_master", &[]).unwrap();
+
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn should_hand_the_connection_back_from_an_owned_transaction() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY)")
+            .unwrap();
+
+        let mut tx = connection.into_transaction().unwrap();
+        tx.raw_cmd("INSERT INTO cats (id) VALUES (1)").unwrap();
+        let connection = tx.commit().unwrap();
+
+        let mut tx = connection.into_transaction().unwrap();
+        tx.raw_cmd("INSERT INTO cats (id) VALUES (2)").unwrap();
+        let mut connection = tx.rollback().unwrap();
+
+        let res = connection.query_raw("SELECT id FROM cats", &[]).unwrap();
+        assert_eq!(1, res.len());
+    }
+
+    #[test]
+    fn should_call_the_logger_for_every_query() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let collector = seen.clone();
+
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection.set_logger(
+            QueryLogger::new(move |info: &QueryInfo| {
+                assert!(info.params.is_none());
+
+                collector.lock().unwrap().push((
+                    info.sql.to_string(),
+                    info.param_count,
+                    info.rows,
+                    info.error.is_some(),
+                ));
+            })
+            .redact_params(),
+        );
+
+        connection.raw_cmd("CREATE TABLE logged (id)").unwrap();
+
+        let mut tx = connection.start_transaction().unwrap();
+        tx.execute_raw("INSERT INTO logged VALUES (?)", &[1.into()]).unwrap();
+        tx.commit().unwrap();
+
+        connection.query_raw("SELECT id FROM logged", &[]).unwrap();
+        connection
+            .execute(Update::table("logged").set("id", 2).into())
+            .unwrap();
+        assert!(connection.query_raw("SELECT * FROM nope", &[]).is_err());
+
+        let expected = vec![
+            (String::from("CREATE TABLE logged (id)"), 0, None, false),
+            (String::from("BEGIN"), 0, None, false),
+            (String::from("INSERT INTO logged VALUES (?)"), 1, Some(1), false),
+            (String::from("COMMIT"), 0, None, false),
+            (String::from("SELECT id FROM logged"), 0, Some(1), false),
+            (
+                String::from("UPDATE `logged` SET `id` = ?"),
+                1,
+                Some(1),
+                false,
+            ),
+            (String::from("SELECT * FROM nope"), 0, None, true),
+        ];
+
+        assert_eq!(expected, *seen.lock().unwrap());
+    }
+
+    #[test]
+    fn should_refuse_to_turn_off_foreign_keys_in_a_transaction() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection
+            .execute_script(
+                "CREATE TABLE owners (id INTEGER PRIMARY KEY);
+                 CREATE TABLE cats (owner_id INTEGER REFERENCES owners (id));",
+            )
+            .unwrap();
+
+        let mut tx = connection.start_transaction().unwrap();
+        let res = tx.without_foreign_keys(|conn| conn.raw_cmd("INSERT INTO cats VALUES (1)"));
+
+        match res {
+            Err(Error::UnsupportedOperation(_)) => (),
+            res => panic!("Expected `UnsupportedOperation`, got {:?}", res),
+        }
+
+        assert!(tx.foreign_key_checks().unwrap());
+        assert!(tx.query_raw("SELECT * FROM cats", &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_restore_foreign_key_checks_after_a_failure() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection
+            .execute_script(
+                "CREATE TABLE owners (id INTEGER PRIMARY KEY);
+                 CREATE TABLE cats (owner_id INTEGER REFERENCES owners (id));",
+            )
+            .unwrap();
+
+        assert!(connection.raw_cmd("INSERT INTO cats VALUES (1)").is_err());
+
+        let res = connection.without_foreign_keys(|conn| {
+            conn.raw_cmd("INSERT INTO cats VALUES (1)")?;
+            conn.raw_cmd("INSERT INTO nope VALUES (1)")
+        });
+
+        assert!(res.is_err());
+        assert!(connection.foreign_key_checks().unwrap());
+        assert!(connection.raw_cmd("INSERT INTO cats VALUES (2)").is_err());
+
+        let res = connection.query_raw("SELECT * FROM cats", &[]).unwrap();
+        assert_eq!(1, res.len());
+    }
+
+    #[test]
+    fn should_create_and_drop_a_database_file() {
+        let mut connection = Sqlite::new("db/test.db").unwrap();
+        let _ = connection.drop_database("created_db", true);
+
+        connection.create_database("created_db").unwrap();
+        assert!(Path::new("db/created_db.db").exists());
+
+        connection
+            .raw_cmd("CREATE TABLE created_db.cats (name TEXT)")
+            .unwrap();
+
+        match connection.create_database("created_db") {
+            Err(Error::DatabaseAlreadyExists { db_name, .. }) => assert_eq!("created_db", db_name),
+            res => panic!("Expected `DatabaseAlreadyExists`, got {:?}", res),
+        }
+
+        connection.drop_database("created_db", false).unwrap();
+        assert!(!Path::new("db/created_db.db").exists());
+
+        match connection.drop_database("created_db", false) {
+            Err(Error::DatabaseDoesNotExist { db_name, .. }) => assert_eq!("created_db", db_name),
+            res => panic!("Expected `DatabaseDoesNotExist`, got {:?}", res),
+        }
+
+        connection.drop_database("created_db", true).unwrap();
+        assert!(connection.create_database("../escape").is_err());
+        assert!(Sqlite::new_in_memory().unwrap().create_database("memory_db").is_err());
+    }
+
+    #[allow(unused)]
+    const TABLE_DEF: &str = r#"
+    CREATE TABLE USER (
+        ID INT PRIMARY KEY     NOT NULL,
+        NAME           TEXT    NOT NULL,
+        AGE            INT     NOT NULL,
+        SALARY         REAL
+    );
+    "#;
+
+    #[allow(unused)]
+    const CREATE_USER: &str = r#"
+    INSERT INTO USER (ID,NAME,AGE,SALARY)
     VALUES (1, 'Joe', 27, 20000.00 );
     "#;
 
+    #[test]
+    fn should_stop_visiting_the_rows_on_an_error() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY)")
+            .unwrap();
+
+        connection
+            .raw_cmd("INSERT INTO cats (id) VALUES (1), (2), (3), (4)")
+            .unwrap();
+
+        let mut seen = Vec::new();
+
+        let res = connection.query_each(Select::from_table("cats").into(), &mut |row| {
+            let id = row["id"].as_i64().unwrap();
+            seen.push(id);
+
+            if id == 2 {
+                Err(Error::NotFound)
+            } else {
+                Ok(())
+            }
+        });
+
+        match res {
+            Err(Error::NotFound) => (),
+            res => panic!("Expected `NotFound`, got {:?}", res),
+        }
+
+        assert_eq!(vec![1, 2], seen);
+
+        let visited = connection
+            .query_each(Select::from_table("cats").into(), &mut |_| Ok(()))
+            .unwrap();
+
+        assert_eq!(4, visited);
+    }
+
+    #[test]
+    fn should_borrow_text_and_bytes_from_the_rows() {
+        use std::borrow::Cow;
+
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, photo BLOB)")
+            .unwrap();
+
+        connection
+            .raw_cmd("INSERT INTO cats (name, photo) VALUES ('musti', X'00ff'), ('naukio', NULL)")
+            .unwrap();
+
+        let mut kept = Vec::new();
+
+        let visited = connection
+            .query_each_borrowed(Select::from_table("cats").into(), &mut |row| {
+                match &row["name"] {
+                    ParameterizedValue::Text(Cow::Borrowed(_)) => (),
+                    value => panic!("Expected borrowed text, got {:?}", value),
+                }
+
+                match &row["photo"] {
+                    ParameterizedValue::Bytes(Cow::Borrowed(_)) | ParameterizedValue::Null => (),
+                    value => panic!("Expected borrowed bytes, got {:?}", value),
+                }
+
+                kept.push(row.into_owned());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(2, visited);
+        assert_eq!(Some("musti"), kept[0]["name"].as_str());
+        assert_eq!(Some(&[0u8, 255][..]), kept[0]["photo"].as_bytes());
+        assert_eq!(Some("naukio"), kept[1]["name"].as_str());
+        assert!(kept[1]["photo"].is_null());
+    }
+
+    #[test]
+    fn should_not_allocate_for_the_borrowed_text_and_bytes() {
+        use crate::connector::result_set::tests::allocations;
+
+        const ROWS: usize = 1000;
+
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .execute_script(
+                "CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, nick TEXT, photo BLOB);
+                 INSERT INTO cats (name, nick, photo)
+                 WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 1000)
+                 SELECT 'musti', 'naukio', X'00ff' FROM c;",
+            )
+            .unwrap();
+
+        let query = || {
+            Query::from(
+                Select::from_table("cats")
+                    .column("name")
+                    .column("nick")
+                    .column("photo"),
+            )
+        };
+
+        let mut length = 0;
+
+        let mut f = |row: BorrowedRow<'_>| -> crate::Result<()> {
+            length += row["name"].as_str().map(str::len).unwrap_or(0);
+            length += row["nick"].as_str().map(str::len).unwrap_or(0);
+            length += row["photo"].as_bytes().map(<[u8]>::len).unwrap_or(0);
+            Ok(())
+        };
+
+        // Caches the statement.
+        connection.query_each_borrowed(query(), &mut f).unwrap();
+
+        let q = query();
+        let before = allocations();
+        let visited = connection.query_each_borrowed(q, &mut f).unwrap();
+        let allocated = allocations() - before;
+
+        assert_eq!(ROWS as u64, visited);
+        assert_eq!(2 * ROWS * 13, length);
+
+        // The column and value vectors of each row, and none for the three
+        // text and blob cells.
+        assert!(allocated < 3 * ROWS, "{} allocations", allocated);
+    }
+
+    #[test]
+    fn should_interrupt_a_query_exceeding_the_timeout() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd(
+                "CREATE VIEW endless AS \
+                 WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                 SELECT max(x) FROM c",
+            )
+            .unwrap();
+
+        let query = Select::from_table("endless");
+
+        match connection.query_with_timeout(query.into(), Duration::from_millis(50)) {
+            Err(Error::Timeout) => (),
+            res => panic!("Expected `Timeout`, got {:?}", res.map(|_| ())),
+        }
+
+        let res = connection
+            .query_with_timeout(Select::default().value(1).into(), Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(1, res.len());
+    }
+
+    #[test]
+    fn should_interrupt_a_borrowed_query_exceeding_the_timeout() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        let endless = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                       SELECT max(x) FROM c";
+
+        let res = connection.query_each_borrowed_with_timeout(
+            Query::from(endless),
+            Duration::from_millis(50),
+            &mut |_| Ok(()),
+        );
+
+        match res {
+            Err(Error::Timeout) => (),
+            res => panic!("Expected `Timeout`, got {:?}", res),
+        }
+
+        let visited = connection
+            .query_each_borrowed_with_timeout(
+                Select::default().value(1).into(),
+                Duration::from_secs(10),
+                &mut |_| Ok(()),
+            )
+            .unwrap();
+
+        assert_eq!(1, visited);
+    }
+
+    #[test]
+    fn should_time_out_the_queries_of_concurrent_connections() {
+        let timeouts = vec![Duration::from_millis(300), Duration::from_millis(50)];
+
+        let handles: Vec<_> = timeouts
+            .into_iter()
+            .map(|timeout| {
+                std::thread::spawn(move || {
+                    let mut connection = Sqlite::new_in_memory().unwrap();
+
+                    let endless =
+                        "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                                   SELECT max(x) FROM c";
+
+                    connection.query_with_timeout(Query::from(endless), timeout)
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            match handle.join().unwrap() {
+                Err(Error::Timeout) => (),
+                res => panic!("Expected `Timeout`, got {:?}", res.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn should_share_the_database_with_the_raw_connection() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .with_raw_connection(|client| {
+                client.execute_batch("CREATE TABLE cats (name TEXT); INSERT INTO cats VALUES ('musti');")
+            })
+            .unwrap();
+
+        let res = connection.query_raw("SELECT name FROM cats", &[]).unwrap();
+        assert_eq!(Some("musti"), res.first().and_then(|row| row[0].as_str()));
+    }
+
+    #[test]
+    fn should_describe_the_tables() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .execute_script(
+                "CREATE TABLE cats (id INTEGER PRIMARY KEY, name VARCHAR(255) NOT NULL, born DATETIME);
+                 CREATE TABLE dogs (id INTEGER PRIMARY KEY);",
+            )
+            .unwrap();
+
+        assert!(connection.table_exists("cats").unwrap());
+        assert!(!connection.table_exists("birds").unwrap());
+
+        assert_eq!(
+            vec!["cats", "dogs"],
+            connection.list_tables("main").unwrap()
+        );
+
+        let columns = connection.describe_table("cats").unwrap();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(vec!["id", "name", "born"], names);
+
+        assert_eq!(ColumnType::Integer, columns[0].column_type);
+        assert_eq!(ColumnType::Text, columns[1].column_type);
+        assert_eq!(ColumnType::DateTime, columns[2].column_type);
+
+        assert!(!columns[1].nullable);
+        assert!(columns[2].nullable);
+        assert_eq!(None, columns[2].default);
+
+        assert!(connection.describe_table("birds").unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_find_the_tables_of_attached_databases() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+        connection.attach_database(":memory:", "attached").unwrap();
+        connection
+            .raw_cmd("CREATE TABLE attached.birds (id INTEGER PRIMARY KEY)")
+            .unwrap();
+
+        assert!(connection.table_exists("birds").unwrap());
+        assert_eq!(vec!["birds"], connection.list_tables("attached").unwrap());
+        assert_eq!(1, connection.describe_table("birds").unwrap().len());
+    }
+
+    #[test]
+    fn should_insert_many_rows_in_batches() {
+        let mut connection = Sqlite::new("db/test.db").unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE batch (a INT, b TEXT, c REAL, d INT, e TEXT)")
+            .unwrap();
+
+        let rows: Vec<Row> = (0..10_000)
+            .map(|i| Row::from((i, "musti", 1.5, i * 2, "naukio")))
+            .collect();
+
+        let columns: Vec<Column> = vec!["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(Column::from)
+            .collect();
+
+        let result = connection
+            .insert_many(Table::from("batch"), columns, rows)
+            .unwrap();
+
+        assert_eq!(10_000, result.count);
+
+        // Statements of 199 rows of five parameters, each reporting the
+        // rowid of its last row.
+        let ids = result.last_insert_ids;
+        assert_eq!(51, ids.len());
+        assert_eq!(Id::Int(199), ids[0]);
+        assert_eq!(Id::Int(398), ids[1]);
+        assert_eq!(Id::Int(9950), ids[49]);
+        assert_eq!(Id::Int(10_000), ids[50]);
+
+        let rows = connection
+            .query_raw("SELECT COUNT(*), SUM(a) FROM batch", &[])
+            .unwrap();
+
+        let row = rows.first().unwrap();
+        assert_eq!(Some(10_000), row[0].as_i64());
+        assert_eq!(Some(49_995_000), row[1].as_i64());
+    }
+
+    #[test]
+    fn should_update_many_rows_in_batches() {
+        let mut connection = Sqlite::new_in_memory().unwrap();
+
+        connection
+            .raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY, name TEXT, age INT)")
+            .unwrap();
+
+        let rows: Vec<Row> = (1..=1000).map(|i| Row::from((i, "musti", 1))).collect();
+        let columns = vec!["id".into(), "name".into(), "age".into()];
+
+        connection
+            .insert_many("cats".into(), columns, rows)
+            .unwrap();
+
+        let mut assignments: Vec<UpdateAssignment> = (1..=1000)
+            .map(|i| {
+                let mut values = vec![(Column::from("age"), DatabaseValue::from(i * 2))];
+
+                if i == 1 {
+                    values.push((Column::from("name"), DatabaseValue::from("naukio")));
+                }
+
+                (ParameterizedValue::from(i), values)
+            })
+            .collect();
+
+        assignments.push((
+            ParameterizedValue::from(5000),
+            vec![(Column::from("age"), DatabaseValue::from(0))],
+        ));
+
+        let count = connection
+            .update_many("cats".into(), "id".into(), assignments)
+            .unwrap();
+
+        assert_eq!(1000, count);
+
+        let rows = connection
+            .query_raw("SELECT SUM(age), MAX(name), MIN(name) FROM cats", &[])
+            .unwrap();
+
+        let row = rows.first().unwrap();
+        assert_eq!(Some(1_001_000), row[0].as_i64());
+        assert_eq!(Some("naukio"), row[1].as_str());
+        assert_eq!(Some("musti"), row[2].as_str());
+    }
+
+    #[test]
+    fn should_parse_in_memory_urls() {
+        let params = SqliteParams::try_from("file::memory:").unwrap();
+        assert_eq!(PathBuf::from(":memory:"), params.file_path);
+        assert_eq!(Some("main"), params.schema.as_ref().map(|s| s.as_str()));
+
+        let params = SqliteParams::try_from("sqlite::memory:?cache=shared").unwrap();
+        assert_eq!(PathBuf::from("file::memory:?cache=shared"), params.file_path);
+        assert_eq!(Some("memory"), params.schema.as_ref().map(|s| s.as_str()));
+
+        let params = SqliteParams::try_from("file:tenant?mode=memory&cache=shared").unwrap();
+        assert_eq!(
+            PathBuf::from("file:tenant?mode=memory&cache=shared"),
+            params.file_path
+        );
+        assert_eq!(Some("tenant"), params.schema.as_ref().map(|s| s.as_str()));
+    }
+
+    #[test]
+    fn should_work_with_a_private_in_memory_database() {
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd(TABLE_DEF).unwrap();
+
+        let mut tx = conn.start_transaction().unwrap();
+        tx.raw_cmd(CREATE_USER).unwrap();
+        tx.commit().unwrap();
+
+        let rows = conn.query_raw("SELECT NAME FROM main.USER", &[]).unwrap();
+        assert_eq!(Some("Joe"), rows.first().and_then(|row| row[0].as_str()));
+
+        let mut other = Sqlite::new_in_memory().unwrap();
+        assert!(other.query_raw("SELECT * FROM USER", &[]).is_err());
+    }
+
+    #[test]
+    fn should_share_a_named_in_memory_database() {
+        let url = "file:should_share_a_named_in_memory_database?mode=memory&cache=shared";
+        let schema = "should_share_a_named_in_memory_database";
+
+        let mut first = Sqlite::try_from(url).unwrap();
+        first.attach_file(schema).unwrap();
+        first
+            .raw_cmd(&format!("CREATE TABLE {}.cats (name TEXT)", schema))
+            .unwrap();
+        first
+            .raw_cmd(&format!("INSERT INTO {}.cats VALUES ('musti')", schema))
+            .unwrap();
+
+        let mut second = Sqlite::try_from(url).unwrap();
+        second.attach_file(schema).unwrap();
+
+        let rows = second
+            .query_raw(&format!("SELECT name FROM {}.cats", schema), &[])
+            .unwrap();
+
+        assert_eq!(Some("musti"), rows.first().and_then(|row| row[0].as_str()));
+    }
+
+    #[test]
+    fn should_apply_the_pragmas_after_connecting() {
+        let url = "file:db/pragmas.db?journal_mode=WAL&busy_timeout=1000&foreign_keys=false";
+        let mut conn = Sqlite::try_from(url).unwrap();
+        conn.attach_file("pragmas").unwrap();
+
+        let rows = conn.query_raw("PRAGMA pragmas.journal_mode", &[]).unwrap();
+        assert_eq!(Some("wal"), rows.first().and_then(|row| row[0].as_str()));
+
+        let rows = conn.query_raw("PRAGMA busy_timeout", &[]).unwrap();
+        assert_eq!(Some(1000), rows.first().and_then(|row| row[0].as_i64()));
+
+        let rows = conn.query_raw("PRAGMA foreign_keys", &[]).unwrap();
+        assert_eq!(Some(0), rows.first().and_then(|row| row[0].as_i64()));
+
+        conn.pragma("foreign_keys", "ON").unwrap();
+
+        let rows = conn.query_raw("PRAGMA foreign_keys", &[]).unwrap();
+        assert_eq!(Some(1), rows.first().and_then(|row| row[0].as_i64()));
+
+        assert!(conn.pragma("foreign_keys", "ON; DROP TABLE x").is_err());
+    }
+
+    #[test]
+    fn should_reject_invalid_pragma_values() {
+        assert!(SqliteParams::try_from("file:db/test.db?journal_mode=WAL;").is_err());
+        assert!(SqliteParams::try_from("file:db/test.db?busy_timeout=soon").is_err());
+        assert!(SqliteParams::try_from("file:db/test.db?foreign_keys=maybe").is_err());
+    }
+
+    #[test]
+    fn should_name_the_invalid_parameter() {
+        let field = |res: crate::Result<SqliteParams>| match res {
+            Err(Error::InvalidConnectionParameter { field, .. }) => field,
+            Err(e) => panic!("Expected `InvalidConnectionParameter`, got {:?}", e),
+            Ok(_) => panic!("Expected an error"),
+        };
+
+        assert_eq!("file_path", field(SqliteParams::builder().build()));
+        assert_eq!("file_path", field(SqliteParams::try_from("file:db")));
+
+        assert_eq!(
+            "journal_mode",
+            field(SqliteParams::try_from("file:db/test.db?journal_mode=WAL;"))
+        );
+
+        assert_eq!(
+            "connection_limit",
+            field(
+                SqliteParams::builder()
+                    .in_memory()
+                    .connection_limit(0)
+                    .build()
+            )
+        );
+    }
+
+    #[test]
+    fn should_round_trip_bytes() {
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE files (id INTEGER PRIMARY KEY, content BLOB)")
+            .unwrap();
+
+        let content: Vec<u8> = vec![0, b'm', 0, 159, 146, 150, 255, b'\n'];
+        let insert = Insert::single_into("files").value("content", content.clone());
+
+        conn.execute(insert.into()).unwrap();
+
+        let select = Select::from_table("files").so_that("content".equals(&content[..]));
+        let rows = conn.query(select.into()).unwrap();
+
+        assert_eq!(
+            Some(&content[..]),
+            rows.first().unwrap()["content"].as_bytes()
+        );
+    }
+
+    #[cfg(feature = "uuid-0_7")]
+    #[test]
+    fn should_round_trip_uuids() {
+        use uuid::Uuid;
+
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE tokens (id UUID PRIMARY KEY, name TEXT)")
+            .unwrap();
+
+        let musti = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let naukio = Uuid::parse_str("936da01f-9abd-4d9d-80c7-02af85c822a8").unwrap();
+
+        for (id, name) in &[(musti, "musti"), (naukio, "naukio")] {
+            let insert = Insert::single_into("tokens")
+                .value("id", *id)
+                .value("name", *name);
+            conn.execute(insert.into()).unwrap();
+        }
+
+        let select = Select::from_table("tokens").so_that("id".equals(musti));
+        let rows = conn.query(select.into()).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(Some(musti), rows.first().unwrap()["id"].as_uuid());
+
+        let select = Select::from_table("tokens").so_that("id".in_selection(vec![musti, naukio]));
+        assert_eq!(2, conn.query(select.into()).unwrap().len());
+    }
+
+    #[cfg(feature = "rust_decimal-1")]
+    #[test]
+    fn should_round_trip_decimals() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE prices (id INTEGER PRIMARY KEY, price DECIMAL(38, 18))")
+            .unwrap();
+
+        let small = Decimal::from_str("0.1").unwrap();
+        let large = Decimal::from_str("1234567890.12345").unwrap();
+
+        for price in &[small, large] {
+            let insert = Insert::single_into("prices").value("price", *price);
+            conn.execute(insert.into()).unwrap();
+        }
+
+        let rows = conn
+            .query(Select::from_table("prices").order_by("id").into())
+            .unwrap();
+
+        assert_eq!(Some(small), rows.get(0).unwrap()["price"].as_decimal());
+        assert_eq!(Some(large), rows.get(1).unwrap()["price"].as_decimal());
+
+        let select = Select::from_table("prices").so_that("price".equals(large));
+        assert_eq!(1, conn.query(select.into()).unwrap().len());
+    }
+
+    #[cfg(feature = "json-1")]
+    #[test]
+    fn should_round_trip_json() {
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE documents (id INTEGER PRIMARY KEY, body JSON, note TEXT)")
+            .unwrap();
+
+        let body = serde_json::json!({
+            "name": "musti",
+            "owner": { "name": "Julius", "cats": [1, 2] },
+        });
+
+        let insert = Insert::single_into("documents")
+            .value("body", body.clone())
+            .value("note", "{\"not\": \"json\"}");
+
+        conn.execute(insert.into()).unwrap();
+
+        let select = Select::from_table("documents").so_that("body".equals(body.clone()));
+        let rows = conn.query(select.into()).unwrap();
+        let row = rows.first().unwrap();
+
+        assert_eq!(Some(&body), row["body"].as_json());
+        assert_eq!(Some("{\"not\": \"json\"}"), row["note"].as_str());
+    }
+
+    #[cfg(feature = "chrono-0_4")]
+    #[test]
+    fn should_round_trip_date_times() {
+        use chrono::{DateTime, Utc};
+
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE events (id INTEGER PRIMARY KEY, at DATETIME)")
+            .unwrap();
+
+        let at = DateTime::parse_from_rfc3339("2019-07-27T07:30:30.123+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        conn.execute(Insert::single_into("events").value("at", at).into())
+            .unwrap();
+
+        conn.raw_cmd("INSERT INTO events (at) VALUES ('2019-07-27 05:30:30')")
+            .unwrap();
+
+        let rows = conn
+            .query(Select::from_table("events").order_by("id").into())
+            .unwrap();
+
+        let stored = rows.get(0).unwrap()["at"].as_datetime();
+        let from_text = rows.get(1).unwrap()["at"].as_datetime();
+
+        assert_eq!(Some(at), stored);
+        assert_eq!(
+            "2019-07-27T05:30:30.123+00:00",
+            stored.unwrap().to_rfc3339()
+        );
+        assert_eq!("2019-07-27T05:30:30+00:00", from_text.unwrap().to_rfc3339());
+
+        let select = Select::from_table("events").so_that("at".less_than(Utc::now()));
+        assert_eq!(2, conn.query(select.into()).unwrap().len());
+
+        let select = Select::from_table("events").so_that("at".greater_than(at));
+        assert!(conn.query(select.into()).unwrap().is_empty());
+
+        let sql = "SELECT id FROM events WHERE at < CURRENT_TIMESTAMP";
+        assert_eq!(2, conn.query_raw(sql, &[]).unwrap().len());
+    }
+
+    #[cfg(feature = "chrono-0_4")]
+    #[test]
+    fn should_round_trip_dates_and_times() {
+        use chrono::{NaiveDate, NaiveTime};
+
+        let first_birthday = NaiveDate::from_ymd(1969, 7, 20);
+        let second_birthday = NaiveDate::from_ymd(1990, 1, 1);
+        let first_alarm = NaiveTime::from_hms(7, 30, 0);
+        let second_alarm = NaiveTime::from_hms_micro(20, 15, 30, 500_000);
+
+        let mut conn = Sqlite::new_in_memory().unwrap();
+        conn.raw_cmd("CREATE TABLE people (id INTEGER PRIMARY KEY, birthday DATE, alarm TIME)")
+            .unwrap();
+
+        let insert = Insert::multi_into("people", vec!["id", "birthday", "alarm"])
+            .values((1, first_birthday, first_alarm))
+            .values((2, second_birthday, second_alarm));
+
+        conn.execute(insert.into()).unwrap();
+
+        let rows = conn
+            .query(Select::from_table("people").order_by("id").into())
+            .unwrap();
+
+        assert_eq!(
+            Some(first_birthday),
+            rows.get(0).unwrap()["birthday"].as_date()
+        );
+        assert_eq!(Some(first_alarm), rows.get(0).unwrap()["alarm"].as_time());
+        assert_eq!(
+            Some(second_birthday),
+            rows.get(1).unwrap()["birthday"].as_date()
+        );
+        assert_eq!(Some(second_alarm), rows.get(1).unwrap()["alarm"].as_time());
+
+        let select = Select::from_table("people").so_that("birthday".equals(second_birthday));
+        let rows = conn.query(select.into()).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(Some(2), rows.first().unwrap()["id"].as_i64());
+
+        let select = Select::from_table("people").so_that("birthday".between(
+            NaiveDate::from_ymd(1960, 1, 1),
+            NaiveDate::from_ymd(1970, 1, 1),
+        ));
+        let rows = conn.query(select.into()).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(Some(1), rows.first().unwrap()["id"].as_i64());
+    }
+
+    #[test]
+    fn should_map_a_busy_database_to_a_lock_timeout() {
+        let url = "file:db/busy.db?busy_timeout=10";
+
+        let mut first = Sqlite::try_from(url).unwrap();
+        first.attach_file("busy").unwrap();
+        first
+            .raw_cmd("CREATE TABLE IF NOT EXISTS busy.locks (id INT)")
+            .unwrap();
+        first.raw_cmd("BEGIN EXCLUSIVE").unwrap();
+
+        let mut second = Sqlite::try_from(url).unwrap();
+        second.attach_file("busy").unwrap();
+
+        match second.query_raw("SELECT * FROM busy.locks", &[]) {
+            Err(Error::LockTimeout) => (),
+            res => panic!("Expected `LockTimeout`, got {:?}", res.map(|_| ())),
+        }
+
+        first.raw_cmd("ROLLBACK").unwrap();
+    }
+
+    #[test]
+    fn should_work_with_two_attached_databases() {
+        let mut conn = Sqlite::new_in_memory().unwrap();
+
+        for tenant in &["tenant_1", "tenant_2"] {
+            conn.attach_database(":memory:", tenant).unwrap();
+            conn.raw_cmd(&format!("CREATE TABLE {}.users (name TEXT)", tenant))
+                .unwrap();
+        }
+
+        let insert = Insert::single_into(("tenant_1", "users")).value("name", "musti");
+        conn.execute(insert.into()).unwrap();
+
+        let insert = Insert::single_into(("tenant_2", "users")).value("name", "naukio");
+        conn.execute(insert.into()).unwrap();
+
+        let select = Select::from_table(("tenant_2", "users"));
+        let rows = conn.query(select.into()).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(Some("naukio"), rows.first().and_then(|row| row["name"].as_str()));
+
+        conn.detach_database("tenant_2").unwrap();
+
+        let select = Select::from_table(("tenant_2", "users"));
+        assert!(conn.query(select.into()).is_err());
+
+        let select = Select::from_table(("tenant_1", "users"));
+        assert_eq!(1, conn.query(select.into()).unwrap().len());
+    }
+
+    #[test]
+    fn should_enforce_the_attached_database_limit() {
+        let mut conn = Sqlite::new_in_memory().unwrap();
+
+        for i in 0..MAX_ATTACHED_DATABASES {
+            conn.attach_database(":memory:", &format!("db_{}", i)).unwrap();
+        }
+
+        match conn.attach_database(":memory:", "one_too_many") {
+            Err(Error::TooManyAttachedDatabases { limit }) => {
+                assert_eq!(MAX_ATTACHED_DATABASES, limit)
+            }
+            res => panic!("Expected `TooManyAttachedDatabases`, got {:?}", res),
+        }
+
+        // Re-attaching an attached name is fine.
+        conn.attach_database(":memory:", "db_0").unwrap();
+    }
+
+    #[test]
+    fn should_capture_the_statement_cache_size() {
+        let params = SqliteParams::try_from("file:db/test.db?statement_cache_size=10").unwrap();
+        assert_eq!(10, params.statement_cache_size);
+
+        let params = SqliteParams::try_from("file:db/test.db").unwrap();
+        assert_eq!(DEFAULT_STATEMENT_CACHE_SIZE, params.statement_cache_size);
+    }
+
     #[test]
     fn should_map_columns_correctly() {
         let mut connection = Sqlite::try_from("file:db/test.db").unwrap();
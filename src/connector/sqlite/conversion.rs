@@ -1,36 +1,162 @@
+#[cfg(any(
+    feature = "chrono-0_4",
+    feature = "json-1",
+    feature = "rust_decimal-1",
+    feature = "uuid-0_7"
+))]
+use crate::connector::ColumnType;
 use crate::{
     ast::ParameterizedValue,
     connector::queryable::{ToColumnNames, ToRow},
 };
+#[cfg(feature = "chrono-0_4")]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use rusqlite::{types::ValueRef, Row as SqliteRow, Rows as SqliteRows};
+#[cfg(feature = "rust_decimal-1")]
+use rust_decimal::Decimal;
+#[cfg(feature = "uuid-0_7")]
+use uuid::Uuid;
 
 impl<'a> ToRow for SqliteRow<'a> {
     fn to_result_row<'b>(&'b self) -> crate::Result<Vec<ParameterizedValue<'static>>> {
-        let mut row = Vec::new();
-
-        for (i, column) in self.columns().iter().enumerate() {
-            let pv = match self.get_raw(i) {
-                ValueRef::Null => ParameterizedValue::Null,
-                ValueRef::Integer(i) => match column.decl_type() {
-                    Some("BOOLEAN") => {
-                        if i == 0 {
-                            ParameterizedValue::Boolean(false)
-                        } else {
-                            ParameterizedValue::Boolean(true)
-                        }
+        let row = to_borrowed_row(self)
+            .into_iter()
+            .map(ParameterizedValue::into_owned)
+            .collect();
+
+        Ok(row)
+    }
+}
+
+/// The values of the row, borrowing text and binary data from the row.
+pub(crate) fn to_borrowed_row<'b>(row: &'b SqliteRow<'_>) -> Vec<ParameterizedValue<'b>> {
+    let mut values = Vec::new();
+
+    for (i, column) in row.columns().iter().enumerate() {
+        let pv = match row.get_raw(i) {
+            ValueRef::Null => ParameterizedValue::Null,
+            ValueRef::Integer(i) => match column.decl_type() {
+                Some("BOOLEAN") => {
+                    if i == 0 {
+                        ParameterizedValue::Boolean(false)
+                    } else {
+                        ParameterizedValue::Boolean(true)
                     }
+                }
+                #[cfg(feature = "chrono-0_4")]
+                decl_type if is_datetime(decl_type) => match Utc.timestamp_millis_opt(i) {
+                    chrono::LocalResult::Single(dt) => ParameterizedValue::DateTime(dt),
                     _ => ParameterizedValue::Integer(i),
                 },
-                ValueRef::Real(f) => ParameterizedValue::Real(f),
-                ValueRef::Text(s) => ParameterizedValue::Text(s.to_string().into()),
-                ValueRef::Blob(_) => panic!("Blobs not supprted, yet"),
-            };
+                #[cfg(feature = "rust_decimal-1")]
+                decl_type if is_decimal(decl_type) => ParameterizedValue::Decimal(Decimal::from(i)),
+                _ => ParameterizedValue::Integer(i),
+            },
+            #[cfg(feature = "rust_decimal-1")]
+            ValueRef::Real(f) if is_decimal(column.decl_type()) => match f.to_string().parse() {
+                Ok(d) => ParameterizedValue::Decimal(d),
+                Err(_) => ParameterizedValue::Real(f),
+            },
+            ValueRef::Real(f) => ParameterizedValue::Real(f),
+            #[cfg(feature = "rust_decimal-1")]
+            ValueRef::Text(s) if is_decimal(column.decl_type()) => match s.parse() {
+                Ok(d) => ParameterizedValue::Decimal(d),
+                Err(_) => ParameterizedValue::Text(s.into()),
+            },
+            #[cfg(feature = "chrono-0_4")]
+            ValueRef::Text(s) if is_datetime(column.decl_type()) => match parse_datetime(s) {
+                Some(dt) => ParameterizedValue::DateTime(dt),
+                None => ParameterizedValue::Text(s.into()),
+            },
+            #[cfg(feature = "chrono-0_4")]
+            ValueRef::Text(s) if is_date(column.decl_type()) => {
+                match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                    Ok(date) => ParameterizedValue::Date(date),
+                    Err(_) => ParameterizedValue::Text(s.into()),
+                }
+            }
+            #[cfg(feature = "chrono-0_4")]
+            ValueRef::Text(s) if is_time(column.decl_type()) => {
+                match NaiveTime::parse_from_str(s, "%H:%M:%S%.f") {
+                    Ok(time) => ParameterizedValue::Time(time),
+                    Err(_) => ParameterizedValue::Text(s.into()),
+                }
+            }
+            #[cfg(feature = "uuid-0_7")]
+            ValueRef::Text(s) if is_uuid(column.decl_type()) => match Uuid::parse_str(s) {
+                Ok(uuid) => ParameterizedValue::Uuid(uuid),
+                Err(_) => ParameterizedValue::Text(s.into()),
+            },
+            #[cfg(feature = "json-1")]
+            ValueRef::Text(s) if is_json(column.decl_type()) => match serde_json::from_str(s) {
+                Ok(json) => ParameterizedValue::Json(json),
+                Err(_) => ParameterizedValue::Text(s.into()),
+            },
+            ValueRef::Text(s) => ParameterizedValue::Text(s.into()),
+            #[cfg(feature = "uuid-0_7")]
+            ValueRef::Blob(b) if is_uuid(column.decl_type()) => match Uuid::from_slice(b) {
+                Ok(uuid) => ParameterizedValue::Uuid(uuid),
+                Err(_) => ParameterizedValue::Bytes(b.into()),
+            },
+            ValueRef::Blob(b) => ParameterizedValue::Bytes(b.into()),
+        };
 
-            row.push(pv);
-        }
+        values.push(pv);
+    }
 
-        Ok(row)
+    values
+}
+
+/// True if the column is declared as a date-time, e.g. `DATETIME`.
+#[cfg(feature = "chrono-0_4")]
+fn is_datetime(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::DateTime)
+}
+
+/// True if the column is declared as `DATE`, stored as `YYYY-MM-DD` text.
+#[cfg(feature = "chrono-0_4")]
+fn is_date(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::Date)
+}
+
+/// True if the column is declared as `TIME`, stored as `HH:MM:SS` text with
+/// optional fractions of a second.
+#[cfg(feature = "chrono-0_4")]
+fn is_time(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::Time)
+}
+
+/// True if the column is declared as `DECIMAL` or `NUMERIC`. The values
+/// are stored as text, converted to a floating point number by the column
+/// affinity.
+#[cfg(feature = "rust_decimal-1")]
+fn is_decimal(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::Decimal)
+}
+
+/// True if the column is declared as `UUID`, stored as text or 16 bytes.
+#[cfg(feature = "uuid-0_7")]
+fn is_uuid(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::Uuid)
+}
+
+/// True if the column is declared as `JSON`, stored as text.
+#[cfg(feature = "json-1")]
+fn is_json(decl_type: Option<&str>) -> bool {
+    decl_type.map(ColumnType::from_data_type) == Some(ColumnType::Json)
+}
+
+/// Parses an RFC 3339 date-time or the `YYYY-MM-DD HH:MM:SS` format of the
+/// SQLite date functions, taken as UTC.
+#[cfg(feature = "chrono-0_4")]
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
     }
+
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|ts| DateTime::<Utc>::from_utc(ts, Utc))
 }
 
 impl<'a> ToColumnNames for SqliteRows<'a> {
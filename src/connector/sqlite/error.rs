@@ -1,4 +1,7 @@
-use crate::error::Error;
+use crate::{
+    connector::Backend,
+    error::{Error, NativeError},
+};
 use libsqlite3_sys as ffi;
 use rusqlite::types::FromSqlError;
 
@@ -7,53 +10,136 @@ impl From<rusqlite::Error> for Error {
         match e {
             rusqlite::Error::QueryReturnedNoRows => Error::NotFound,
 
-            rusqlite::Error::SqliteFailure(
-                ffi::Error {
-                    code: ffi::ErrorCode::ConstraintViolation,
-                    extended_code: 2067,
-                },
-                Some(description),
-            ) => {
-                let splitted: Vec<&str> = description.split(": ").collect();
-                let splitted: Vec<&str> = splitted[1].split('.').collect();
-
-                Error::UniqueConstraintViolation {
-                    field_name: splitted[1].into(),
-                }
-            }
+            rusqlite::Error::SqliteFailure(error, ref description) => {
+                let mapped = sqlite_error(error, description.as_ref().map(String::as_str));
+                let description = description.clone();
+                let code = Some(error.extended_code.to_string());
+                let native = NativeError::new(Backend::Sqlite, code, description, e);
 
-            rusqlite::Error::SqliteFailure(
-                ffi::Error {
-                    code: ffi::ErrorCode::ConstraintViolation,
-                    extended_code: 1555,
-                },
-                Some(description),
-            ) => {
-                let splitted: Vec<&str> = description.split(": ").collect();
-                let splitted: Vec<&str> = splitted[1].split('.').collect();
-
-                Error::UniqueConstraintViolation {
-                    field_name: splitted[1].into(),
+                match mapped {
+                    Some(mapped) => mapped.with_native(native),
+                    // SQLITE_CANTOPEN without the path of the database.
+                    None if error.code == ffi::ErrorCode::CannotOpen => {
+                        Error::ConnectionError(native.into())
+                    }
+                    None => native.into(),
                 }
             }
 
-            rusqlite::Error::SqliteFailure(
-                ffi::Error {
-                    code: ffi::ErrorCode::ConstraintViolation,
-                    extended_code: 1299,
-                },
-                Some(description),
-            ) => {
-                let splitted: Vec<&str> = description.split(": ").collect();
-                let splitted: Vec<&str> = splitted[1].split('.').collect();
-
-                Error::NullConstraintViolation {
-                    field_name: splitted[1].into(),
-                }
+            e => Error::QueryError(e.into()),
+        }
+    }
+}
+
+/// Maps an error of SQLite, reading the names from the description. `None` if
+/// the code has no variant of its own, or the description doesn't have the
+/// expected names.
+fn sqlite_error(error: ffi::Error, description: Option<&str>) -> Option<Error> {
+    use ffi::ErrorCode::*;
+
+    match (error.code, error.extended_code) {
+        // SQLITE_CONSTRAINT_UNIQUE and SQLITE_CONSTRAINT_PRIMARYKEY
+        (ConstraintViolation, 2067) | (ConstraintViolation, 1555) => description
+            .and_then(constraint_columns)
+            .map(|field_name| Error::UniqueConstraintViolation {
+                field_name,
+                constraint: None,
+                native: None,
+            }),
+
+        // SQLITE_CONSTRAINT_NOTNULL
+        (ConstraintViolation, 1299) => description.and_then(constraint_columns).map(|field_name| {
+            Error::NullConstraintViolation {
+                field_name,
+                native: None,
             }
+        }),
 
-            e => Error::QueryError(e.into()),
+        // SQLITE_CONSTRAINT_FOREIGNKEY, the message doesn't tell which
+        // constraint failed.
+        (ConstraintViolation, 787) => Some(Error::ForeignKeyConstraintViolation {
+            constraint: UNNAMED_CONSTRAINT.into(),
+            native: None,
+        }),
+
+        // SQLITE_CONSTRAINT_CHECK, naming the constraint or, in older
+        // versions, the table.
+        (ConstraintViolation, 275) => Some(Error::CheckConstraintViolation {
+            constraint: description
+                .and_then(|d| d.splitn(2, ": ").nth(1))
+                .unwrap_or(UNNAMED_CONSTRAINT)
+                .into(),
+            native: None,
+        }),
+
+        // SQLITE_CANTOPEN, e.g. a missing directory or an attached file that
+        // can't be created: `unable to open database: /nonexistent/cats.db`.
+        (CannotOpen, _) => {
+            description
+                .and_then(unopened_path)
+                .map(|db_name| Error::DatabaseDoesNotExist {
+                    db_name: db_name.into(),
+                    native: None,
+                })
         }
+
+        // SQLITE_BUSY, locked by another connection past the `busy_timeout`.
+        (DatabaseBusy, _) => Some(Error::LockTimeout),
+
+        // Interrupted by a `CancelToken`, or by `query_with_timeout` turning
+        // the error into a timeout.
+        (OperationInterrupted, _) => Some(Error::QueryCancelled),
+
+        // SQLITE_ERROR: `no such table: main.cats`, `no such column:
+        // cats.nme`
+        (_, 1) => description.and_then(|description| {
+            if description.starts_with("no such table: ") {
+                Some(Error::TableDoesNotExist {
+                    table: description["no such table: ".len()..].into(),
+                    native: None,
+                })
+            } else if description.starts_with("no such column: ") {
+                Some(Error::ColumnDoesNotExist {
+                    column: description["no such column: ".len()..].into(),
+                    native: None,
+                })
+            } else {
+                None
+            }
+        }),
+
+        _ => None,
+    }
+}
+
+/// The path of the database in an error of a database that can't be opened.
+fn unopened_path(description: &str) -> Option<&str> {
+    if description.starts_with("unable to open database: ") {
+        Some(&description["unable to open database: ".len()..]).filter(|path| !path.is_empty())
+    } else {
+        None
+    }
+}
+
+/// The constraint name given for the constraints SQLite doesn't name in its
+/// errors.
+pub(crate) const UNNAMED_CONSTRAINT: &str = "(not available)";
+
+/// The columns of a constraint error such as `UNIQUE constraint failed:
+/// cats.id, cats.name`, without the table: `id, name`.
+fn constraint_columns(description: &str) -> Option<String> {
+    let columns = description.splitn(2, ": ").nth(1)?;
+
+    let columns: Vec<&str> = columns
+        .split(", ")
+        .map(|column| column.rsplit('.').next().unwrap_or(column))
+        .filter(|column| !column.is_empty())
+        .collect();
+
+    if columns.is_empty() {
+        None
+    } else {
+        Some(columns.join(", "))
     }
 }
 
@@ -62,3 +148,162 @@ impl From<FromSqlError> for Error {
         Error::ColumnReadFailure(e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn error_of(sql: &str) -> Error {
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE owners (id INTEGER PRIMARY KEY);
+             CREATE TABLE cats (
+                 id INTEGER PRIMARY KEY,
+                 name TEXT NOT NULL,
+                 tag TEXT,
+                 lives INTEGER CONSTRAINT lives_positive CHECK (lives > 0),
+                 owner_id INTEGER REFERENCES owners (id),
+                 UNIQUE (name, tag)
+             );
+             INSERT INTO owners (id) VALUES (1);
+             INSERT INTO cats VALUES (1, 'musti', 'a', 9, 1);",
+        )
+        .unwrap();
+
+        conn.execute_batch(sql).unwrap_err().into()
+    }
+
+    #[test]
+    fn unique_violations_give_the_columns() {
+        match error_of("INSERT INTO cats VALUES (2, 'musti', 'a', 9, NULL)") {
+            Error::UniqueConstraintViolation { field_name, .. } => {
+                assert_eq!("name, tag", field_name)
+            }
+            e => panic!("Expected `UniqueConstraintViolation`, got {:?}", e),
+        }
+
+        match error_of("INSERT INTO cats VALUES (1, 'naukio', NULL, 9, NULL)") {
+            Error::UniqueConstraintViolation { field_name, .. } => assert_eq!("id", field_name),
+            e => panic!("Expected `UniqueConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn null_violations_give_the_column() {
+        match error_of("INSERT INTO cats (id) VALUES (2)") {
+            Error::NullConstraintViolation { field_name, .. } => assert_eq!("name", field_name),
+            e => panic!("Expected `NullConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn foreign_key_violations_are_mapped() {
+        let statements = &[
+            "INSERT INTO cats VALUES (2, 'naukio', NULL, 9, 2)",
+            "DELETE FROM owners",
+        ];
+
+        for statement in statements {
+            match error_of(statement) {
+                Error::ForeignKeyConstraintViolation { constraint, .. } => {
+                    assert_eq!(UNNAMED_CONSTRAINT, constraint)
+                }
+                e => panic!("Expected `ForeignKeyConstraintViolation`, got {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn check_violations_give_the_constraint() {
+        match error_of("INSERT INTO cats VALUES (2, 'naukio', NULL, 0, 1)") {
+            Error::CheckConstraintViolation { constraint, .. } => {
+                assert_eq!("lives_positive", constraint)
+            }
+            e => panic!("Expected `CheckConstraintViolation`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn unopenable_databases_do_not_exist() {
+        match error_of("ATTACH DATABASE '/nonexistent/directory/cats.db' AS cats_db") {
+            Error::DatabaseDoesNotExist { db_name, .. } => {
+                assert_eq!("/nonexistent/directory/cats.db", db_name)
+            }
+            e => panic!("Expected `DatabaseDoesNotExist`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn mapped_errors_keep_the_native_error() {
+        let e = error_of("INSERT INTO cats VALUES (2, 'naukio', NULL, 9, 2)");
+
+        assert_eq!(Some("787"), e.code());
+        assert_eq!(Some("FOREIGN KEY constraint failed"), e.native_message());
+    }
+
+    #[test]
+    fn unopenable_databases_without_a_path_fail_to_connect() {
+        let descriptions = &[None, Some("unable to open database file")];
+
+        for description in descriptions {
+            let e = rusqlite::Error::SqliteFailure(
+                ffi::Error::new(ffi::SQLITE_CANTOPEN),
+                description.map(String::from),
+            );
+
+            match Error::from(e) {
+                Error::ConnectionError(_) => (),
+                e => panic!("Expected `ConnectionError`, got {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn busy_databases_time_out_waiting_for_the_lock() {
+        let e = rusqlite::Error::SqliteFailure(ffi::Error::new(ffi::SQLITE_BUSY), None);
+
+        match Error::from(e) {
+            e @ Error::LockTimeout => assert!(e.is_transient()),
+            e => panic!("Expected `LockTimeout`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn missing_tables_and_columns_give_the_name() {
+        match error_of("SELECT * FROM main.dogs") {
+            Error::TableDoesNotExist { table, .. } => assert_eq!("main.dogs", table),
+            e => panic!("Expected `TableDoesNotExist`, got {:?}", e),
+        }
+
+        match error_of("SELECT cats.nme FROM cats") {
+            Error::ColumnDoesNotExist { column, .. } => assert_eq!("cats.nme", column),
+            e => panic!("Expected `ColumnDoesNotExist`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn other_errors_keep_the_code_and_message() {
+        let e = error_of("INSERT INTO cats VALUES (1)");
+
+        assert_eq!(Some("1"), e.code());
+        assert_eq!(
+            Some("table cats has 5 columns but 1 values were supplied"),
+            e.native_message()
+        );
+        assert_eq!(Some(Backend::Sqlite), e.native().map(NativeError::backend));
+    }
+
+    #[test]
+    fn constraint_columns_are_parsed_defensively() {
+        assert_eq!(
+            Some("id".to_string()),
+            constraint_columns("UNIQUE constraint failed: main.cats.id")
+        );
+
+        assert_eq!(None, constraint_columns("UNIQUE constraint failed"));
+        assert_eq!(None, constraint_columns("UNIQUE constraint failed: "));
+    }
+}
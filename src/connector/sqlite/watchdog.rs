@@ -0,0 +1,117 @@
+//! A single thread interrupting the queries running past their timeout,
+//! shared by all the SQLite connections.
+use lazy_static::lazy_static;
+use rusqlite::InterruptHandle;
+use std::{
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+lazy_static! {
+    static ref WATCHDOG: Mutex<Sender<Deadline>> = Mutex::new(spawn());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Running,
+    Finished,
+    Interrupted,
+}
+
+struct Deadline {
+    at: Instant,
+    interrupt: InterruptHandle,
+    state: Arc<Mutex<State>>,
+}
+
+/// A query watched by the watchdog until `finish` is called.
+pub(crate) struct Watched {
+    state: Arc<Mutex<State>>,
+}
+
+impl Watched {
+    /// Stops watching the query, returning true if it was interrupted.
+    pub(crate) fn finish(self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if *state == State::Running {
+            *state = State::Finished;
+        }
+
+        *state == State::Interrupted
+    }
+}
+
+/// Interrupts the connection of the handle if the query is not finished
+/// before the timeout.
+pub(crate) fn watch(interrupt: InterruptHandle, timeout: Duration) -> Watched {
+    let state = Arc::new(Mutex::new(State::Running));
+
+    let deadline = Deadline {
+        at: Instant::now() + timeout,
+        interrupt,
+        state: state.clone(),
+    };
+
+    // Only fails if the watchdog thread is gone, leaving the query without a
+    // timeout.
+    let _ = WATCHDOG.lock().unwrap().send(deadline);
+
+    Watched { state }
+}
+
+fn spawn() -> Sender<Deadline> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .name(String::from("sqlite-watchdog"))
+        .spawn(move || run(receiver))
+        .expect("Could not start the SQLite watchdog thread");
+
+    sender
+}
+
+fn run(receiver: Receiver<Deadline>) {
+    let mut deadlines: Vec<Deadline> = Vec::new();
+
+    loop {
+        deadlines.retain(|deadline| *deadline.state.lock().unwrap() == State::Running);
+
+        let received = match deadlines.iter().map(|deadline| deadline.at).min() {
+            Some(at) => {
+                let now = Instant::now();
+                let wait = if at > now {
+                    at - now
+                } else {
+                    Duration::from_secs(0)
+                };
+
+                receiver.recv_timeout(wait)
+            }
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok(deadline) => deadlines.push(deadline),
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let now = Instant::now();
+
+        for deadline in deadlines.iter().filter(|deadline| deadline.at <= now) {
+            let mut state = deadline.state.lock().unwrap();
+
+            // Holding the lock, the query can't be marked finished before
+            // the interrupt.
+            if *state == State::Running {
+                deadline.interrupt.interrupt();
+                *state = State::Interrupted;
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+/// The default number of prepared statements kept per connection.
+pub(crate) const DEFAULT_STATEMENT_CACHE_SIZE: usize = 1000;
+
+/// A least-recently-used cache of prepared statements, keyed by the SQL
+/// string. A capacity of zero disables caching.
+pub(crate) struct StatementCache<T> {
+    capacity: usize,
+    tick: u64,
+    prepares: u64,
+    entries: HashMap<String, (T, u64)>,
+}
+
+impl<T: Clone> StatementCache<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            prepares: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the cached statement for the query, or prepares and caches a
+    /// new one.
+    pub(crate) fn get_or_prepare<F, E>(&mut self, sql: &str, prepare: F) -> Result<T, E>
+    where
+        F: FnOnce(&str) -> Result<T, E>,
+    {
+        self.tick += 1;
+
+        if let Some((stmt, last_used)) = self.entries.get_mut(sql) {
+            *last_used = self.tick;
+            return Ok(stmt.clone());
+        }
+
+        let stmt = prepare(sql)?;
+        self.prepares += 1;
+
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+
+            self.entries
+                .insert(sql.to_string(), (stmt.clone(), self.tick));
+        }
+
+        Ok(stmt)
+    }
+
+    /// Drops the cached statement of the query, e.g. when the server reports
+    /// the statement to be stale.
+    pub(crate) fn remove(&mut self, sql: &str) {
+        self.entries.remove(sql);
+    }
+
+    /// Drops all cached statements, e.g. after reconnecting.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Changes the capacity, dropping the least recently used statements if
+    /// needed.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.entries.len() > capacity {
+            self.evict_least_recently_used();
+        }
+    }
+
+    /// The number of statements prepared through the cache.
+    pub(crate) fn prepares(&self) -> u64 {
+        self.prepares
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(sql, _)| sql.clone());
+
+        if let Some(sql) = oldest {
+            self.entries.remove(&sql);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepare(sql: &str) -> Result<String, ()> {
+        Ok(sql.to_uppercase())
+    }
+
+    #[test]
+    fn a_cached_statement_is_not_prepared_again() {
+        let mut cache = StatementCache::new(10);
+
+        assert_eq!(Ok(String::from("SELECT 1")), cache.get_or_prepare("select 1", prepare));
+        assert_eq!(Ok(String::from("SELECT 1")), cache.get_or_prepare("select 1", prepare));
+        assert_eq!(1, cache.prepares());
+    }
+
+    #[test]
+    fn the_least_recently_used_statement_is_evicted() {
+        let mut cache = StatementCache::new(2);
+
+        cache.get_or_prepare("a", prepare).unwrap();
+        cache.get_or_prepare("b", prepare).unwrap();
+        cache.get_or_prepare("a", prepare).unwrap();
+        cache.get_or_prepare("c", prepare).unwrap();
+        assert_eq!(3, cache.prepares());
+
+        cache.get_or_prepare("a", prepare).unwrap();
+        assert_eq!(3, cache.prepares());
+
+        cache.get_or_prepare("b", prepare).unwrap();
+        assert_eq!(4, cache.prepares());
+    }
+
+    #[test]
+    fn zero_capacity_disables_caching() {
+        let mut cache = StatementCache::new(0);
+
+        cache.get_or_prepare("a", prepare).unwrap();
+        cache.get_or_prepare("a", prepare).unwrap();
+
+        assert_eq!(2, cache.prepares());
+    }
+
+    #[test]
+    fn a_removed_statement_is_prepared_again() {
+        let mut cache = StatementCache::new(10);
+
+        cache.get_or_prepare("a", prepare).unwrap();
+        cache.remove("a");
+        cache.get_or_prepare("a", prepare).unwrap();
+
+        assert_eq!(2, cache.prepares());
+    }
+}
@@ -1,5 +1,6 @@
 use super::*;
 use crate::ast::*;
+use std::time::Duration;
 
 /// A representation of an SQL database transaction. If not commited, a
 /// transaction will be rolled back by default when dropped.
@@ -42,11 +43,238 @@ impl<'a> Drop for Transaction<'a> {
     }
 }
 
+/// A transaction owning its connection, handing it back when committed or
+/// rolled back. Started with
+/// [into_transaction](trait.Queryable.html#method.into_transaction). If not
+/// commited, the transaction is rolled back when dropped, and the connection
+/// dropped with it.
+///
+/// Does not support nesting, so starting a new transaction using the
+/// transaction object will panic.
+///
+/// ```
+/// # use prisma_query::connector::*;
+/// enum State {
+///     Idle(Sqlite),
+///     Busy(OwnedTransaction<Sqlite>),
+/// }
+///
+/// let state = State::Idle(Sqlite::new_in_memory().unwrap());
+///
+/// let state = match state {
+///     State::Idle(conn) => State::Busy(conn.into_transaction().unwrap()),
+///     busy => busy,
+/// };
+///
+/// let mut conn = match state {
+///     State::Busy(mut tx) => {
+///         tx.raw_cmd("CREATE TABLE cats (id INTEGER PRIMARY KEY)").unwrap();
+///         tx.commit().unwrap()
+///     }
+///     State::Idle(conn) => conn,
+/// };
+///
+/// assert!(conn.table_exists("cats").unwrap());
+/// ```
+pub struct OwnedTransaction<Q: Queryable> {
+    // Only taken when the transaction is finished.
+    inner: Option<Q>,
+}
+
+impl<Q: Queryable> OwnedTransaction<Q> {
+    pub(crate) fn new(mut inner: Q) -> crate::Result<Self> {
+        inner.raw_cmd("BEGIN")?;
+        Ok(Self { inner: Some(inner) })
+    }
+
+    /// Commits the changes to the database, returning the connection. If
+    /// committing fails, the connection is dropped.
+    pub fn commit(mut self) -> crate::Result<Q> {
+        self.finish("COMMIT")
+    }
+
+    /// Rolls back the changes to the database, returning the connection. If
+    /// rolling back fails, the connection is dropped.
+    pub fn rollback(mut self) -> crate::Result<Q> {
+        self.finish("ROLLBACK")
+    }
+
+    fn finish(&mut self, cmd: &str) -> crate::Result<Q> {
+        let mut inner = self.inner.take().expect("The transaction is finished");
+        inner.raw_cmd(cmd)?;
+
+        Ok(inner)
+    }
+
+    fn conn(&mut self) -> &mut Q {
+        self.inner.as_mut().expect("The transaction is finished")
+    }
+}
+
+impl<Q: Queryable> Drop for OwnedTransaction<Q> {
+    fn drop(&mut self) {
+        if let Some(ref mut inner) = self.inner {
+            let _ = inner.raw_cmd("ROLLBACK");
+        }
+    }
+}
+
+impl<Q: Queryable> Queryable for OwnedTransaction<Q> {
+    fn execute(&mut self, q: Query) -> crate::Result<Option<Id>> {
+        self.conn().execute(q)
+    }
+
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        self.conn().execute_with_result(q)
+    }
+
+    fn insert_returning<'a>(
+        &mut self,
+        insert: Insert<'a>,
+        columns: Vec<Column<'a>>,
+    ) -> crate::Result<ResultRow> {
+        self.conn().insert_returning(insert, columns)
+    }
+
+    fn upsert<'a>(
+        &mut self,
+        table: Table<'a>,
+        unique_columns: Vec<Column<'a>>,
+        values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+        updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> crate::Result<ExecuteResult> {
+        self.conn().upsert(table, unique_columns, values, updates)
+    }
+
+    fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
+        self.conn().query(q)
+    }
+
+    fn query_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<ResultSet> {
+        self.conn().query_raw(sql, params)
+    }
+
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        self.conn().query_with_timeout(q, timeout)
+    }
+
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        self.conn().query_each(q, f)
+    }
+
+    fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
+        self.conn().execute_raw(sql, params)
+    }
+
+    #[allow(deprecated)]
+    fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
+        self.conn().turn_off_fk_constraints()
+    }
+
+    #[allow(deprecated)]
+    fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
+        self.conn().turn_on_fk_constraints()
+    }
+
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        self.conn().foreign_key_checks()
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        self.conn().set_foreign_key_checks(enabled)
+    }
+
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        self.conn().set_schema(schema)
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        self.conn().cancel_token()
+    }
+
+    fn empty_tables(&mut self, tables: Vec<Table>) -> crate::Result<()> {
+        self.conn().empty_tables(tables)
+    }
+
+    fn start_transaction<'b>(&'b mut self) -> crate::Result<Transaction<'b>> {
+        panic!("Nested transactions are not supported")
+    }
+
+    fn into_transaction(self) -> crate::Result<OwnedTransaction<Self>> {
+        panic!("Nested transactions are not supported")
+    }
+
+    fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
+        self.conn().raw_cmd(cmd)
+    }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        self.conn().execute_script(sql)
+    }
+
+    fn ping(&mut self) -> crate::Result<()> {
+        self.conn().ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        self.conn().version()
+    }
+
+    fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        self.conn().capabilities()
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        self.conn().table_exists(table)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        self.conn().list_tables(schema)
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        self.conn().describe_table(table)
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        self.inner
+            .as_ref()
+            .expect("The transaction is finished")
+            .batch_limits()
+    }
+}
+
 impl<'a> Queryable for Transaction<'a> {
     fn execute(&mut self, q: Query) -> crate::Result<Option<Id>> {
         self.inner.execute(q)
     }
 
+    fn execute_with_result(&mut self, q: Query) -> crate::Result<ExecuteResult> {
+        self.inner.execute_with_result(q)
+    }
+
+    fn insert_returning<'b>(
+        &mut self,
+        insert: Insert<'b>,
+        columns: Vec<Column<'b>>,
+    ) -> crate::Result<ResultRow> {
+        self.inner.insert_returning(insert, columns)
+    }
+
+    fn upsert<'b>(
+        &mut self,
+        table: Table<'b>,
+        unique_columns: Vec<Column<'b>>,
+        values: Vec<(Column<'b>, DatabaseValue<'b>)>,
+        updates: Vec<(Column<'b>, DatabaseValue<'b>)>,
+    ) -> crate::Result<ExecuteResult> {
+        self.inner.upsert(table, unique_columns, values, updates)
+    }
+
     fn query(&mut self, q: Query) -> crate::Result<ResultSet> {
         self.inner.query(q)
     }
@@ -55,18 +283,48 @@ impl<'a> Queryable for Transaction<'a> {
         self.inner.query_raw(sql, params)
     }
 
+    fn query_with_timeout(&mut self, q: Query, timeout: Duration) -> crate::Result<ResultSet> {
+        self.inner.query_with_timeout(q, timeout)
+    }
+
+    fn query_each(
+        &mut self,
+        q: Query,
+        f: &mut dyn FnMut(ResultRow) -> crate::Result<()>,
+    ) -> crate::Result<u64> {
+        self.inner.query_each(q, f)
+    }
+
     fn execute_raw(&mut self, sql: &str, params: &[ParameterizedValue]) -> crate::Result<u64> {
         self.inner.execute_raw(sql, params)
     }
 
+    #[allow(deprecated)]
     fn turn_off_fk_constraints(&mut self) -> crate::Result<()> {
         self.inner.turn_off_fk_constraints()
     }
 
+    #[allow(deprecated)]
     fn turn_on_fk_constraints(&mut self) -> crate::Result<()> {
         self.inner.turn_on_fk_constraints()
     }
 
+    fn foreign_key_checks(&mut self) -> crate::Result<bool> {
+        self.inner.foreign_key_checks()
+    }
+
+    fn set_foreign_key_checks(&mut self, enabled: bool) -> crate::Result<()> {
+        self.inner.set_foreign_key_checks(enabled)
+    }
+
+    fn set_schema(&mut self, schema: &str) -> crate::Result<()> {
+        self.inner.set_schema(schema)
+    }
+
+    fn cancel_token(&mut self) -> crate::Result<CancelToken> {
+        self.inner.cancel_token()
+    }
+
     fn empty_tables(&mut self, tables: Vec<Table>) -> crate::Result<()> {
         self.inner.empty_tables(tables)
     }
@@ -75,7 +333,43 @@ impl<'a> Queryable for Transaction<'a> {
         panic!("Nested transactions are not supported")
     }
 
+    fn into_transaction(self) -> crate::Result<OwnedTransaction<Self>> {
+        panic!("Nested transactions are not supported")
+    }
+
     fn raw_cmd(&mut self, cmd: &str) -> crate::Result<()> {
         self.inner.raw_cmd(cmd)
     }
+
+    fn execute_script(&mut self, sql: &str) -> crate::Result<()> {
+        self.inner.execute_script(sql)
+    }
+
+    fn ping(&mut self) -> crate::Result<()> {
+        self.inner.ping()
+    }
+
+    fn version(&mut self) -> crate::Result<ServerVersion> {
+        self.inner.version()
+    }
+
+    fn capabilities(&mut self) -> crate::Result<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn table_exists(&mut self, table: &str) -> crate::Result<bool> {
+        self.inner.table_exists(table)
+    }
+
+    fn list_tables(&mut self, schema: &str) -> crate::Result<Vec<String>> {
+        self.inner.list_tables(schema)
+    }
+
+    fn describe_table(&mut self, table: &str) -> crate::Result<Vec<ColumnDescription>> {
+        self.inner.describe_table(table)
+    }
+
+    fn batch_limits(&self) -> BatchLimits {
+        self.inner.batch_limits()
+    }
 }
@@ -0,0 +1,40 @@
+//! Inserting a row or updating the existing one.
+use crate::{
+    ast::{Column, DatabaseValue, Insert, OnConflict, Table},
+    error::Error,
+};
+
+/// An `INSERT` of the values, updating the row with the assignments if the
+/// unique columns conflict with an existing row.
+pub(crate) fn statement<'a>(
+    table: Table<'a>,
+    unique_columns: Vec<Column<'a>>,
+    values: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    updates: Vec<(Column<'a>, DatabaseValue<'a>)>,
+) -> crate::Result<Insert<'a>> {
+    if unique_columns.is_empty() {
+        return Err(invalid("An upsert needs unique columns"));
+    }
+
+    if values.is_empty() {
+        return Err(invalid("An upsert needs values to insert"));
+    }
+
+    if updates.is_empty() {
+        return Err(invalid("An upsert needs columns to update"));
+    }
+
+    let insert = values
+        .into_iter()
+        .fold(Insert::single_into(table), |insert, (column, value)| {
+            insert.value(column, value)
+        });
+
+    let insert = Insert::from(insert).on_conflict(OnConflict::Update(unique_columns, updates));
+
+    Ok(insert)
+}
+
+fn invalid(message: &'static str) -> Error {
+    Error::QueryError(failure::err_msg(message))
+}
@@ -1,5 +1,6 @@
+use crate::connector::Backend;
 use failure::{Error as FError, Fail};
-use std::io;
+use std::{fmt, io};
 
 #[derive(Debug, Fail)]
 pub enum Error {
@@ -7,29 +8,90 @@ pub enum Error {
     QueryError(FError),
 
     #[fail(display = "Database '{}' does not exist.", db_name)]
-    DatabaseDoesNotExist { db_name: String },
+    DatabaseDoesNotExist {
+        db_name: String,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Access denied to database '{}'", db_name)]
-    DatabaseAccessDenied { db_name: String },
+    DatabaseAccessDenied {
+        db_name: String,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Database '{}' already exists", db_name)]
-    DatabaseAlreadyExists { db_name: String },
+    DatabaseAlreadyExists {
+        db_name: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Schema '{}' does not exist", schema)]
+    SchemaDoesNotExist {
+        schema: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Schema '{}' already exists", schema)]
+    SchemaAlreadyExists {
+        schema: String,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Authentication failed for user '{}'", user)]
-    AuthenticationFailed { user: String },
+    AuthenticationFailed {
+        user: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Table '{}' does not exist", table)]
+    TableDoesNotExist {
+        table: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Column '{}' does not exist", column)]
+    ColumnDoesNotExist {
+        column: String,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Query returned no data")]
     NotFound,
 
+    #[fail(display = "Query returned {} rows, expected exactly one", count)]
+    TooManyRows { count: usize },
+
     #[fail(display = "Unique constraint failed: {}", field_name)]
-    UniqueConstraintViolation { field_name: String },
+    UniqueConstraintViolation {
+        field_name: String,
+        constraint: Option<String>,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Null constraint failed: {}", field_name)]
-    NullConstraintViolation { field_name: String },
+    NullConstraintViolation {
+        field_name: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Foreign key constraint failed: {}", constraint)]
+    ForeignKeyConstraintViolation {
+        constraint: String,
+        native: Option<NativeError>,
+    },
+
+    #[fail(display = "Check constraint failed: {}", constraint)]
+    CheckConstraintViolation {
+        constraint: String,
+        native: Option<NativeError>,
+    },
 
     #[fail(display = "Error creating a database connection.")]
     ConnectionError(FError),
 
+    #[fail(display = "The connection was closed")]
+    ConnectionClosed,
+
     #[fail(display = "Error reading the column value: {}", _0)]
     ColumnReadFailure(FError),
 
@@ -39,21 +101,30 @@ pub enum Error {
     #[fail(display = "Error accessing result set, column not found: {}", _0)]
     ColumnNotFound(String),
 
+    #[fail(display = "Expected {} values in a row, got {}", expected, found)]
+    RowWidthMismatch { expected: usize, found: usize },
+
     #[fail(
         display = "Error accessing result set, type mismatch, expected: {}",
         _0
     )]
     ResultTypeMismatch(&'static str),
 
-    #[fail(display = "The specified database url {} is invalid", _0)]
+    #[fail(display = "The specified database url is invalid: {}", _0)]
     DatabaseUrlIsInvalid(String),
 
     #[fail(display = "Conversion failed: {}", _0)]
     ConversionError(&'static str),
 
+    #[fail(display = "The connector does not support `{}`", _0)]
+    UnsupportedOperation(&'static str),
+
     #[fail(display = "The provided arguments are not supported")]
     InvalidConnectionArguments,
 
+    #[fail(display = "Invalid connection parameter `{}`: {}", field, message)]
+    InvalidConnectionParameter { field: String, message: String },
+
     #[fail(display = "Error in an I/O operation")]
     IoError(FError),
 
@@ -63,15 +134,262 @@ pub enum Error {
     #[fail(display = "Operation timed out")]
     Timeout,
 
+    #[fail(display = "Timed out waiting for a lock")]
+    LockTimeout,
+
+    #[fail(display = "The query was cancelled")]
+    QueryCancelled,
+
+    #[fail(display = "The transaction failed due to a write conflict or a deadlock")]
+    TransactionWriteConflict,
+
     #[fail(display = "Error opening a TLS connection. {}", message)]
     TlsError { message: String },
+
+    #[fail(display = "Too many attached databases, the limit is {}", limit)]
+    TooManyAttachedDatabases { limit: usize },
+
+    #[fail(
+        display = "Error executing the statement at index {} of the script: {}",
+        statement_index, cause
+    )]
+    ScriptError {
+        statement_index: usize,
+        cause: FError,
+    },
+
+    #[fail(display = "An UPDATE must set at least one column")]
+    EmptyUpdate,
 }
 
-#[cfg(any(
-    feature = "mysql-16",
-    feature = "postgresql-0_16",
-    feature = "rusqlite-0_19"
-))]
+impl Error {
+    /// Wraps the error into a `ConnectionError`, keeping the timeouts and
+    /// connection errors as they are.
+    pub(crate) fn into_connection_error(self) -> Error {
+        match self {
+            e @ Error::ConnectionError(_)
+            | e @ Error::ConnectionClosed
+            | e @ Error::ConnectTimeout
+            | e @ Error::Timeout => e,
+            e => Error::ConnectionError(e.into()),
+        }
+    }
+
+    /// A timeout while opening the connection is a `ConnectTimeout`. The
+    /// drivers report the socket timeouts the same way when connecting and
+    /// when running a query.
+    pub(crate) fn when_connecting(self) -> Error {
+        match self {
+            Error::Timeout => Error::ConnectTimeout,
+            e => e,
+        }
+    }
+
+    /// True if running the operation again might succeed: on deadlocks,
+    /// serialization failures, closed connections and timeouts waiting for
+    /// a lock. Statement and socket timeouts are not transient, being likely
+    /// to time out again if retried.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::TransactionWriteConflict | Error::ConnectionClosed | Error::LockTimeout => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            Error::UniqueConstraintViolation { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// True for a violated unique, not null, foreign key or check
+    /// constraint.
+    pub fn is_constraint_violation(&self) -> bool {
+        match self {
+            Error::UniqueConstraintViolation { .. }
+            | Error::NullConstraintViolation { .. }
+            | Error::ForeignKeyConstraintViolation { .. }
+            | Error::CheckConstraintViolation { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::NotFound => true,
+            _ => false,
+        }
+    }
+
+    /// True if connecting, running the query or waiting for a lock timed
+    /// out.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout | Error::ConnectTimeout | Error::LockTimeout => true,
+            _ => false,
+        }
+    }
+
+    /// True if the connection could not be opened or was lost.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            Error::ConnectionError(_)
+            | Error::ConnectionClosed
+            | Error::ConnectTimeout
+            | Error::TlsError { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// The error reported by the database. Kept by the variants naming a
+    /// database object, e.g. `UniqueConstraintViolation`, and by the errors
+    /// not mapped to a variant of their own, also when wrapped into a
+    /// `ConnectionError` or a `ScriptError`. The variants without details,
+    /// such as `Timeout`, don't keep it.
+    ///
+    /// ```
+    /// # use prisma_query::{connector::*, error::Error};
+    /// let mut conn = Sqlite::new_in_memory().unwrap();
+    /// let error = conn.raw_cmd("SELEC 1").unwrap_err();
+    ///
+    /// let native = error.native().unwrap();
+    ///
+    /// assert_eq!(Backend::Sqlite, native.backend());
+    /// assert_eq!(Some("1"), native.code());
+    /// assert_eq!(Some("near \"SELEC\": syntax error"), native.message());
+    /// ```
+    pub fn native(&self) -> Option<&NativeError> {
+        match self {
+            Error::QueryError(e)
+            | Error::ConnectionError(e)
+            | Error::ColumnReadFailure(e)
+            | Error::IoError(e)
+            | Error::ScriptError { cause: e, .. } => e.iter_chain().find_map(|cause| {
+                cause
+                    .downcast_ref::<NativeError>()
+                    .or_else(|| cause.downcast_ref::<Error>().and_then(Error::native))
+            }),
+            Error::DatabaseDoesNotExist { native, .. }
+            | Error::DatabaseAccessDenied { native, .. }
+            | Error::DatabaseAlreadyExists { native, .. }
+            | Error::SchemaDoesNotExist { native, .. }
+            | Error::SchemaAlreadyExists { native, .. }
+            | Error::AuthenticationFailed { native, .. }
+            | Error::TableDoesNotExist { native, .. }
+            | Error::ColumnDoesNotExist { native, .. }
+            | Error::UniqueConstraintViolation { native, .. }
+            | Error::NullConstraintViolation { native, .. }
+            | Error::ForeignKeyConstraintViolation { native, .. }
+            | Error::CheckConstraintViolation { native, .. } => native.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Keeps the error reported by the database in the variants naming a
+    /// database object.
+    pub(crate) fn with_native(mut self, error: NativeError) -> Error {
+        match self {
+            Error::DatabaseDoesNotExist { ref mut native, .. }
+            | Error::DatabaseAccessDenied { ref mut native, .. }
+            | Error::DatabaseAlreadyExists { ref mut native, .. }
+            | Error::SchemaDoesNotExist { ref mut native, .. }
+            | Error::SchemaAlreadyExists { ref mut native, .. }
+            | Error::AuthenticationFailed { ref mut native, .. }
+            | Error::TableDoesNotExist { ref mut native, .. }
+            | Error::ColumnDoesNotExist { ref mut native, .. }
+            | Error::UniqueConstraintViolation { ref mut native, .. }
+            | Error::NullConstraintViolation { ref mut native, .. }
+            | Error::ForeignKeyConstraintViolation { ref mut native, .. }
+            | Error::CheckConstraintViolation { ref mut native, .. } => *native = Some(error),
+            _ => (),
+        }
+
+        self
+    }
+
+    /// The error code of the database: the SQLSTATE of PostgreSQL, the error
+    /// number of MySQL or the extended result code of SQLite.
+    pub fn code(&self) -> Option<&str> {
+        self.native().and_then(NativeError::code)
+    }
+
+    /// The error message of the database.
+    pub fn native_message(&self) -> Option<&str> {
+        self.native().and_then(NativeError::message)
+    }
+
+    /// Attaches the index of the failed statement to an error of a script.
+    pub(crate) fn in_script(self, statement_index: usize) -> Error {
+        Error::ScriptError {
+            statement_index,
+            cause: self.into(),
+        }
+    }
+}
+
+/// An error reported by the database, with its code and message.
+#[derive(Debug)]
+pub struct NativeError {
+    backend: Backend,
+    code: Option<String>,
+    message: Option<String>,
+    cause: FError,
+}
+
+impl NativeError {
+    pub(crate) fn new<E>(
+        backend: Backend,
+        code: Option<String>,
+        message: Option<String>,
+        cause: E,
+    ) -> Self
+    where
+        E: Into<FError>,
+    {
+        Self {
+            backend,
+            code,
+            message,
+            cause: cause.into(),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// The SQLSTATE of PostgreSQL, the error number of MySQL or the extended
+    /// result code of SQLite.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_ref().map(String::as_str)
+    }
+
+    /// The message as sent by the database.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(String::as_str)
+    }
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cause)
+    }
+}
+
+impl Fail for NativeError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        Some(self.cause.as_fail())
+    }
+}
+
+impl From<NativeError> for Error {
+    fn from(e: NativeError) -> Error {
+        Error::QueryError(e.into())
+    }
+}
+
+#[cfg(feature = "pooled")]
 impl From<r2d2::Error> for Error {
     fn from(e: r2d2::Error) -> Error {
         Error::ConnectionError(e.into())
@@ -89,3 +407,85 @@ impl From<io::Error> for Error {
         Error::IoError(e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_classified() {
+        let unique = Error::UniqueConstraintViolation {
+            field_name: "id".into(),
+            constraint: None,
+            native: None,
+        };
+
+        let foreign_key = Error::ForeignKeyConstraintViolation {
+            constraint: "cats_owner_id_fkey".into(),
+            native: None,
+        };
+
+        assert!(unique.is_unique_violation() && unique.is_constraint_violation());
+        assert!(!foreign_key.is_unique_violation() && foreign_key.is_constraint_violation());
+        assert!(!unique.is_transient());
+
+        assert!(Error::NotFound.is_not_found());
+        assert!(!Error::NotFound.is_constraint_violation());
+
+        assert!(Error::Timeout.is_timeout() && !Error::Timeout.is_transient());
+        assert!(Error::LockTimeout.is_timeout() && Error::LockTimeout.is_transient());
+        assert!(Error::ConnectTimeout.is_timeout() && Error::ConnectTimeout.is_connection_error());
+
+        assert!(Error::ConnectionClosed.is_connection_error());
+        assert!(Error::ConnectionClosed.is_transient());
+        assert!(Error::TransactionWriteConflict.is_transient());
+        assert!(!Error::QueryCancelled.is_transient());
+    }
+
+    #[test]
+    fn timeouts_when_connecting_are_connect_timeouts() {
+        match Error::Timeout.when_connecting() {
+            Error::ConnectTimeout => (),
+            e => panic!("Expected `ConnectTimeout`, got {:?}", e),
+        }
+
+        match Error::ConnectionClosed.when_connecting() {
+            Error::ConnectionClosed => (),
+            e => panic!("Expected `ConnectionClosed`, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn wrapped_and_mapped_errors_keep_the_native_error() {
+        let native = || {
+            let message = "no such table: cats";
+            let cause = failure::err_msg(message);
+
+            NativeError::new(
+                Backend::Sqlite,
+                Some("1".into()),
+                Some(message.into()),
+                cause,
+            )
+        };
+
+        let errors = vec![
+            Error::from(native()),
+            Error::from(native()).in_script(2),
+            Error::from(native()).into_connection_error(),
+            Error::ConnectionError(native().into()),
+            Error::TableDoesNotExist {
+                table: "cats".into(),
+                native: None,
+            }
+            .with_native(native()),
+        ];
+
+        for error in errors {
+            assert_eq!(Some("1"), error.code(), "{:?}", error);
+            assert_eq!(Some("no such table: cats"), error.native_message());
+        }
+
+        assert!(Error::Timeout.with_native(native()).native().is_none());
+    }
+}
@@ -95,10 +95,13 @@ pub mod ast;
 ))]
 pub mod connector;
 pub mod error;
-#[cfg(any(
-    feature = "mysql-16",
-    feature = "postgresql-0_16",
-    feature = "rusqlite-0_19"
+#[cfg(all(
+    feature = "pooled",
+    any(
+        feature = "mysql-16",
+        feature = "postgresql-0_16",
+        feature = "rusqlite-0_19"
+    )
 ))]
 pub mod pool;
 #[cfg(any(
@@ -11,30 +11,33 @@ pub mod sqlite;
 
 use std::path::PathBuf;
 
+/// A connection checked out from a pool, dereferencing to the connector of the
+/// database and through that implementing
+/// [Queryable](../connector/trait.Queryable.html).
+pub type PooledConnection<Inner> = r2d2::PooledConnection<PrismaConnectionManager<Inner>>;
+
 /// An `r2d2::ManageConnection` for all of the connectors supported by
-/// prisma-query.
+/// prisma-query, available with the `pooled` feature.
+///
+/// The connections are validated with a cheap round trip to the database, and
+/// configured with the same parameters the connectors accept.
 ///
 /// ## Sqlite
 ///
-/// ```no_run
+/// ```
 /// use prisma_query::connector::Queryable;
 /// use prisma_query::ast::*;
 /// use prisma_query::pool::PrismaConnectionManager;
-/// use std::thread;
 ///
 /// fn main() {
-///     let manager = PrismaConnectionManager::sqlite(None, "db/test.db").unwrap();
-///     let pool = r2d2::Pool::new(manager).unwrap();
+///     let manager = PrismaConnectionManager::sqlite(Some("test".into()), "db/test.db").unwrap();
+///     let pool = r2d2::Pool::builder().max_size(2).build(manager).unwrap();
 ///
-///     for i in 0..10i32 {
-///         let pool = pool.clone();
-///         thread::spawn(move || {
-///             let mut client = pool.get().unwrap();
-///             let insert = Insert::single_into("foo").value("key", i);
+///     // The pooled connection dereferences to a `Sqlite` connector.
+///     let mut client = pool.get().unwrap();
+///     let result = client.query(Select::default().value(1).into()).unwrap();
 ///
-///             client.execute(Query::from(insert)).unwrap();
-///         });
-///     }
+///     assert_eq!(Some(1), result.first().and_then(|row| row[0].as_i64()));
 /// }
 /// ```
 ///
@@ -102,4 +105,8 @@ where
     inner: Inner,
     file_path: Option<PathBuf>,
     schema: Option<String>,
+    #[cfg(feature = "rusqlite-0_19")]
+    sqlite_pragmas: crate::connector::SqlitePragmas,
+    #[cfg(feature = "mysql-16")]
+    mysql_uuid_format: crate::connector::MysqlUuidFormat,
 }
@@ -16,6 +16,9 @@ impl PrismaConnectionManager<MysqlConnectionManager> {
             inner: MysqlConnectionManager::new(opts),
             file_path: None,
             schema: None,
+            #[cfg(feature = "rusqlite-0_19")]
+            sqlite_pragmas: Default::default(),
+            mysql_uuid_format: Default::default(),
         }
     }
 }
@@ -24,7 +27,8 @@ impl TryFrom<MysqlParams> for r2d2::Pool<PrismaConnectionManager<MysqlConnection
     type Error = Error;
 
     fn try_from(params: MysqlParams) -> crate::Result<Self> {
-        let manager = PrismaConnectionManager::mysql(params.config);
+        let mut manager = PrismaConnectionManager::mysql(params.driver_opts());
+        manager.mysql_uuid_format = params.uuid_format;
 
         let pool = r2d2::Pool::builder()
             .max_size(params.connection_limit)
@@ -41,19 +45,23 @@ impl ManageConnection for PrismaConnectionManager<MysqlConnectionManager> {
 
     fn connect(&self) -> Result<Self::Connection, Self::Error> {
         match metrics::connect("pool.mysql", || self.inner.connect()) {
-            Ok(client) => Ok(Mysql::from(client)),
+            Ok(client) => {
+                let mut conn = Mysql::from(client);
+                conn.set_uuid_format(self.mysql_uuid_format);
+
+                Ok(conn)
+            }
             Err(e) => Err(Error::from(e).compat()),
         }
     }
 
     fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        match conn.query_raw("SELECT version()", &[]) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.compat()),
-        }
+        conn.ping().map_err(|e| e.compat())
     }
 
     fn has_broken(&self, _: &mut Self::Connection) -> bool {
+        // The driver doesn't expose the connection state without a round
+        // trip, which is left for `is_valid`.
         false
     }
 }
@@ -1,10 +1,11 @@
 use super::PrismaConnectionManager;
 use crate::{
-    connector::{metrics, PostgreSql, PostgresParams, Queryable, DEFAULT_SCHEMA},
+    connector::{
+        metrics, PostgreSql, PostgresParams, Queryable, SslAcceptMode, SslParams, DEFAULT_SCHEMA,
+    },
     error::Error,
 };
 use failure::{Compat, Fail};
-use native_tls::TlsConnector;
 use r2d2::ManageConnection;
 use std::convert::TryFrom;
 use tokio_postgres_native_tls::MakeTlsConnector;
@@ -20,7 +21,11 @@ impl TryFrom<Url> for PrismaConnectionManager<PostgresManager> {
 
     fn try_from(url: Url) -> crate::Result<Self> {
         let params = PostgresParams::try_from(url)?;
-        Self::postgres(params.config, Some(params.schema))
+        Self::postgres_with_ssl_params(
+            params.driver_config(),
+            Some(params.schema),
+            params.ssl_params,
+        )
     }
 }
 
@@ -28,8 +33,11 @@ impl TryFrom<PostgresParams> for r2d2::Pool<PrismaConnectionManager<PostgresMana
     type Error = Error;
 
     fn try_from(params: PostgresParams) -> crate::Result<Self> {
-        let manager =
-            PrismaConnectionManager::postgres(params.config, Some(params.schema)).unwrap();
+        let manager = PrismaConnectionManager::postgres_with_ssl_params(
+            params.driver_config(),
+            Some(params.schema),
+            params.ssl_params,
+        )?;
 
         let pool = r2d2::Pool::builder()
             .max_size(params.connection_limit)
@@ -41,16 +49,27 @@ impl TryFrom<PostgresParams> for r2d2::Pool<PrismaConnectionManager<PostgresMana
 }
 
 impl PrismaConnectionManager<PostgresManager> {
+    /// A manager accepting invalid certificates, e.g. for Heroku.
     pub fn postgres(opts: postgres::Config, schema: Option<String>) -> crate::Result<Self> {
-        let mut tls_builder = TlsConnector::builder();
-        tls_builder.danger_accept_invalid_certs(true); // For Heroku
+        let ssl_params = SslParams::default().accept_mode(SslAcceptMode::AcceptInvalidCerts);
+        Self::postgres_with_ssl_params(opts, schema, ssl_params)
+    }
 
-        let tls = MakeTlsConnector::new(tls_builder.build()?);
+    pub fn postgres_with_ssl_params(
+        opts: postgres::Config,
+        schema: Option<String>,
+        ssl_params: SslParams,
+    ) -> crate::Result<Self> {
+        let tls = ssl_params.tls_connector()?;
 
         Ok(Self {
             inner: PostgresConnectionManager::new(opts, tls),
             file_path: None,
             schema,
+            #[cfg(feature = "rusqlite-0_19")]
+            sqlite_pragmas: Default::default(),
+            #[cfg(feature = "mysql-16")]
+            mysql_uuid_format: Default::default(),
         })
     }
 }
@@ -78,14 +97,11 @@ impl ManageConnection for PrismaConnectionManager<PostgresManager> {
     }
 
     fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        match conn.query_raw("", &[]) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.compat()),
-        }
+        conn.ping().map_err(|e| e.compat())
     }
 
-    fn has_broken(&self, _: &mut Self::Connection) -> bool {
-        false
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        conn.client.is_closed()
     }
 }
 
@@ -13,11 +13,11 @@ impl TryFrom<SqliteParams> for r2d2::Pool<PrismaConnectionManager<SqliteConnecti
     type Error = Error;
 
     fn try_from(params: SqliteParams) -> crate::Result<Self> {
-        let manager =
-            PrismaConnectionManager::sqlite(params.schema, params.file_path.to_str().unwrap())?;
+        let connection_limit = params.connection_limit;
+        let manager = PrismaConnectionManager::sqlite_with_params(params.schema.clone(), params);
 
         let pool = r2d2::Pool::builder()
-            .max_size(params.connection_limit)
+            .max_size(connection_limit)
             .test_on_check_out(false)
             .build(manager)?;
 
@@ -28,12 +28,18 @@ impl TryFrom<SqliteParams> for r2d2::Pool<PrismaConnectionManager<SqliteConnecti
 impl PrismaConnectionManager<SqliteConnectionManager> {
     pub fn sqlite(db_name: Option<String>, path: &str) -> crate::Result<Self> {
         let params = SqliteParams::try_from(path)?;
+        Ok(Self::sqlite_with_params(db_name, params))
+    }
 
-        Ok(Self {
+    fn sqlite_with_params(db_name: Option<String>, params: SqliteParams) -> Self {
+        Self {
             inner: SqliteConnectionManager::memory(),
             file_path: Some(params.file_path),
             schema: db_name,
-        })
+            sqlite_pragmas: params.pragmas,
+            #[cfg(feature = "mysql-16")]
+            mysql_uuid_format: Default::default(),
+        }
     }
 }
 
@@ -44,14 +50,16 @@ impl ManageConnection for PrismaConnectionManager<SqliteConnectionManager> {
     fn connect(&self) -> Result<Self::Connection, Self::Error> {
         match metrics::connect("pool.sqlite", || self.inner.connect()) {
             Ok(client) => {
-                let mut sqlite = Sqlite {
+                let mut sqlite = Sqlite::from_client(
                     client,
-                    file_path: self.file_path.clone().unwrap(),
-                };
+                    self.file_path.clone().unwrap(),
+                    self.sqlite_pragmas.clone(),
+                )
+                .map_err(|err| err.compat())?;
 
                 if let Some(ref schema) = self.schema {
                     sqlite
-                        .attach_database(schema)
+                        .attach_file(schema)
                         .map_err(|err| Error::from(err).compat())?;
                 }
 
@@ -62,10 +70,7 @@ impl ManageConnection for PrismaConnectionManager<SqliteConnectionManager> {
     }
 
     fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
-        match conn.query_raw("SELECT 1", &[]) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.compat()),
-        }
+        conn.ping().map_err(|e| e.compat())
     }
 
     fn has_broken(&self, _: &mut Self::Connection) -> bool {
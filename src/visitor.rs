@@ -85,6 +85,12 @@ pub trait Visitor<'a> {
         self.parameter_substitution()
     }
 
+    /// The parameter written inline as a literal of the database, for
+    /// logging and debugging queries. Bytes are written as `X'...'`.
+    fn inline_parameter(value: &ParameterizedValue<'a>) -> String {
+        value.to_string()
+    }
+
     /// The join statements in the query
     fn visit_joins(&mut self, joins: Vec<Join<'a>>) -> String {
         let result = joins.into_iter().fold(Vec::new(), |mut acc, j| {
@@ -165,29 +171,19 @@ pub trait Visitor<'a> {
 
     /// A walk through an `UPDATE` statement
     fn visit_update(&mut self, update: Update<'a>) -> String {
+        let table = update.table.clone();
+
         let mut result = vec![format!(
             "UPDATE {} SET",
             self.visit_table(update.table, true)
         )];
 
-        {
-            let pairs = update.columns.into_iter().zip(update.values.into_iter());
-
-            let assignments: Vec<String> = pairs
-                .map(|(key, value)| {
-                    format!(
-                        "{} = {}",
-                        self.visit_column(key),
-                        self.visit_database_value(value)
-                    )
-                })
-                .collect();
-
-            result.push(assignments.join(", "));
-        }
+        let pairs = update.columns.into_iter().zip(update.values.into_iter());
+        result.push(self.visit_assignments(pairs.collect()));
 
         if let Some(conditions) = update.conditions {
-            result.push(format!("WHERE {}", self.visit_conditions(conditions)));
+            let conditions = self.visit_mutation_conditions(&table, conditions);
+            result.push(format!("WHERE {}", conditions));
         }
 
         result.join(" ")
@@ -195,18 +191,31 @@ pub trait Visitor<'a> {
 
     /// A walk through an `DELETE` statement
     fn visit_delete(&mut self, delete: Delete<'a>) -> String {
+        let table = delete.table.clone();
+
         let mut result = vec![format!(
             "DELETE FROM {}",
             self.visit_table(delete.table, true)
         )];
 
         if let Some(conditions) = delete.conditions {
-            result.push(format!("WHERE {}", self.visit_conditions(conditions)));
+            let conditions = self.visit_mutation_conditions(&table, conditions);
+            result.push(format!("WHERE {}", conditions));
         }
 
         result.join(" ")
     }
 
+    /// The conditions of an `UPDATE` or a `DELETE` statement modifying the
+    /// given table.
+    fn visit_mutation_conditions(
+        &mut self,
+        _table: &Table<'a>,
+        conditions: ConditionTree<'a>,
+    ) -> String {
+        self.visit_conditions(conditions)
+    }
+
     /// A helper for delimiting an identifier, surrounding every part with `C_BACKTICK`
     /// and delimiting the values with a `.`
     ///
@@ -216,12 +225,16 @@ pub trait Visitor<'a> {
     ///     "`a`.`b`",
     ///     Sqlite::delimited_identifiers(vec!["a".into(), "b".into()])
     /// );
+    ///
+    /// assert_eq!("`a``b`", Sqlite::delimited_identifiers(vec!["a`b"]));
     /// ```
     fn delimited_identifiers(parts: Vec<&str>) -> String {
         let mut result = Vec::new();
 
         for part in parts.into_iter() {
-            result.push(format!("{}{}{}", Self::C_BACKTICK, part, Self::C_BACKTICK));
+            // A quote character in the identifier is escaped by doubling it.
+            let escaped = part.replace(Self::C_BACKTICK, &Self::C_BACKTICK.repeat(2));
+            result.push(format!("{}{}{}", Self::C_BACKTICK, escaped, Self::C_BACKTICK));
         }
 
         result.join(".")
@@ -272,9 +285,70 @@ pub trait Visitor<'a> {
                 Some(table) => format!("{}.*", self.visit_table(*table, false)),
                 None => String::from("*"),
             },
+            DatabaseValue::Case(case) => self.visit_case(*case),
+            DatabaseValue::Excluded(column) => self.visit_excluded(*column),
         }
     }
 
+    /// The value of the column in a conflicting `INSERT`
+    fn visit_excluded(&mut self, column: Column<'a>) -> String {
+        format!(
+            "excluded.{}",
+            Self::delimited_identifiers(vec![&*column.name])
+        )
+    }
+
+    /// Column assignments of an `UPDATE` or an upsert
+    fn visit_assignments(&mut self, assignments: Vec<(Column<'a>, DatabaseValue<'a>)>) -> String {
+        let assignments: Vec<String> = assignments
+            .into_iter()
+            .map(|(key, value)| {
+                format!(
+                    "{} = {}",
+                    self.visit_column(key),
+                    self.visit_database_value(value)
+                )
+            })
+            .collect();
+
+        assignments.join(", ")
+    }
+
+    /// `ON CONFLICT ... DO UPDATE`, as in PostgreSQL and SQLite
+    fn visit_on_conflict_update(
+        &mut self,
+        columns: Vec<Column<'a>>,
+        assignments: Vec<(Column<'a>, DatabaseValue<'a>)>,
+    ) -> String {
+        let columns: Vec<String> = columns.into_iter().map(|c| self.visit_column(c)).collect();
+
+        format!(
+            "ON CONFLICT ({}) DO UPDATE SET {}",
+            columns.join(", "),
+            self.visit_assignments(assignments)
+        )
+    }
+
+    /// A `CASE` expression
+    fn visit_case(&mut self, case: Case<'a>) -> String {
+        let mut result = vec![format!("CASE {}", self.visit_database_value(*case.operand))];
+
+        for (value, then) in case.branches {
+            result.push(format!(
+                "WHEN {} THEN {}",
+                self.visit_database_value(value),
+                self.visit_database_value(then)
+            ));
+        }
+
+        if let Some(otherwise) = case.otherwise {
+            result.push(format!("ELSE {}", self.visit_database_value(*otherwise)));
+        }
+
+        result.push(String::from("END"));
+        result.join(" ")
+    }
+
     /// A database table identifier
     fn visit_table(&mut self, table: Table<'a>, include_alias: bool) -> String {
         let mut result = match table.typ {
@@ -360,16 +434,26 @@ pub trait Visitor<'a> {
     /// A comparison expression
     fn visit_compare(&mut self, compare: Compare<'a>) -> String {
         match compare {
-            Compare::Equals(left, right) => format!(
-                "{} = {}",
-                self.visit_database_value(*left),
-                self.visit_database_value(*right),
-            ),
-            Compare::NotEquals(left, right) => format!(
-                "{} <> {}",
-                self.visit_database_value(*left),
-                self.visit_database_value(*right),
-            ),
+            Compare::Equals(left, right) => match *right {
+                DatabaseValue::Parameterized(ParameterizedValue::Null) => {
+                    format!("{} IS NULL", self.visit_database_value(*left))
+                }
+                right => format!(
+                    "{} = {}",
+                    self.visit_database_value(*left),
+                    self.visit_database_value(right),
+                ),
+            },
+            Compare::NotEquals(left, right) => match *right {
+                DatabaseValue::Parameterized(ParameterizedValue::Null) => {
+                    format!("{} IS NOT NULL", self.visit_database_value(*left))
+                }
+                right => format!(
+                    "{} <> {}",
+                    self.visit_database_value(*left),
+                    self.visit_database_value(right),
+                ),
+            },
             Compare::LessThan(left, right) => format!(
                 "{} < {}",
                 self.visit_database_value(*left),
@@ -501,6 +585,12 @@ pub trait Visitor<'a> {
         result.join(", ")
     }
 
+    /// A random number, an integer on SQLite and between zero and one on
+    /// PostgreSQL and MySQL
+    fn visit_random(&mut self) -> String {
+        String::from("RANDOM()")
+    }
+
     /// A visit in the `GROUP BY` section of the query
     fn visit_grouping(&mut self, grouping: Grouping<'a>) -> String {
         let mut result = Vec::new();
@@ -534,6 +624,7 @@ pub trait Visitor<'a> {
             FunctionType::AggregateToString(agg) => {
                 self.visit_aggregate_to_string(agg.value.as_ref().clone())
             }
+            FunctionType::Random => self.visit_random(),
         };
 
         if let Some(alias) = fun.alias {
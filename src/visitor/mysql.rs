@@ -1,5 +1,6 @@
 use crate::{ast::*, visitor::Visitor};
 use mysql::Value as MyValue;
+use std::mem;
 
 #[cfg(feature = "chrono-0_4")]
 use chrono::{Datelike, Timelike};
@@ -7,6 +8,31 @@ use chrono::{Datelike, Timelike};
 /// A visitor to generate queries for the MySQL database.
 ///
 /// The returned parameter values can be used directly with the mysql crate.
+///
+/// MySQL does not allow a subquery in the conditions of an `UPDATE` or a
+/// `DELETE` to read from the modified table. Such subqueries are wrapped in
+/// a derived table, which MySQL materializes before modifying the rows.
+///
+/// ```rust
+/// # use prisma_query::{ast::*, visitor::{Visitor, Mysql, Postgres}};
+/// let oldest = Select::from_table("users").column("id").order_by("age".descend()).limit(10);
+/// let query = Delete::from_table("users").so_that("id".in_selection(oldest));
+///
+/// let (mysql, _) = Mysql::build(query.clone());
+/// let (postgres, _) = Postgres::build(query);
+///
+/// assert_eq!(
+///     "DELETE FROM `users` WHERE `id` IN (SELECT * FROM \
+///      (SELECT `id` FROM `users` ORDER BY `age` DESC LIMIT ?) AS `_prisma_sub`)",
+///     mysql
+/// );
+///
+/// assert_eq!(
+///     "DELETE FROM \"users\" WHERE \"id\" IN \
+///      (SELECT \"id\" FROM \"users\" ORDER BY \"age\" DESC LIMIT $1)",
+///     postgres
+/// );
+/// ```
 pub struct Mysql<'a> {
     parameters: Vec<ParameterizedValue<'a>>,
 }
@@ -34,7 +60,7 @@ impl<'a> Visitor<'a> for Mysql<'a> {
     fn visit_insert(&mut self, insert: Insert<'a>) -> String {
         let mut result = match insert.on_conflict {
             Some(OnConflict::DoNothing) => vec![String::from("INSERT IGNORE")],
-            None => vec![String::from("INSERT")],
+            _ => vec![String::from("INSERT")],
         };
 
         result.push(format!("INTO {}", self.visit_table(insert.table, true)));
@@ -61,9 +87,23 @@ impl<'a> Visitor<'a> for Mysql<'a> {
             ))
         }
 
+        if let Some(OnConflict::Update(_, assignments)) = insert.on_conflict {
+            result.push(format!(
+                "ON DUPLICATE KEY UPDATE {}",
+                self.visit_assignments(assignments)
+            ));
+        };
+
         result.join(" ")
     }
 
+    fn visit_excluded(&mut self, column: Column<'a>) -> String {
+        format!(
+            "VALUES({})",
+            Self::delimited_identifiers(vec![&*column.name])
+        )
+    }
+
     fn parameter_substitution(&self) -> String {
         String::from("?")
     }
@@ -97,6 +137,151 @@ impl<'a> Visitor<'a> for Mysql<'a> {
     fn visit_aggregate_to_string(&mut self, value: DatabaseValue<'a>) -> String {
         format!("group_concat({})", self.visit_database_value(value))
     }
+
+    fn visit_random(&mut self) -> String {
+        String::from("RAND()")
+    }
+
+    fn visit_mutation_conditions(
+        &mut self,
+        table: &Table<'a>,
+        mut conditions: ConditionTree<'a>,
+    ) -> String {
+        tree_values(&mut conditions, &mut |value| {
+            wrap_self_references(value, table)
+        });
+        self.visit_conditions(conditions)
+    }
+}
+
+/// The alias of the derived table wrapping a subquery on the modified table.
+const SUBQUERY_ALIAS: &str = "_prisma_sub";
+
+/// Replaces a subquery reading from the modified table with `SELECT * FROM
+/// (subquery) AS _prisma_sub`.
+fn wrap_self_references<'a>(value: &mut DatabaseValue<'a>, target: &Table<'a>) {
+    match value {
+        DatabaseValue::Select(select) => {
+            if reads_from(select, target) {
+                let inner = mem::replace(select, Select::default());
+                let derived = Table::from(inner).alias(SUBQUERY_ALIAS);
+
+                *select = Select::from_table(derived).value(asterisk());
+            }
+        }
+        DatabaseValue::Row(row) => {
+            for value in row.values.iter_mut() {
+                wrap_self_references(value, target);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// True if the table, a join or a nested subquery of the `SELECT` is the
+/// target table.
+fn reads_from<'a>(select: &mut Select<'a>, target: &Table<'a>) -> bool {
+    let mut found = match select.table {
+        Some(ref mut table) => table_reads_from(table, target),
+        None => false,
+    };
+
+    for join in select.joins.iter_mut() {
+        let data = match join {
+            Join::Inner(data) | Join::LeftOuter(data) => data,
+        };
+
+        found |= table_reads_from(&mut data.table, target);
+        tree_values(&mut data.conditions, &mut |v| {
+            found |= value_reads_from(v, target)
+        });
+    }
+
+    for value in select.columns.iter_mut() {
+        found |= value_reads_from(value, target);
+    }
+
+    if let Some(ref mut conditions) = select.conditions {
+        tree_values(conditions, &mut |v| found |= value_reads_from(v, target));
+    }
+
+    found
+}
+
+fn table_reads_from<'a>(table: &mut Table<'a>, target: &Table<'a>) -> bool {
+    match (&mut table.typ, &target.typ) {
+        (TableType::Table(name), TableType::Table(target_name)) => {
+            let same_database = match (&table.database, &target.database) {
+                (Some(database), Some(target_database)) => database == target_database,
+                _ => true,
+            };
+
+            name == target_name && same_database
+        }
+        (TableType::Query(select), _) => reads_from(select, target),
+        _ => false,
+    }
+}
+
+fn value_reads_from<'a>(value: &mut DatabaseValue<'a>, target: &Table<'a>) -> bool {
+    match value {
+        DatabaseValue::Select(select) => reads_from(select, target),
+        DatabaseValue::Row(row) => row
+            .values
+            .iter_mut()
+            .any(|value| value_reads_from(value, target)),
+        _ => false,
+    }
+}
+
+/// Calls `f` with every value compared in the conditions.
+fn tree_values<'a>(tree: &mut ConditionTree<'a>, f: &mut dyn FnMut(&mut DatabaseValue<'a>)) {
+    match tree {
+        ConditionTree::And(left, right) | ConditionTree::Or(left, right) => {
+            expression_values(left, f);
+            expression_values(right, f);
+        }
+        ConditionTree::Not(expression) | ConditionTree::Single(expression) => {
+            expression_values(expression, f)
+        }
+        ConditionTree::NoCondition | ConditionTree::NegativeCondition => (),
+    }
+}
+
+fn expression_values<'a>(
+    expression: &mut Expression<'a>,
+    f: &mut dyn FnMut(&mut DatabaseValue<'a>),
+) {
+    match expression {
+        Expression::Value(value) => f(value),
+        Expression::ConditionTree(tree) => tree_values(tree, f),
+        Expression::Compare(compare) => match compare {
+            Compare::Equals(left, right)
+            | Compare::NotEquals(left, right)
+            | Compare::LessThan(left, right)
+            | Compare::LessThanOrEquals(left, right)
+            | Compare::GreaterThan(left, right)
+            | Compare::GreaterThanOrEquals(left, right)
+            | Compare::In(left, right)
+            | Compare::NotIn(left, right) => {
+                f(left);
+                f(right);
+            }
+            Compare::Like(value, _)
+            | Compare::NotLike(value, _)
+            | Compare::BeginsWith(value, _)
+            | Compare::NotBeginsWith(value, _)
+            | Compare::EndsInto(value, _)
+            | Compare::NotEndsInto(value, _)
+            | Compare::Null(value)
+            | Compare::NotNull(value) => f(value),
+            Compare::Between(value, left, right) | Compare::NotBetween(value, left, right) => {
+                f(value);
+                f(left);
+                f(right);
+            }
+        },
+    }
 }
 
 impl<'a> From<ParameterizedValue<'a>> for MyValue {
@@ -108,6 +293,7 @@ impl<'a> From<ParameterizedValue<'a>> for MyValue {
             ParameterizedValue::Text(s) => MyValue::Bytes((&*s).as_bytes().to_vec()),
             ParameterizedValue::Boolean(b) => MyValue::Int(b as i64),
             ParameterizedValue::Char(c) => MyValue::Bytes(vec![c as u8]),
+            ParameterizedValue::Bytes(bytes) => MyValue::Bytes(bytes.into_owned()),
             #[cfg(feature = "json-1")]
             ParameterizedValue::Json(json) => {
                 let s = serde_json::to_string(&json).expect("Cannot convert JSON to String.");
@@ -130,6 +316,102 @@ impl<'a> From<ParameterizedValue<'a>> for MyValue {
                 dt.second() as u8,
                 dt.timestamp_subsec_micros(),
             ),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(date) => MyValue::Date(
+                date.year() as u16,
+                date.month() as u8,
+                date.day() as u8,
+                0,
+                0,
+                0,
+                0,
+            ),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(time) => MyValue::Time(
+                false,
+                0,
+                time.hour() as u8,
+                time.minute() as u8,
+                time.second() as u8,
+                time.nanosecond() / 1_000,
+            ),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(d) => MyValue::Bytes(d.to_string().into_bytes()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{ast::*, visitor::*};
+
+    fn expected_values<'a, T>(
+        sql: &'static str,
+        params: Vec<T>,
+    ) -> (String, Vec<ParameterizedValue<'a>>)
+    where
+        T: Into<ParameterizedValue<'a>>,
+    {
+        (
+            String::from(sql),
+            params.into_iter().map(|p| p.into()).collect(),
+        )
+    }
+
+    fn admins<'a>() -> Select<'a> {
+        let admin_ids = Select::from_table("roles")
+            .column("user_id")
+            .so_that("name".equals("admin"));
+
+        Select::from_table("users")
+            .column("id")
+            .so_that("id".in_selection(admin_ids))
+    }
+
+    #[test]
+    fn test_update_wraps_subqueries_on_the_same_table() {
+        let expected = expected_values(
+            "UPDATE `users` SET `admin` = ? WHERE `id` IN (SELECT * FROM (SELECT `id` FROM `users` \
+             WHERE `id` IN (SELECT `user_id` FROM `roles` WHERE `name` = ?)) AS `_prisma_sub`)",
+            vec![ParameterizedValue::from(true), ParameterizedValue::from("admin")],
+        );
+
+        let query = Update::table("users")
+            .set("admin", true)
+            .so_that("id".in_selection(admins()));
+
+        assert_eq!(expected, Mysql::build(query));
+    }
+
+    #[test]
+    fn test_delete_wraps_subqueries_on_the_same_table() {
+        let expected = expected_values(
+            "DELETE FROM `posts` WHERE (`published` = ? AND `author_id` NOT IN \
+             (SELECT * FROM (SELECT `id` FROM `posts`) AS `_prisma_sub`))",
+            vec![false],
+        );
+
+        let authors = Select::from_table("posts").column("id");
+
+        let conditions = "published"
+            .equals(false)
+            .and("author_id".not_in_selection(authors));
+
+        let query = Delete::from_table("posts").so_that(conditions);
+
+        assert_eq!(expected, Mysql::build(query));
+    }
+
+    #[test]
+    fn test_subqueries_on_other_tables_are_not_wrapped() {
+        let expected = expected_values(
+            "DELETE FROM `roles` WHERE `user_id` IN (SELECT `id` FROM `users`)",
+            Vec::<i64>::new(),
+        );
+
+        let users = Select::from_table("users").column("id");
+        let query = Delete::from_table("roles").so_that("user_id".in_selection(users));
+
+        assert_eq!(expected, Mysql::build(query));
+    }
+}
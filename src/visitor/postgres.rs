@@ -1,5 +1,5 @@
 use crate::{ast::*, visitor::Visitor};
-use postgres::types::{IsNull, Type};
+use postgres::types::{IsNull, Kind, Type};
 use rust_decimal::Decimal;
 use std::{error::Error, str::FromStr};
 use tokio_postgres::types::ToSql;
@@ -40,6 +40,14 @@ impl<'a> Visitor<'a> for Postgres<'a> {
         format!("${}", self.parameters.len())
     }
 
+    /// Bytes are written in the `'\x...'` hex format of `bytea`.
+    fn inline_parameter(value: &ParameterizedValue<'a>) -> String {
+        match value {
+            ParameterizedValue::Bytes(bytes) => format!("'\\x{}'", hex(bytes)),
+            _ => value.to_string(),
+        }
+    }
+
     fn visit_limit_and_offset(
         &mut self,
         limit: Option<ParameterizedValue<'a>>,
@@ -84,14 +92,17 @@ impl<'a> Visitor<'a> for Postgres<'a> {
             ))
         }
 
-        if let Some(OnConflict::DoNothing) = insert.on_conflict {
-            result.push(String::from("ON CONFLICT DO NOTHING"));
+        match insert.on_conflict {
+            Some(OnConflict::DoNothing) => result.push(String::from("ON CONFLICT DO NOTHING")),
+            Some(OnConflict::Update(columns, assignments)) => {
+                result.push(self.visit_on_conflict_update(columns, assignments))
+            }
+            None => (),
         };
 
         if let Some(returning) = insert.returning {
             if !returning.is_empty() {
-                let values = returning.into_iter().map(|r| r.into()).collect();
-                result.push(format!("RETURNING {}", self.visit_columns(values)));
+                result.push(format!("RETURNING {}", self.visit_columns(returning)));
             }
         };
 
@@ -129,6 +140,7 @@ impl<'a> ToSql for ParameterizedValue<'a> {
             ParameterizedValue::Text(string) => string.to_sql(ty, out),
             ParameterizedValue::Boolean(boo) => boo.to_sql(ty, out),
             ParameterizedValue::Char(c) => (*c as i8).to_sql(ty, out),
+            ParameterizedValue::Bytes(bytes) => (&bytes[..]).to_sql(ty, out),
             #[cfg(feature = "array")]
             ParameterizedValue::Array(vec) => vec.to_sql(ty, out),
             #[cfg(feature = "json-1")]
@@ -136,7 +148,16 @@ impl<'a> ToSql for ParameterizedValue<'a> {
             #[cfg(feature = "uuid-0_7")]
             ParameterizedValue::Uuid(value) => value.to_sql(ty, out),
             #[cfg(feature = "chrono-0_4")]
-            ParameterizedValue::DateTime(value) => value.naive_utc().to_sql(ty, out),
+            ParameterizedValue::DateTime(value) => match *ty {
+                Type::TIMESTAMPTZ => value.to_sql(ty, out),
+                _ => value.naive_utc().to_sql(ty, out),
+            },
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(value) => value.to_sql(ty, out),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(value) => value.to_sql(ty, out),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(value) => value.to_sql(ty, out),
         }
     }
 
@@ -163,9 +184,15 @@ impl<'a> ToSql for ParameterizedValue<'a> {
                 }
                 _ => float.to_sql(ty, out),
             },
-            ParameterizedValue::Text(string) => string.to_sql_checked(ty, out),
+            // The driver only knows the built-in text types. An enum value is
+            // sent as its label.
+            ParameterizedValue::Text(string) => match ty.kind() {
+                Kind::Enum(_) => string.to_sql(ty, out),
+                _ => string.to_sql_checked(ty, out),
+            },
             ParameterizedValue::Boolean(boo) => boo.to_sql_checked(ty, out),
             ParameterizedValue::Char(c) => (*c as i8).to_sql_checked(ty, out),
+            ParameterizedValue::Bytes(bytes) => (&bytes[..]).to_sql_checked(ty, out),
             #[cfg(feature = "array")]
             ParameterizedValue::Array(vec) => vec.to_sql_checked(ty, out),
             #[cfg(feature = "json-1")]
@@ -173,7 +200,70 @@ impl<'a> ToSql for ParameterizedValue<'a> {
             #[cfg(feature = "uuid-0_7")]
             ParameterizedValue::Uuid(value) => value.to_sql_checked(ty, out),
             #[cfg(feature = "chrono-0_4")]
-            ParameterizedValue::DateTime(value) => value.naive_utc().to_sql_checked(ty, out),
+            ParameterizedValue::DateTime(value) => match *ty {
+                Type::TIMESTAMPTZ => value.to_sql_checked(ty, out),
+                _ => value.naive_utc().to_sql_checked(ty, out),
+            },
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(value) => value.to_sql_checked(ty, out),
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(value) => value.to_sql_checked(ty, out),
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(value) => value.to_sql_checked(ty, out),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::visitor::*;
+
+    #[test]
+    fn bytes_are_inlined_in_the_bytea_hex_format() {
+        let bytes = ParameterizedValue::from(&[0u8, 159, 255][..]);
+
+        assert_eq!("'\\x009fff'", Postgres::inline_parameter(&bytes));
+        assert_eq!(
+            "1",
+            Postgres::inline_parameter(&ParameterizedValue::from(1))
+        );
+    }
+
+    #[test]
+    fn test_subqueries_on_the_modified_table_are_not_wrapped() {
+        let admins = || {
+            Select::from_table("users")
+                .column("id")
+                .so_that("admin".equals(true))
+        };
+
+        let update = Update::table("users")
+            .set("banned", false)
+            .so_that("id".in_selection(admins()));
+
+        let (sql, params) = Postgres::build(update);
+
+        assert_eq!(
+            "UPDATE \"users\" SET \"banned\" = $1 WHERE \"id\" IN \
+             (SELECT \"id\" FROM \"users\" WHERE \"admin\" = $2)",
+            sql
+        );
+
+        assert_eq!(
+            vec![
+                ParameterizedValue::from(false),
+                ParameterizedValue::from(true)
+            ],
+            params
+        );
+
+        let delete = Delete::from_table("users").so_that("id".not_in_selection(admins()));
+        let (sql, _) = Postgres::build(delete);
+
+        assert_eq!(
+            "DELETE FROM \"users\" WHERE \"id\" NOT IN \
+             (SELECT \"id\" FROM \"users\" WHERE \"admin\" = $1)",
+            sql
+        );
+    }
+}
@@ -36,7 +36,7 @@ impl<'a> Visitor<'a> for Sqlite<'a> {
     fn visit_insert(&mut self, insert: Insert<'a>) -> String {
         let mut result = match insert.on_conflict {
             Some(OnConflict::DoNothing) => vec![String::from("INSERT OR IGNORE")],
-            None => vec![String::from("INSERT")],
+            _ => vec![String::from("INSERT")],
         };
 
         result.push(format!("INTO {}", self.visit_table(insert.table, true)));
@@ -63,6 +63,13 @@ impl<'a> Visitor<'a> for Sqlite<'a> {
             ))
         }
 
+        if let Some(OnConflict::Update(columns, assignments)) = insert.on_conflict {
+            result.push(self.visit_on_conflict_update(columns, assignments));
+        };
+
+        // The bundled SQLite predates `RETURNING`, so the returned columns
+        // are left out.
+
         result.join(" ")
     }
 
@@ -109,6 +116,7 @@ impl<'a> ToSql for ParameterizedValue<'a> {
             ParameterizedValue::Text(cow) => ToSqlOutput::from(&**cow),
             ParameterizedValue::Boolean(boo) => ToSqlOutput::from(*boo),
             ParameterizedValue::Char(c) => ToSqlOutput::from(*c as u8),
+            ParameterizedValue::Bytes(bytes) => ToSqlOutput::from(&bytes[..]),
             #[cfg(feature = "array")]
             ParameterizedValue::Array(_) => unimplemented!("Arrays are not supported for sqlite."),
             #[cfg(feature = "json-1")]
@@ -120,7 +128,19 @@ impl<'a> ToSql for ParameterizedValue<'a> {
             #[cfg(feature = "uuid-0_7")]
             ParameterizedValue::Uuid(value) => ToSqlOutput::from(value.to_hyphenated().to_string()),
             #[cfg(feature = "chrono-0_4")]
-            ParameterizedValue::DateTime(value) => ToSqlOutput::from(value.timestamp_millis()),
+            ParameterizedValue::DateTime(value) => {
+                ToSqlOutput::from(value.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            }
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Date(date) => {
+                ToSqlOutput::from(date.format("%Y-%m-%d").to_string())
+            }
+            #[cfg(feature = "chrono-0_4")]
+            ParameterizedValue::Time(time) => {
+                ToSqlOutput::from(time.format("%H:%M:%S%.f").to_string())
+            }
+            #[cfg(feature = "rust_decimal-1")]
+            ParameterizedValue::Decimal(value) => ToSqlOutput::from(value.to_string()),
         };
 
         Ok(value)
@@ -216,6 +236,22 @@ mod tests {
         assert_eq!(default_params(expected.1), params);
     }
 
+    #[test]
+    fn test_select_where_equals_none() {
+        let expected_sql =
+            "SELECT `naukio`.* FROM `naukio` WHERE (`word` IS NULL AND `age` IS NOT NULL)";
+
+        let conditions = "word"
+            .equals(None::<&str>)
+            .and("age".not_equals(None::<i64>));
+
+        let query = Select::from_table("naukio").so_that(conditions);
+        let (sql, params) = Sqlite::build(query);
+
+        assert_eq!(expected_sql, sql);
+        assert!(params.is_empty());
+    }
+
     #[test]
     fn test_select_where_like() {
         let expected = expected_values(
@@ -324,6 +360,23 @@ mod tests {
         assert_eq!(default_params(expected_params), params);
     }
 
+    #[cfg(feature = "chrono-0_4")]
+    #[test]
+    fn test_select_where_less_than_a_date_time() {
+        use chrono::{DateTime, Utc};
+
+        let now: DateTime<Utc> = Utc::now();
+        let query = Select::from_table("naukio").so_that("created_at".less_than(now));
+
+        let (sql, params) = Sqlite::build(query);
+
+        assert_eq!(
+            "SELECT `naukio`.* FROM `naukio` WHERE `created_at` < ?",
+            sql
+        );
+        assert_eq!(vec![ParameterizedValue::DateTime(now)], params);
+    }
+
     #[test]
     fn test_select_and_different_execution_order() {
         let expected_sql =
@@ -494,6 +547,63 @@ mod tests {
         assert_eq!(expected_sql, sql);
     }
 
+    #[test]
+    fn test_insert_on_conflict_update() {
+        let expected = expected_values(
+            "INSERT INTO `cats` (`id`, `name`) VALUES (?, ?) \
+             ON CONFLICT (`id`) DO UPDATE SET `name` = excluded.`name`, `age` = ?",
+            vec![ParameterizedValue::from(1), "musti".into(), 2.into()],
+        );
+
+        let insert: Insert = Insert::single_into("cats")
+            .value("id", 1)
+            .value("name", "musti")
+            .into();
+
+        let conflict = OnConflict::Update(
+            vec![Column::from("id")],
+            vec![
+                (Column::from("name"), excluded("name")),
+                (Column::from("age"), DatabaseValue::from(2)),
+            ],
+        );
+
+        let (sql, params) = Sqlite::build(insert.on_conflict(conflict));
+
+        assert_eq!(expected.0, sql);
+        assert_eq!(expected.1, params);
+    }
+
+    #[test]
+    fn test_subqueries_on_the_modified_table_are_not_wrapped() {
+        let admins = || {
+            Select::from_table("users")
+                .column("id")
+                .so_that("admin".equals(true))
+        };
+
+        let update = Update::table("users")
+            .set("banned", false)
+            .so_that("id".in_selection(admins()));
+
+        let (sql, _) = Sqlite::build(update);
+
+        assert_eq!(
+            "UPDATE `users` SET `banned` = ? WHERE `id` IN \
+             (SELECT `id` FROM `users` WHERE `admin` = ?)",
+            sql
+        );
+
+        let delete = Delete::from_table("users").so_that("id".not_in_selection(admins()));
+        let (sql, _) = Sqlite::build(delete);
+
+        assert_eq!(
+            "DELETE FROM `users` WHERE `id` NOT IN \
+             (SELECT `id` FROM `users` WHERE `admin` = ?)",
+            sql
+        );
+    }
+
     fn sqlite_harness() -> ::rusqlite::Connection {
         let conn = ::rusqlite::Connection::open_in_memory().unwrap();
 